@@ -0,0 +1,85 @@
+use tokio_dep::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_dep::runtime::Handle;
+
+use std::future::Future;
+
+use crate::ReadWriteTokio;
+
+impl<R: AsyncRead + Unpin, W: AsyncWrite + Unpin> ReadWriteTokio<R, W> {
+    /// Turn this async pseudo-socket into a blocking `Read + Write` one,
+    /// performing each operation with `handle.block_on`.
+    ///
+    /// The returned object must not be used from a thread that is a runtime worker,
+    /// unless `ReadWriteTokioBlocking::set_block_in_place` is turned on.
+    pub fn into_blocking(self, handle: Handle) -> ReadWriteTokioBlocking<R, W> {
+        ReadWriteTokioBlocking {
+            inner: self,
+            handle,
+            block_in_place: false,
+        }
+    }
+}
+
+/// Blocking `Read + Write` adapter over `ReadWriteTokio`, similar to `tokio_util::io::SyncIoBridge`.
+/// Created by `ReadWriteTokio::into_blocking`.
+/// Note that this struct is only present in `readwrite` if "blocking" Cargo feature is enabled.
+pub struct ReadWriteTokioBlocking<R, W> {
+    inner: ReadWriteTokio<R, W>,
+    handle: Handle,
+    block_in_place: bool,
+}
+
+fn block_on<F: Future>(handle: &Handle, block_in_place: bool, f: F) -> F::Output {
+    if block_in_place {
+        tokio_dep::task::block_in_place(|| handle.block_on(f))
+    } else {
+        handle.block_on(f)
+    }
+}
+
+impl<R: AsyncRead + Unpin, W: AsyncWrite + Unpin> ReadWriteTokioBlocking<R, W> {
+    /// Wrap each blocking operation in `tokio::task::block_in_place`.
+    /// This allows using the adapter from within a worker thread of a multi-threaded runtime
+    /// (but panics on a current-thread runtime).
+    pub fn set_block_in_place(&mut self, enabled: bool) {
+        self.block_in_place = enabled;
+    }
+    /// Shut down the writer, mapping to `AsyncWrite::poll_shutdown`
+    pub fn shutdown(&mut self) -> std::io::Result<()> {
+        block_on(&self.handle, self.block_in_place, self.inner.shutdown())
+    }
+    /// Borrow the async pseudo-socket
+    pub fn get_ref(&self) -> &ReadWriteTokio<R, W> {
+        &self.inner
+    }
+    /// Mutably borrow the async pseudo-socket
+    pub fn get_mut(&mut self) -> &mut ReadWriteTokio<R, W> {
+        &mut self.inner
+    }
+    /// Convert back into the async pseudo-socket
+    pub fn into_inner(self) -> ReadWriteTokio<R, W> {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin, W: AsyncWrite + Unpin> std::io::Read for ReadWriteTokioBlocking<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        block_on(&self.handle, self.block_in_place, self.inner.read(buf))
+    }
+}
+
+impl<R: AsyncRead + Unpin, W: AsyncWrite + Unpin> std::io::Write for ReadWriteTokioBlocking<R, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        block_on(&self.handle, self.block_in_place, self.inner.write(buf))
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        block_on(&self.handle, self.block_in_place, self.inner.flush())
+    }
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        block_on(
+            &self.handle,
+            self.block_in_place,
+            self.inner.write_vectored(bufs),
+        )
+    }
+}
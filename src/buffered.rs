@@ -0,0 +1,219 @@
+use std::io::{BufReader, BufWriter, Error, Read, Write};
+
+use crate::ReadWrite;
+
+/// Error returned by `unbuffer` when flushing the write buffer fails.
+/// Like `std::io::IntoInnerError`, it gives back the still buffered pseudo-socket, so no data is lost.
+pub struct UnbufferError<T> {
+    error: Error,
+    buffered: T,
+}
+
+impl<T> UnbufferError<T> {
+    /// The error from flushing
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+    /// Take the error, dropping the buffered pseudo-socket
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+    /// Take back the buffered pseudo-socket, dropping the error
+    pub fn into_inner(self) -> T {
+        self.buffered
+    }
+    /// Take both the error and the buffered pseudo-socket
+    pub fn into_parts(self) -> (Error, T) {
+        (self.error, self.buffered)
+    }
+}
+
+// The pseudo-socket is usually not `Debug`, so only the error is shown
+impl<T> std::fmt::Debug for UnbufferError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnbufferError")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> std::fmt::Display for UnbufferError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("failed to flush the write buffer while unbuffering")
+    }
+}
+
+impl<T> std::error::Error for UnbufferError<T> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<T> From<UnbufferError<T>> for Error {
+    fn from(e: UnbufferError<T>) -> Self {
+        e.error
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Wrap the reader in a `std::io::BufReader`, making the pseudo-socket `BufRead`
+    pub fn buffered(self) -> ReadWrite<BufReader<R>, W> {
+        self.map_read(BufReader::new)
+    }
+    /// Wrap the reader in a `std::io::BufReader` with the specified buffer capacity
+    pub fn buffered_with_capacity(self, capacity: usize) -> ReadWrite<BufReader<R>, W> {
+        self.map_read(|r| BufReader::with_capacity(capacity, r))
+    }
+    /// Wrap the reader in a `std::io::BufReader` and the writer in a `std::io::BufWriter`.
+    /// The pseudo-socket becomes `BufRead`, and small writes are coalesced until flushed.
+    pub fn buffered_both(self) -> ReadWrite<BufReader<R>, BufWriter<W>> {
+        self.map_both(BufReader::new, BufWriter::new)
+    }
+    /// Wrap both halves in buffers of the specified capacities
+    pub fn buffered_both_with_capacity(
+        self,
+        read_capacity: usize,
+        write_capacity: usize,
+    ) -> ReadWrite<BufReader<R>, BufWriter<W>> {
+        self.map_both(
+            |r| BufReader::with_capacity(read_capacity, r),
+            |w| BufWriter::with_capacity(write_capacity, w),
+        )
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<BufReader<R>, BufWriter<W>> {
+    /// Flush the write buffer and remove both buffering layers.
+    ///
+    /// Data already read into the read buffer, but not consumed yet, is discarded.
+    /// Check `borrow_read().buffer()` beforehand if it matters.
+    /// If flushing fails, the still buffered pseudo-socket is returned inside the error.
+    pub fn unbuffer(mut self) -> Result<ReadWrite<R, W>, UnbufferError<Self>> {
+        if let Err(error) = self.1.flush() {
+            return Err(UnbufferError {
+                error,
+                buffered: self,
+            });
+        }
+        let (r, w) = self.into_inner();
+        // The write buffer is empty after flushing
+        Ok(ReadWrite::new(r.into_inner(), w.into_parts().0))
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<BufReader<R>, W> {
+    /// Remove the read buffer, returning the raw reader, the buffered but not yet consumed bytes, and the writer.
+    /// Unlike `into_inner`, no read data is lost.
+    pub fn into_parts(self) -> (R, Vec<u8>, W) {
+        let (r, w) = self.into_inner();
+        let buffered = r.buffer().to_vec();
+        (r.into_inner(), buffered, w)
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+
+    use super::UnbufferError;
+    use crate::ReadWriteTokio;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Wrap the reader in a `tokio::io::BufReader` and the writer in a `tokio::io::BufWriter`.
+        /// Reader teardown or shutdown notification set up for the old reader is discarded.
+        pub fn buffered_both(self) -> ReadWriteTokio<BufReader<R>, BufWriter<W>> {
+            self.map_both(BufReader::new, BufWriter::new)
+        }
+        /// Wrap both halves in buffers of the specified capacities
+        pub fn buffered_both_with_capacity(
+            self,
+            read_capacity: usize,
+            write_capacity: usize,
+        ) -> ReadWriteTokio<BufReader<R>, BufWriter<W>> {
+            self.map_both(
+                |r| BufReader::with_capacity(read_capacity, r),
+                |w| BufWriter::with_capacity(write_capacity, w),
+            )
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite + Unpin> ReadWriteTokio<BufReader<R>, BufWriter<W>> {
+        /// Flush the write buffer and remove both buffering layers.
+        ///
+        /// Data already read into the read buffer, but not consumed yet, is discarded.
+        /// Check `borrow_read().buffer()` beforehand if it matters.
+        /// If flushing fails, the still buffered pseudo-socket is returned inside the error.
+        pub async fn unbuffer(mut self) -> Result<ReadWriteTokio<R, W>, UnbufferError<Self>> {
+            if let Err(error) = self.borrow_mut_write().flush().await {
+                return Err(UnbufferError {
+                    error,
+                    buffered: self,
+                });
+            }
+            Ok(self.map_both(BufReader::into_inner, BufWriter::into_inner))
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<BufReader<R>, W> {
+        /// Remove the read buffer, returning the raw reader, the buffered but not yet consumed bytes, and the writer.
+        /// Unlike `into_inner`, no read data is lost.
+        pub fn into_parts(self) -> (R, Vec<u8>, W) {
+            let (r, w) = self.into_inner();
+            let buffered = r.buffer().to_vec();
+            (r.into_inner(), buffered, w)
+        }
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+
+    use super::UnbufferError;
+    use crate::ReadWriteAsyncstd;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstd<R, W> {
+        /// Wrap the reader in a `futures::io::BufReader` and the writer in a `futures::io::BufWriter`
+        pub fn buffered_both(self) -> ReadWriteAsyncstd<BufReader<R>, BufWriter<W>> {
+            self.map_both(BufReader::new, BufWriter::new)
+        }
+        /// Wrap both halves in buffers of the specified capacities
+        pub fn buffered_both_with_capacity(
+            self,
+            read_capacity: usize,
+            write_capacity: usize,
+        ) -> ReadWriteAsyncstd<BufReader<R>, BufWriter<W>> {
+            self.map_both(
+                |r| BufReader::with_capacity(read_capacity, r),
+                |w| BufWriter::with_capacity(write_capacity, w),
+            )
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite + Unpin> ReadWriteAsyncstd<BufReader<R>, BufWriter<W>> {
+        /// Flush the write buffer and remove both buffering layers.
+        ///
+        /// Data already read into the read buffer, but not consumed yet, is discarded.
+        /// Check `borrow_read().buffer()` beforehand if it matters.
+        /// If flushing fails, the still buffered pseudo-socket is returned inside the error.
+        pub async fn unbuffer(mut self) -> Result<ReadWriteAsyncstd<R, W>, UnbufferError<Self>> {
+            if let Err(error) = self.borrow_mut_write().flush().await {
+                return Err(UnbufferError {
+                    error,
+                    buffered: self,
+                });
+            }
+            Ok(self.map_both(BufReader::into_inner, BufWriter::into_inner))
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstd<BufReader<R>, W> {
+        /// Remove the read buffer, returning the raw reader, the buffered but not yet consumed bytes, and the writer.
+        /// Unlike `into_inner`, no read data is lost.
+        pub fn into_parts(self) -> (R, Vec<u8>, W) {
+            let (r, w) = self.into_inner();
+            let buffered = r.buffer().to_vec();
+            (r.into_inner(), buffered, w)
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::io::{ErrorKind, Read, Result, Write};
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use crate::ReadWrite;
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Write all remaining data of a `Buf`, e.g. `Bytes`, advancing it.
+    /// On error, `buf` is left at the first byte that was not written.
+    pub fn write_bytes(&mut self, buf: &mut impl Buf) -> Result<()> {
+        while buf.has_remaining() {
+            match self.1.write(buf.chunk()) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(k) => buf.advance(k),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+    /// Read `n` bytes into a fresh `BytesMut`, retrying short reads.
+    /// Fewer bytes are returned only if the reader reaches EOF first.
+    ///
+    /// The buffer grows as data arrives instead of being allocated for `n` bytes upfront.
+    pub fn read_bytes(&mut self, n: usize) -> Result<BytesMut> {
+        let mut buf = BytesMut::new().writer();
+        std::io::copy(&mut (&mut self.0).take(n as u64), &mut buf)?;
+        Ok(buf.into_inner())
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use bytes::{Buf, BufMut, BytesMut};
+    use tokio_dep::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+    use crate::ReadWriteTokio;
+
+    /// How much `read_bytes` reserves at a time, so that a large `n` (e.g. a length prefix sent by the peer)
+    /// does not allocate memory before the data actually arrives
+    const READ_CHUNK: usize = 8 * 1024;
+
+    impl<R: AsyncRead + Unpin, W: AsyncWrite + Unpin> ReadWriteTokio<R, W> {
+        /// Write all remaining data of a `Buf`, e.g. `Bytes`, advancing it
+        pub async fn write_bytes(&mut self, buf: &mut impl Buf) -> std::io::Result<()> {
+            self.borrow_mut_write().write_all_buf(buf).await
+        }
+        /// Read `n` bytes into a fresh `BytesMut`, retrying short reads.
+        /// Fewer bytes are returned only if the reader reaches EOF first.
+        ///
+        /// The buffer grows as data arrives instead of being allocated for `n` bytes upfront.
+        pub async fn read_bytes(&mut self, n: usize) -> std::io::Result<BytesMut> {
+            let mut buf = BytesMut::new();
+            while buf.len() < n {
+                let remaining = n - buf.len();
+                buf.reserve(remaining.min(READ_CHUNK));
+                if self.read_buf(&mut (&mut buf).limit(remaining)).await? == 0 {
+                    break;
+                }
+            }
+            Ok(buf)
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::future::Future;
+use std::io::ErrorKind;
+use std::pin::Pin;
+use std::task::Context;
+
+/// Async wrapper which stops I/O once a cancellation signal fires, e.g. for graceful shutdown.
+/// Created by `with_cancellation` methods or `Cancellable::new`.
+///
+/// The signal is polled alongside the inner I/O, so a read parked waiting for a silent peer
+/// is woken up when it fires. After that, reads report EOF (or the error set with `with_read_error`)
+/// and writes fail with `ErrorKind::ConnectionAborted`, without touching the inner object.
+/// Flushing and shutting down are always forwarded.
+pub struct Cancellable<T, S> {
+    inner: T,
+    signal: Option<Pin<Box<S>>>,
+    read_error: Option<ErrorKind>,
+}
+
+impl<T, S: Future<Output = ()>> Cancellable<T, S> {
+    /// Wrap `inner`, cancelling it when `signal` completes
+    pub fn new(inner: T, signal: S) -> Self {
+        Cancellable {
+            inner,
+            signal: Some(Box::pin(signal)),
+            read_error: None,
+        }
+    }
+    /// Make reads fail with the given error kind after cancellation instead of reporting EOF,
+    /// e.g. `ErrorKind::Interrupted`
+    pub fn with_read_error(mut self, kind: ErrorKind) -> Self {
+        self.read_error = Some(kind);
+        self
+    }
+    /// Whether the signal has fired, as far as observed by I/O so far
+    pub fn is_cancelled(&self) -> bool {
+        self.signal.is_none()
+    }
+    /// Borrow the inner object
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+    /// Mutably borrow the inner object. I/O through it is not affected by cancellation.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+    /// Return the inner object, dropping the signal
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Poll the signal, registering for wakeup. Returns whether it has fired.
+    fn poll_cancelled(&mut self, cx: &mut Context<'_>) -> bool {
+        let Some(signal) = self.signal.as_mut() else {
+            return true;
+        };
+        if signal.as_mut().poll(cx).is_pending() {
+            return false;
+        }
+        self.signal = None;
+        true
+    }
+
+    /// Outcome of a read after cancellation: `Ok(())` for EOF
+    fn cancelled_read(&self) -> std::io::Result<()> {
+        match self.read_error {
+            None => Ok(()),
+            Some(kind) => Err(std::io::Error::new(kind, "pseudo-socket was cancelled")),
+        }
+    }
+}
+
+fn cancelled_write() -> std::io::Error {
+    std::io::Error::new(ErrorKind::ConnectionAborted, "pseudo-socket was cancelled")
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+    #[cfg(feature = "cancellation-token")]
+    use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{cancelled_write, Cancellable};
+    use crate::ReadWriteTokio;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Stop I/O once `signal` completes, see `Cancellable`
+        pub fn with_cancellation<S: Future<Output = ()>>(self, signal: S) -> Cancellable<Self, S> {
+            Cancellable::new(self, signal)
+        }
+        /// Stop I/O once `token` is cancelled, see `Cancellable`.
+        /// Note that this method is only present in `readwrite` if "cancellation-token" Cargo feature is enabled.
+        #[cfg(feature = "cancellation-token")]
+        pub fn with_cancellation_token(
+            self,
+            token: CancellationToken,
+        ) -> Cancellable<Self, WaitForCancellationFutureOwned> {
+            Cancellable::new(self, token.cancelled_owned())
+        }
+    }
+
+    impl<T: AsyncRead + Unpin, S: Future<Output = ()>> AsyncRead for Cancellable<T, S> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            if this.poll_cancelled(cx) {
+                return Poll::Ready(this.cancelled_read());
+            }
+            Pin::new(&mut this.inner).poll_read(cx, buf)
+        }
+    }
+
+    impl<T: AsyncWrite + Unpin, S: Future<Output = ()>> AsyncWrite for Cancellable<T, S> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            if this.poll_cancelled(cx) {
+                return Poll::Ready(Err(cancelled_write()));
+            }
+            Pin::new(&mut this.inner).poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[std::io::IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            if this.poll_cancelled(cx) {
+                return Poll::Ready(Err(cancelled_write()));
+            }
+            Pin::new(&mut this.inner).poll_write_vectored(cx, bufs)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite};
+
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{cancelled_write, Cancellable};
+    use crate::ReadWriteAsyncstd;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstd<R, W> {
+        /// Stop I/O once `signal` completes, see `Cancellable`
+        pub fn with_cancellation<S: Future<Output = ()>>(self, signal: S) -> Cancellable<Self, S> {
+            Cancellable::new(self, signal)
+        }
+    }
+
+    impl<T: AsyncRead + Unpin, S: Future<Output = ()>> AsyncRead for Cancellable<T, S> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            if this.poll_cancelled(cx) {
+                return Poll::Ready(this.cancelled_read().map(|()| 0));
+            }
+            Pin::new(&mut this.inner).poll_read(cx, buf)
+        }
+    }
+
+    impl<T: AsyncWrite + Unpin, S: Future<Output = ()>> AsyncWrite for Cancellable<T, S> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            if this.poll_cancelled(cx) {
+                return Poll::Ready(Err(cancelled_write()));
+            }
+            Pin::new(&mut this.inner).poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_close(cx)
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[std::io::IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            if this.poll_cancelled(cx) {
+                return Poll::Ready(Err(cancelled_write()));
+            }
+            Pin::new(&mut this.inner).poll_write_vectored(cx, bufs)
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use compio_buf::{BufResult, IoBuf, IoBufMut};
+use compio_io::{AsyncRead, AsyncWrite};
+
+/// Combined async reader and writer, `compio` version.
+/// Note that this struct is only present in `readwrite` if "compio" Cargo feature is enabled.
+///
+/// Unlike poll-based runtimes, `compio` traits take ownership of buffers, so each operation
+/// is simply forwarded to the respective half.
+pub struct ReadWriteCompio<R, W> {
+    r: R,
+    w: W,
+}
+
+impl<R: AsyncRead, W: AsyncWrite> From<(R, W)> for ReadWriteCompio<R, W> {
+    fn from((r, w): (R, W)) -> Self {
+        ReadWriteCompio { r, w }
+    }
+}
+impl<R: AsyncRead, W: AsyncWrite> ReadWriteCompio<R, W> {
+    /// Bundle separate async reader and writer into a combined pseudo-socket
+    pub fn new(r: R, w: W) -> Self {
+        ReadWriteCompio { r, w }
+    }
+    /// Borrow inner objects
+    pub fn borrow(&self) -> (&R, &W) {
+        (&self.r, &self.w)
+    }
+    /// Borrow the reader
+    pub fn borrow_read(&self) -> &R {
+        &self.r
+    }
+    /// Borrow the writer
+    pub fn borrow_write(&self) -> &W {
+        &self.w
+    }
+    /// Mutably borrow inner objects
+    pub fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+        (&mut self.r, &mut self.w)
+    }
+    /// Mutably borrow the reader
+    pub fn borrow_mut_read(&mut self) -> &mut R {
+        &mut self.r
+    }
+    /// Mutably borrow the writer
+    pub fn borrow_mut_write(&mut self) -> &mut W {
+        &mut self.w
+    }
+    /// Convert ReadWrite back into individual reader and writer pair
+    pub fn into_inner(self) -> (R, W) {
+        (self.r, self.w)
+    }
+    /// Convert ReadWrite back into the reader, dropping the writer
+    pub fn into_reader(self) -> R {
+        self.r
+    }
+    /// Convert ReadWrite back into the writer, dropping the reader
+    pub fn into_writer(self) -> W {
+        self.w
+    }
+}
+
+impl<R: AsyncRead, W: AsyncWrite> crate::Pair for ReadWriteCompio<R, W> {
+    type Reader = R;
+    type Writer = W;
+    fn new(r: R, w: W) -> Self {
+        ReadWriteCompio::new(r, w)
+    }
+    fn borrow(&self) -> (&R, &W) {
+        ReadWriteCompio::borrow(self)
+    }
+    fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+        ReadWriteCompio::borrow_mut(self)
+    }
+    fn into_inner(self) -> (R, W) {
+        ReadWriteCompio::into_inner(self)
+    }
+}
+
+impl<R: AsyncRead, W> AsyncRead for ReadWriteCompio<R, W> {
+    async fn read<B: IoBufMut>(&mut self, buf: B) -> BufResult<usize, B> {
+        self.r.read(buf).await
+    }
+}
+
+impl<R, W: AsyncWrite> AsyncWrite for ReadWriteCompio<R, W> {
+    async fn write<T: IoBuf>(&mut self, buf: T) -> BufResult<usize, T> {
+        self.w.write(buf).await
+    }
+
+    async fn flush(&mut self) -> std::io::Result<()> {
+        self.w.flush().await
+    }
+
+    async fn shutdown(&mut self) -> std::io::Result<()> {
+        self.w.shutdown().await
+    }
+}
@@ -0,0 +1,239 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+
+use crate::{ReadWrite, ShutdownWrite};
+
+const COPY_CHUNK: usize = 8192;
+
+/// Error from `copy_bidirectional` functions, with amounts of data copied in each direction before the failure.
+///
+/// It is returned wrapped in `std::io::Error` of the same kind as the original error;
+/// use `std::io::Error::get_ref` and `downcast_ref` to access the counters.
+#[derive(Debug)]
+pub struct CopyBidirectionalError {
+    /// Bytes copied from the first endpoint to the second one
+    pub a_to_b: u64,
+    /// Bytes copied from the second endpoint to the first one
+    pub b_to_a: u64,
+    /// The error that aborted copying
+    pub error: Error,
+}
+
+impl std::fmt::Display for CopyBidirectionalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (after copying {} bytes a->b and {} bytes b->a)",
+            self.error, self.a_to_b, self.b_to_a
+        )
+    }
+}
+
+impl std::error::Error for CopyBidirectionalError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<CopyBidirectionalError> for Error {
+    fn from(e: CopyBidirectionalError) -> Self {
+        Error::new(e.error.kind(), e)
+    }
+}
+
+fn copy_counting<R: Read, W: Write>(mut r: R, mut w: W, copied: &AtomicU64) -> Result<()> {
+    let mut buf = [0u8; COPY_CHUNK];
+    loop {
+        let n = match r.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let mut chunk = &buf[..n];
+        while !chunk.is_empty() {
+            match w.write(chunk) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    chunk = &chunk[n..];
+                    copied.fetch_add(n as u64, Ordering::Relaxed);
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    w.flush()
+}
+
+/// Copy one direction, then signal EOF to the receiving side.
+/// On error, shut the receiving connection down completely, which also unblocks the opposite direction
+/// if it is reading from the same connection.
+fn copy_direction<R: Read, W: ShutdownWrite>(r: R, mut w: W, copied: &AtomicU64) -> Result<()> {
+    let result = copy_counting(r, &mut w, copied).and_then(|()| w.shutdown_write());
+    if result.is_err() {
+        let _ = w.shutdown_both();
+    }
+    result
+}
+
+/// Pump data between two pseudo-sockets in both directions until both readers reach EOF.
+///
+/// Each direction runs in its own thread. When one reader reaches EOF,
+/// the writer on the opposite side is flushed and shut down with `ShutdownWrite` to signal EOF to its peer,
+/// while the other direction continues.
+///
+/// Returns numbers of bytes copied from `a` to `b` and from `b` to `a`.
+/// The first error in either direction is returned as `CopyBidirectionalError` wrapped in `std::io::Error`.
+/// Blocking I/O can't be interrupted in general, so to stop the other direction the failed one
+/// shuts down its writer's connection with `ShutdownWrite::shutdown_both`.
+/// This function returns only after both threads have finished, so with writers that
+/// do not share a connection with the opposite reader it may wait until that reader reaches EOF.
+pub fn copy_bidirectional<R1, W1, R2, W2>(
+    a: ReadWrite<R1, W1>,
+    b: ReadWrite<R2, W2>,
+) -> Result<(u64, u64)>
+where
+    R1: Read + Send,
+    W1: ShutdownWrite + Send,
+    R2: Read + Send,
+    W2: ShutdownWrite + Send,
+{
+    let (ar, aw) = a.into_inner();
+    let (br, bw) = b.into_inner();
+    let a_to_b = AtomicU64::new(0);
+    let b_to_a = AtomicU64::new(0);
+    let (tx, rx) = mpsc::channel();
+    let first_error = std::thread::scope(|s| {
+        {
+            let tx = tx.clone();
+            let copied = &a_to_b;
+            s.spawn(move || tx.send(copy_direction(ar, bw, copied)));
+        }
+        {
+            let copied = &b_to_a;
+            s.spawn(move || tx.send(copy_direction(br, aw, copied)));
+        }
+        let mut first_error = None;
+        for _ in 0..2 {
+            let result = rx
+                .recv()
+                .unwrap_or_else(|_| Err(Error::other("copy_bidirectional thread panicked")));
+            if let Err(e) = result {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error
+    });
+    let (a_to_b, b_to_a) = (a_to_b.into_inner(), b_to_a.into_inner());
+    match first_error {
+        None => Ok((a_to_b, b_to_a)),
+        Some(error) => Err(CopyBidirectionalError {
+            a_to_b,
+            b_to_a,
+            error,
+        }
+        .into()),
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use std::future::{poll_fn, Future};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::task::Poll;
+
+    use tokio_dep::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+    use super::{CopyBidirectionalError, COPY_CHUNK};
+    use crate::ReadWriteTokio;
+
+    async fn copy_counting<R, W>(r: &mut R, w: &mut W, copied: &AtomicU64) -> std::io::Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut buf = [0u8; COPY_CHUNK];
+        loop {
+            let n = r.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            let mut chunk = &buf[..n];
+            while !chunk.is_empty() {
+                let n = w.write(chunk).await?;
+                if n == 0 {
+                    return Err(std::io::ErrorKind::WriteZero.into());
+                }
+                chunk = &chunk[n..];
+                copied.fetch_add(n as u64, Ordering::Relaxed);
+            }
+        }
+        w.flush().await?;
+        w.shutdown().await
+    }
+
+    /// Pump data between two async pseudo-sockets in both directions until both readers reach EOF.
+    ///
+    /// Directions are driven independently, so this works with unrelated reader and writer halves.
+    /// When one reader reaches EOF, the writer on the opposite side is flushed and shut down,
+    /// while the other direction continues.
+    ///
+    /// Returns numbers of bytes copied from `a` to `b` and from `b` to `a`.
+    /// The first error in either direction aborts both and is returned as `CopyBidirectionalError`
+    /// wrapped in `std::io::Error`.
+    pub async fn copy_bidirectional_tokio<R1, W1, R2, W2>(
+        a: &mut ReadWriteTokio<R1, W1>,
+        b: &mut ReadWriteTokio<R2, W2>,
+    ) -> std::io::Result<(u64, u64)>
+    where
+        R1: AsyncRead + Unpin,
+        W1: AsyncWrite + Unpin,
+        R2: AsyncRead + Unpin,
+        W2: AsyncWrite + Unpin,
+    {
+        let (ar, aw) = a.borrow_mut();
+        let (br, bw) = b.borrow_mut();
+        let a_to_b = AtomicU64::new(0);
+        let b_to_a = AtomicU64::new(0);
+        let mut a_to_b_fut = std::pin::pin!(copy_counting(ar, bw, &a_to_b));
+        let mut b_to_a_fut = std::pin::pin!(copy_counting(br, aw, &b_to_a));
+        let (mut a_to_b_done, mut b_to_a_done) = (false, false);
+        let result = poll_fn(|cx| {
+            if !a_to_b_done {
+                if let Poll::Ready(r) = a_to_b_fut.as_mut().poll(cx) {
+                    r?;
+                    a_to_b_done = true;
+                }
+            }
+            if !b_to_a_done {
+                if let Poll::Ready(r) = b_to_a_fut.as_mut().poll(cx) {
+                    r?;
+                    b_to_a_done = true;
+                }
+            }
+            if a_to_b_done && b_to_a_done {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+        let (a_to_b, b_to_a) = (
+            a_to_b.load(Ordering::Relaxed),
+            b_to_a.load(Ordering::Relaxed),
+        );
+        match result {
+            Ok(()) => Ok((a_to_b, b_to_a)),
+            Err(error) => Err(CopyBidirectionalError {
+                a_to_b,
+                b_to_a,
+                error,
+            }
+            .into()),
+        }
+    }
+}
+#[cfg(feature = "tokio")]
+pub use self::tokio::copy_bidirectional_tokio;
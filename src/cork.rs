@@ -0,0 +1,283 @@
+use std::io::{ErrorKind, Read, Result, Write};
+
+use crate::ReadWrite;
+
+/// Corking state shared by all `Corkable` variants
+#[derive(Default)]
+struct CorkBuf {
+    buf: Vec<u8>,
+    depth: usize,
+}
+
+impl CorkBuf {
+    /// Leave one level of corking. Returns whether pending data should be written out now.
+    fn leave(&mut self) -> bool {
+        if self.depth > 1 {
+            self.depth -= 1;
+            return false;
+        }
+        self.depth = 0;
+        true
+    }
+}
+
+macro_rules! corkable_common {
+    ($name:ident) => {
+        impl<T> $name<T> {
+            /// Wrap `inner`, initially uncorked
+            pub fn new(inner: T) -> Self {
+                $name {
+                    inner,
+                    cork: Default::default(),
+                }
+            }
+            /// Start accumulating writes. May be called again while already corked.
+            pub fn cork(&mut self) {
+                self.cork.depth += 1;
+            }
+            /// Whether writes are currently being accumulated
+            pub fn is_corked(&self) -> bool {
+                self.cork.depth > 0
+            }
+            /// Data accumulated, but not written to the inner writer yet
+            pub fn pending(&self) -> &[u8] {
+                &self.cork.buf
+            }
+            /// Take away data accumulated, but not written to the inner writer yet
+            pub fn take_pending(&mut self) -> Vec<u8> {
+                std::mem::take(&mut self.cork.buf)
+            }
+            /// Borrow the inner object
+            pub fn get_ref(&self) -> &T {
+                &self.inner
+            }
+            /// Mutably borrow the inner object. Writes done through it bypass the cork.
+            pub fn get_mut(&mut self) -> &mut T {
+                &mut self.inner
+            }
+            /// Return the inner object, discarding pending data. Uncork first to avoid losing it.
+            pub fn into_inner(self) -> T {
+                self.inner
+            }
+        }
+    };
+}
+
+/// Wrapper with explicit control over write batching, like `TCP_CORK`.
+/// Created by `ReadWrite::corkable` or `Corkable::new`.
+///
+/// While corked, all writes are accumulated in an internal buffer.
+/// Uncorking writes the accumulated data out in as few writes as the inner writer allows and flushes it.
+/// While uncorked, writes pass straight through.
+///
+/// `cork` calls nest: data is only written out when the outermost cork is removed.
+/// If writing out fails, the unwritten remainder stays in the buffer (see `pending`);
+/// it is retried by the next `uncork` or write, or can be taken away with `take_pending`.
+pub struct Corkable<T> {
+    inner: T,
+    cork: CorkBuf,
+}
+
+corkable_common!(Corkable);
+
+impl<T: Write> Corkable<T> {
+    /// Remove one level of corking. When the outermost cork is removed, write out the accumulated data and flush.
+    ///
+    /// When already uncorked, retries writing out data left over from a failed uncork, if any.
+    pub fn uncork(&mut self) -> Result<()> {
+        if !self.cork.leave() {
+            return Ok(());
+        }
+        self.write_pending()?;
+        self.inner.flush()
+    }
+
+    fn write_pending(&mut self) -> Result<()> {
+        let buf = &mut self.cork.buf;
+        while !buf.is_empty() {
+            match self.inner.write(buf) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => drop(buf.drain(..n)),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Allow explicit batching of writes with `cork` and `uncork`
+    pub fn corkable(self) -> Corkable<Self> {
+        Corkable::new(self)
+    }
+}
+
+impl<T: Read> Read for Corkable<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<T: Write> Write for Corkable<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if self.is_corked() {
+            self.cork.buf.extend_from_slice(buf);
+            return Ok(buf.len());
+        }
+        self.write_pending()?;
+        self.inner.write(buf)
+    }
+    /// Flushes the inner writer only. Data accumulated while corked is not written out.
+    fn flush(&mut self) -> Result<()> {
+        if !self.is_corked() {
+            self.write_pending()?;
+        }
+        self.inner.flush()
+    }
+}
+
+/// Generates a `Corkable` variant for a poll-based async runtime
+#[cfg(any(feature = "tokio", feature = "asyncstd"))]
+macro_rules! corkable_async {
+    ($name:ident, $rw:ident, $flavour:literal, $close:ident) => {
+        #[doc = concat!("Wrapper with explicit control over write batching, like `TCP_CORK`, ", $flavour, " version.")]
+        #[doc = concat!("Created by `", stringify!($rw), "::corkable` or `", stringify!($name), "::new`.")]
+        ///
+        /// See `Corkable` for details. Additionally, closing the writer removes all corks
+        /// and writes out the accumulated data first.
+        pub struct $name<T> {
+            inner: T,
+            cork: CorkBuf,
+        }
+
+        corkable_common!($name);
+
+        impl<R: AsyncRead, W: AsyncWrite> $rw<R, W> {
+            /// Allow explicit batching of writes with `cork` and `uncork`
+            pub fn corkable(self) -> $name<Self> {
+                $name::new(self)
+            }
+        }
+
+        impl<T: AsyncWrite + Unpin> $name<T> {
+            fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                let buf = &mut self.cork.buf;
+                while !buf.is_empty() {
+                    let n = std::task::ready!(Pin::new(&mut self.inner).poll_write(cx, buf))?;
+                    if n == 0 {
+                        return Poll::Ready(Err(std::io::ErrorKind::WriteZero.into()));
+                    }
+                    buf.drain(..n);
+                }
+                Poll::Ready(Ok(()))
+            }
+
+            /// Remove one level of corking. When the outermost cork is removed, write out the accumulated data and flush.
+            ///
+            /// When already uncorked, retries writing out data left over from a failed uncork, if any.
+            pub fn poll_uncork(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                // Corking level is only left once, on the first poll
+                if self.cork.depth > 0 && !self.cork.leave() {
+                    return Poll::Ready(Ok(()));
+                }
+                std::task::ready!(self.poll_write_pending(cx))?;
+                Pin::new(&mut self.inner).poll_flush(cx)
+            }
+
+            /// Remove one level of corking. When the outermost cork is removed, write out the accumulated data and flush.
+            ///
+            /// When already uncorked, retries writing out data left over from a failed uncork, if any.
+            pub async fn uncork(&mut self) -> std::io::Result<()> {
+                std::future::poll_fn(|cx| self.poll_uncork(cx)).await
+            }
+        }
+
+        impl<T: AsyncWrite + Unpin> AsyncWrite for $name<T> {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<std::io::Result<usize>> {
+                let this = self.get_mut();
+                if this.is_corked() {
+                    this.cork.buf.extend_from_slice(buf);
+                    return Poll::Ready(Ok(buf.len()));
+                }
+                std::task::ready!(this.poll_write_pending(cx))?;
+                Pin::new(&mut this.inner).poll_write(cx, buf)
+            }
+
+            /// Flushes the inner writer only. Data accumulated while corked is not written out.
+            fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                let this = self.get_mut();
+                if !this.is_corked() {
+                    std::task::ready!(this.poll_write_pending(cx))?;
+                }
+                Pin::new(&mut this.inner).poll_flush(cx)
+            }
+
+            fn $close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                let this = self.get_mut();
+                this.cork.depth = 0;
+                std::task::ready!(this.poll_write_pending(cx))?;
+                Pin::new(&mut this.inner).$close(cx)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::CorkBuf;
+    use crate::ReadWriteTokio;
+
+    corkable_async!(CorkableTokio, ReadWriteTokio, "`tokio 1`", poll_shutdown);
+
+    impl<T: AsyncRead + Unpin> AsyncRead for CorkableTokio<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+        }
+    }
+}
+#[cfg(feature = "tokio")]
+pub use self::tokio::CorkableTokio;
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::CorkBuf;
+    use crate::ReadWriteAsyncstd;
+
+    corkable_async!(
+        CorkableAsyncstd,
+        ReadWriteAsyncstd,
+        "`futures 0.3`",
+        poll_close
+    );
+
+    impl<T: AsyncRead + Unpin> AsyncRead for CorkableAsyncstd<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+        }
+    }
+}
+#[cfg(feature = "asyncstd")]
+pub use self::asyncstd::CorkableAsyncstd;
@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Result, Write};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::Waker;
+
+type Map = Box<dyn FnMut(&[u8]) -> Vec<u8> + Send>;
+
+struct State {
+    buf: VecDeque<u8>,
+    map: Option<Map>,
+    /// Tasks of all clones waiting for data
+    wakers: Vec<Waker>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    readable: Condvar,
+}
+
+/// Loopback pseudo-socket for tests: everything written to it becomes readable back from it.
+/// Created by `echo` or `echo_with`.
+///
+/// Implements `Read + Write`, as well as async read and write traits of `tokio` and `futures`
+/// if the respective Cargo features are enabled.
+///
+/// Clones share the same buffer, so data written through one clone can be read through another.
+///
+/// Reading with no data available fails with `ErrorKind::WouldBlock` in sync code,
+/// or waits for another clone to write if `set_blocking(true)` was called.
+/// Async reads return `Pending` and are woken by the next write. If several clones are waiting,
+/// all of them are woken, and those finding the data already taken by another wait again.
+/// There is no EOF.
+#[derive(Clone)]
+pub struct Echo {
+    shared: Arc<Shared>,
+    blocking: bool,
+}
+
+/// Create a loopback pseudo-socket, see `Echo`
+pub fn echo() -> Echo {
+    Echo {
+        shared: Arc::new(Shared {
+            state: Mutex::new(State {
+                buf: VecDeque::new(),
+                map: None,
+                wakers: Vec::new(),
+            }),
+            readable: Condvar::new(),
+        }),
+        blocking: false,
+    }
+}
+
+/// Create a loopback pseudo-socket which passes each written buffer through `map` before making it readable.
+///
+/// `map` may keep state, e.g. to hold back data to simulate lag, and may return an empty vector.
+pub fn echo_with(map: impl FnMut(&[u8]) -> Vec<u8> + Send + 'static) -> Echo {
+    let echo = echo();
+    echo.lock().map = Some(Box::new(map));
+    echo
+}
+
+impl Echo {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether sync reads with no data available wait for a write from another clone instead of failing with `WouldBlock`
+    pub fn set_blocking(&mut self, blocking: bool) {
+        self.blocking = blocking;
+    }
+
+    /// Number of bytes available for reading
+    pub fn available(&self) -> usize {
+        self.lock().buf.len()
+    }
+
+    fn push(&self, data: &[u8]) {
+        let mut state = self.lock();
+        match state.map.as_mut() {
+            Some(map) => {
+                let mapped = map(data);
+                state.buf.extend(mapped);
+            }
+            None => state.buf.extend(data),
+        }
+        if !state.buf.is_empty() {
+            for waker in state.wakers.drain(..) {
+                waker.wake();
+            }
+            self.shared.readable.notify_all();
+        }
+    }
+}
+
+fn pop(state: &mut State, buf: &mut [u8]) -> usize {
+    let n = buf.len().min(state.buf.len());
+    for (dst, src) in buf.iter_mut().zip(state.buf.drain(..n)) {
+        *dst = src;
+    }
+    n
+}
+
+impl Read for Echo {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut state = self.lock();
+        while state.buf.is_empty() {
+            if !self.blocking {
+                return Err(ErrorKind::WouldBlock.into());
+            }
+            state = self
+                .shared
+                .readable
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        Ok(pop(&mut state, buf))
+    }
+}
+
+impl Write for Echo {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if !buf.is_empty() {
+            self.push(buf);
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "asyncstd"))]
+impl Echo {
+    fn poll_pop(&self, cx: &mut std::task::Context<'_>, buf: &mut [u8]) -> std::task::Poll<usize> {
+        let mut state = self.lock();
+        if state.buf.is_empty() && !buf.is_empty() {
+            if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            return std::task::Poll::Pending;
+        }
+        std::task::Poll::Ready(pop(&mut state, buf))
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::Echo;
+
+    impl AsyncRead for Echo {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let n = std::task::ready!(self.poll_pop(cx, buf.initialize_unfilled()));
+            buf.advance(n);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncWrite for Echo {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            if !buf.is_empty() {
+                self.push(buf);
+            }
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::Echo;
+
+    impl AsyncRead for Echo {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.poll_pop(cx, buf).map(Ok)
+        }
+    }
+
+    impl AsyncWrite for Echo {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            if !buf.is_empty() {
+                self.push(buf);
+            }
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use core::fmt;
+
+use embedded_io::{ErrorType, Read, ReadReady, Write, WriteReady};
+
+/// Combined reader and writer for `embedded-io` traits, usable in `no_std` environments.
+/// Note that this struct is only present in `readwrite` if "embedded-io" Cargo feature is enabled.
+///
+/// Reader and writer may have different error types, so errors are reported as `EitherError`.
+pub struct ReadWriteEmbedded<R, W> {
+    r: R,
+    w: W,
+}
+
+/// Error of `ReadWriteEmbedded`, telling which half it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EitherError<RE, WE> {
+    /// Error of the reader
+    Read(RE),
+    /// Error of the writer
+    Write(WE),
+}
+
+impl<RE: fmt::Display, WE: fmt::Display> fmt::Display for EitherError<RE, WE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EitherError::Read(e) => write!(f, "read error: {}", e),
+            EitherError::Write(e) => write!(f, "write error: {}", e),
+        }
+    }
+}
+
+impl<RE: core::error::Error, WE: core::error::Error> core::error::Error for EitherError<RE, WE> {}
+
+impl<RE: embedded_io::Error, WE: embedded_io::Error> embedded_io::Error for EitherError<RE, WE> {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            EitherError::Read(e) => e.kind(),
+            EitherError::Write(e) => e.kind(),
+        }
+    }
+}
+
+impl<R: ErrorType, W: ErrorType> From<(R, W)> for ReadWriteEmbedded<R, W> {
+    fn from((r, w): (R, W)) -> Self {
+        ReadWriteEmbedded { r, w }
+    }
+}
+impl<R: ErrorType, W: ErrorType> ReadWriteEmbedded<R, W> {
+    /// Bundle separate reader and writer into a combined pseudo-socket
+    pub fn new(r: R, w: W) -> Self {
+        ReadWriteEmbedded { r, w }
+    }
+    /// Borrow inner objects
+    pub fn borrow(&self) -> (&R, &W) {
+        (&self.r, &self.w)
+    }
+    /// Borrow the reader
+    pub fn borrow_read(&self) -> &R {
+        &self.r
+    }
+    /// Borrow the writer
+    pub fn borrow_write(&self) -> &W {
+        &self.w
+    }
+    /// Mutably borrow inner objects
+    pub fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+        (&mut self.r, &mut self.w)
+    }
+    /// Mutably borrow the reader
+    pub fn borrow_mut_read(&mut self) -> &mut R {
+        &mut self.r
+    }
+    /// Mutably borrow the writer
+    pub fn borrow_mut_write(&mut self) -> &mut W {
+        &mut self.w
+    }
+    /// Convert ReadWriteEmbedded back into individual reader and writer pair
+    pub fn into_inner(self) -> (R, W) {
+        (self.r, self.w)
+    }
+    /// Convert ReadWriteEmbedded back into the reader, dropping the writer
+    pub fn into_reader(self) -> R {
+        self.r
+    }
+    /// Convert ReadWriteEmbedded back into the writer, dropping the reader
+    pub fn into_writer(self) -> W {
+        self.w
+    }
+}
+
+impl<R: ErrorType, W: ErrorType> crate::Pair for ReadWriteEmbedded<R, W> {
+    type Reader = R;
+    type Writer = W;
+    fn new(r: R, w: W) -> Self {
+        ReadWriteEmbedded::new(r, w)
+    }
+    fn borrow(&self) -> (&R, &W) {
+        ReadWriteEmbedded::borrow(self)
+    }
+    fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+        ReadWriteEmbedded::borrow_mut(self)
+    }
+    fn into_inner(self) -> (R, W) {
+        ReadWriteEmbedded::into_inner(self)
+    }
+}
+
+impl<R: ErrorType, W: ErrorType> ErrorType for ReadWriteEmbedded<R, W> {
+    type Error = EitherError<R::Error, W::Error>;
+}
+
+impl<R: Read, W: ErrorType> Read for ReadWriteEmbedded<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.r.read(buf).map_err(EitherError::Read)
+    }
+}
+
+impl<R: ErrorType, W: Write> Write for ReadWriteEmbedded<R, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.w.write(buf).map_err(EitherError::Write)
+    }
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.w.flush().map_err(EitherError::Write)
+    }
+}
+
+impl<R: ReadReady, W: ErrorType> ReadReady for ReadWriteEmbedded<R, W> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        self.r.read_ready().map_err(EitherError::Read)
+    }
+}
+
+impl<R: ErrorType, W: WriteReady> WriteReady for ReadWriteEmbedded<R, W> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        self.w.write_ready().map_err(EitherError::Write)
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl<R: embedded_io_async::Read, W: ErrorType> embedded_io_async::Read for ReadWriteEmbedded<R, W> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.r.read(buf).await.map_err(EitherError::Read)
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl<R: ErrorType, W: embedded_io_async::Write> embedded_io_async::Write
+    for ReadWriteEmbedded<R, W>
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.w.write(buf).await.map_err(EitherError::Write)
+    }
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.w.flush().await.map_err(EitherError::Write)
+    }
+}
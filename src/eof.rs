@@ -0,0 +1,68 @@
+use std::io::{ErrorKind, Read, Result, Write};
+
+use crate::ReadWrite;
+
+/// Reader turning EOF after some data into `ErrorKind::UnexpectedEof`.
+/// Created by `ReadWrite::error_on_eof` or `EofAsError::new`.
+///
+/// EOF before the first byte is reported as `Ok(0)`, any later EOF is an error.
+/// To tell a peer closing the connection between messages apart from one closing it mid-message,
+/// call `message_done` after each complete message, so that EOF right after it is `Ok(0)` again.
+/// Reads into an empty buffer return `Ok(0)` as usual.
+pub struct EofAsError<R> {
+    inner: R,
+    seen_data: bool,
+}
+
+impl<R: Read> EofAsError<R> {
+    /// Wrap `inner`, initially with no data read
+    pub fn new(inner: R) -> Self {
+        EofAsError {
+            inner,
+            seen_data: false,
+        }
+    }
+    /// Whether any bytes were read so far, or since the last `message_done`
+    pub fn seen_data(&self) -> bool {
+        self.seen_data
+    }
+    /// Mark a message boundary: EOF before the next byte is reported as `Ok(0)` again
+    pub fn message_done(&mut self) {
+        self.seen_data = false;
+    }
+    /// Borrow the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Mutably borrow the inner reader. Data read through it is not taken into account.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+    /// Return the inner reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for EofAsError<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self.inner.read(buf)? {
+            0 if self.seen_data && !buf.is_empty() => Err(std::io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "reader ended after partial data",
+            )),
+            0 => Ok(0),
+            n => {
+                self.seen_data = true;
+                Ok(n)
+            }
+        }
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Report EOF of the reader as `ErrorKind::UnexpectedEof` once any data has been read, see `EofAsError`
+    pub fn error_on_eof(self) -> ReadWrite<EofAsError<R>, W> {
+        self.map_read(EofAsError::new)
+    }
+}
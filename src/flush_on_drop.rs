@@ -0,0 +1,133 @@
+use std::io::{BufRead, Error, IoSlice, IoSliceMut, Read, Result, Write};
+
+use crate::ReadWrite;
+
+/// Pseudo-socket which flushes its writer when dropped, so that buffered data is not lost silently.
+/// Created by `ReadWrite::flush_on_drop`.
+///
+/// Errors of the flush on drop are ignored unless a callback is set with `on_error`.
+/// Use `finish` to flush explicitly and get the error.
+pub struct FlushOnDrop<R: Read, W: Write> {
+    inner: Option<ReadWrite<R, W>>,
+    on_error: Option<Box<dyn FnOnce(Error) + Send>>,
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Flush the writer when the pseudo-socket is dropped
+    pub fn flush_on_drop(self) -> FlushOnDrop<R, W> {
+        FlushOnDrop {
+            inner: Some(self),
+            on_error: None,
+        }
+    }
+    /// Flush the writer and convert back into individual reader and writer pair
+    pub fn finish(mut self) -> Result<(R, W)> {
+        self.1.flush()?;
+        Ok(self.into_inner())
+    }
+}
+
+impl<R: Read, W: Write> FlushOnDrop<R, W> {
+    /// Call `f` with the error if flushing on drop fails
+    pub fn on_error(mut self, f: impl FnOnce(Error) + Send + 'static) -> Self {
+        self.on_error = Some(Box::new(f));
+        self
+    }
+    /// Borrow the inner pseudo-socket
+    pub fn get_ref(&self) -> &ReadWrite<R, W> {
+        self.inner.as_ref().expect("only taken by finish or drop")
+    }
+    /// Mutably borrow the inner pseudo-socket
+    pub fn get_mut(&mut self) -> &mut ReadWrite<R, W> {
+        self.inner.as_mut().expect("only taken by finish or drop")
+    }
+    /// Flush the writer and convert back into individual reader and writer pair.
+    /// Nothing is flushed on drop afterwards.
+    pub fn finish(mut self) -> Result<(R, W)> {
+        self.inner
+            .take()
+            .expect("only taken by finish or drop")
+            .finish()
+    }
+}
+
+impl<R: Read, W: Write> Drop for FlushOnDrop<R, W> {
+    fn drop(&mut self) {
+        let Some(mut rw) = self.inner.take() else {
+            return;
+        };
+        if let Err(e) = rw.1.flush() {
+            if let Some(f) = self.on_error.take() {
+                f(e);
+            }
+        }
+    }
+}
+
+impl<R: Read, W: Write> Read for FlushOnDrop<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.get_mut().read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        self.get_mut().read_vectored(bufs)
+    }
+}
+
+impl<R: Read, W: Write> Write for FlushOnDrop<R, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.get_mut().write(buf)
+    }
+    fn flush(&mut self) -> Result<()> {
+        self.get_mut().flush()
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        self.get_mut().write_vectored(bufs)
+    }
+}
+
+impl<R: BufRead, W: Write> BufRead for FlushOnDrop<R, W> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.get_mut().fill_buf()
+    }
+    fn consume(&mut self, amt: usize) {
+        self.get_mut().consume(amt)
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+    use crate::ReadWriteTokio;
+
+    impl<R: AsyncRead, W: AsyncWrite + Unpin> ReadWriteTokio<R, W> {
+        /// Flush the writer and convert back into individual reader and writer pair.
+        ///
+        /// There is no async drop, so unlike the sync `ReadWrite::flush_on_drop`
+        /// this has to be called explicitly before dropping a buffered pseudo-socket.
+        pub async fn finish(mut self) -> std::io::Result<(R, W)> {
+            self.borrow_mut_write().flush().await?;
+            Ok(self.into_inner())
+        }
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+    use crate::ReadWriteAsyncstd;
+
+    impl<R: AsyncRead, W: AsyncWrite + Unpin> ReadWriteAsyncstd<R, W> {
+        /// Flush the writer and convert back into individual reader and writer pair.
+        ///
+        /// There is no async drop, so unlike the sync `ReadWrite::flush_on_drop`
+        /// this has to be called explicitly before dropping a buffered pseudo-socket.
+        pub async fn finish(mut self) -> std::io::Result<(R, W)> {
+            self.borrow_mut_write().flush().await?;
+            Ok(self.into_inner())
+        }
+    }
+}
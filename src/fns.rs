@@ -0,0 +1,295 @@
+use std::io::{Read, Result, Write};
+
+use crate::ReadWrite;
+
+/// Reader implemented by a closure. See `from_fns`.
+pub struct FnReader<F>(pub F);
+
+/// Default flush function of `FnWriter`, which does nothing
+pub type NoopFlush = fn() -> Result<()>;
+
+fn noop_flush() -> Result<()> {
+    Ok(())
+}
+
+/// Writer implemented by closures. See `from_fns`.
+pub struct FnWriter<F, FF = NoopFlush> {
+    write_fn: F,
+    flush_fn: FF,
+}
+
+impl<F: FnMut(&mut [u8]) -> Result<usize>> Read for FnReader<F> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (self.0)(buf)
+    }
+}
+
+impl<F: FnMut(&[u8]) -> Result<usize>> FnWriter<F> {
+    /// Writer with no-op flush
+    pub fn new(write_fn: F) -> Self {
+        FnWriter {
+            write_fn,
+            flush_fn: noop_flush,
+        }
+    }
+}
+
+impl<F: FnMut(&[u8]) -> Result<usize>, FF: FnMut() -> Result<()>> FnWriter<F, FF> {
+    /// Writer with custom flush
+    pub fn with_flush(write_fn: F, flush_fn: FF) -> Self {
+        FnWriter { write_fn, flush_fn }
+    }
+}
+
+impl<F: FnMut(&[u8]) -> Result<usize>, FF: FnMut() -> Result<()>> Write for FnWriter<F, FF> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        (self.write_fn)(buf)
+    }
+    fn flush(&mut self) -> Result<()> {
+        (self.flush_fn)()
+    }
+}
+
+/// Build a pseudo-socket from a read callback and a write callback, without defining any types.
+/// Flushing does nothing.
+pub fn from_fns<RF, WF>(read_fn: RF, write_fn: WF) -> ReadWrite<FnReader<RF>, FnWriter<WF>>
+where
+    RF: FnMut(&mut [u8]) -> Result<usize>,
+    WF: FnMut(&[u8]) -> Result<usize>,
+{
+    ReadWrite(FnReader(read_fn), FnWriter::new(write_fn))
+}
+
+/// Like `from_fns`, but also with a flush callback
+pub fn from_fns_with_flush<RF, WF, FF>(
+    read_fn: RF,
+    write_fn: WF,
+    flush_fn: FF,
+) -> ReadWrite<FnReader<RF>, FnWriter<WF, FF>>
+where
+    RF: FnMut(&mut [u8]) -> Result<usize>,
+    WF: FnMut(&[u8]) -> Result<usize>,
+    FF: FnMut() -> Result<()>,
+{
+    ReadWrite(FnReader(read_fn), FnWriter::with_flush(write_fn, flush_fn))
+}
+
+/// Default flush and shutdown callback of async closure-based writers, which does nothing
+#[cfg(any(feature = "tokio", feature = "asyncstd"))]
+pub type NoopPoll = fn(&mut std::task::Context<'_>) -> std::task::Poll<Result<()>>;
+
+#[cfg(any(feature = "tokio", feature = "asyncstd"))]
+fn noop_poll(_cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<()>> {
+    std::task::Poll::Ready(Ok(()))
+}
+
+/// Generates closure-based async reader and writer types for a poll-based runtime
+#[cfg(any(feature = "tokio", feature = "asyncstd"))]
+macro_rules! poll_fns_writer {
+    ($name:ident, $flavour:literal, $ctor:literal, $close:ident) => {
+        #[doc = concat!("Async writer implemented by poll-style closures, ", $flavour, " version. See `", $ctor, "`.")]
+        pub struct $name<F, FF = NoopPoll, SF = NoopPoll> {
+            write_fn: F,
+            flush_fn: FF,
+            close_fn: SF,
+        }
+
+        impl<F> $name<F>
+        where
+            F: FnMut(&mut Context<'_>, &[u8]) -> Poll<std::io::Result<usize>>,
+        {
+            #[doc = concat!("Writer with no-op `poll_flush` and `", stringify!($close), "`")]
+            pub fn new(write_fn: F) -> Self {
+                $name {
+                    write_fn,
+                    flush_fn: noop_poll,
+                    close_fn: noop_poll,
+                }
+            }
+        }
+
+        impl<F, FF, SF> $name<F, FF, SF>
+        where
+            F: FnMut(&mut Context<'_>, &[u8]) -> Poll<std::io::Result<usize>>,
+            FF: FnMut(&mut Context<'_>) -> Poll<std::io::Result<()>>,
+            SF: FnMut(&mut Context<'_>) -> Poll<std::io::Result<()>>,
+        {
+            #[doc = concat!("Writer with custom `poll_flush` and `", stringify!($close), "`")]
+            pub fn with_flush_and_close(write_fn: F, flush_fn: FF, close_fn: SF) -> Self {
+                $name {
+                    write_fn,
+                    flush_fn,
+                    close_fn,
+                }
+            }
+        }
+
+        impl<F, FF, SF> AsyncWrite for $name<F, FF, SF>
+        where
+            F: FnMut(&mut Context<'_>, &[u8]) -> Poll<std::io::Result<usize>>,
+            FF: FnMut(&mut Context<'_>) -> Poll<std::io::Result<()>>,
+            SF: FnMut(&mut Context<'_>) -> Poll<std::io::Result<()>>,
+        {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<std::io::Result<usize>> {
+                (self.get_mut().write_fn)(cx, buf)
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                (self.get_mut().flush_fn)(cx)
+            }
+
+            fn $close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                (self.get_mut().close_fn)(cx)
+            }
+        }
+
+        // Closures are never pinned
+        impl<F, FF, SF> Unpin for $name<F, FF, SF> {}
+    };
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{noop_poll, NoopPoll};
+    use crate::ReadWriteTokio;
+
+    /// Async reader implemented by a poll-style closure, `tokio 1` version. See `from_poll_fns_tokio`.
+    pub struct FnReaderTokio<F>(pub F);
+
+    impl<F> AsyncRead for FnReaderTokio<F>
+    where
+        F: FnMut(&mut Context<'_>, &mut ReadBuf<'_>) -> Poll<std::io::Result<()>>,
+    {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            (self.get_mut().0)(cx, buf)
+        }
+    }
+
+    // Closures are never pinned
+    impl<F> Unpin for FnReaderTokio<F> {}
+
+    poll_fns_writer!(
+        FnWriterTokio,
+        "`tokio 1`",
+        "from_poll_fns_tokio",
+        poll_shutdown
+    );
+
+    /// Build an async pseudo-socket from poll-style read and write callbacks, without defining any types.
+    /// Flushing and shutting down do nothing.
+    pub fn from_poll_fns_tokio<RF, WF>(
+        read_fn: RF,
+        write_fn: WF,
+    ) -> ReadWriteTokio<FnReaderTokio<RF>, FnWriterTokio<WF>>
+    where
+        RF: FnMut(&mut Context<'_>, &mut ReadBuf<'_>) -> Poll<std::io::Result<()>>,
+        WF: FnMut(&mut Context<'_>, &[u8]) -> Poll<std::io::Result<usize>>,
+    {
+        ReadWriteTokio::new(FnReaderTokio(read_fn), FnWriterTokio::new(write_fn))
+    }
+
+    impl<RF, WF, FF, SF> ReadWriteTokio<FnReaderTokio<RF>, FnWriterTokio<WF, FF, SF>>
+    where
+        RF: FnMut(&mut Context<'_>, &mut ReadBuf<'_>) -> Poll<std::io::Result<()>>,
+        WF: FnMut(&mut Context<'_>, &[u8]) -> Poll<std::io::Result<usize>>,
+        FF: FnMut(&mut Context<'_>) -> Poll<std::io::Result<()>>,
+        SF: FnMut(&mut Context<'_>) -> Poll<std::io::Result<()>>,
+    {
+        /// Build an async pseudo-socket from poll-style callbacks, without defining any types.
+        /// See `from_poll_fns_tokio` for a version with no-op flush and shutdown.
+        ///
+        /// The closures are stored unpinned, so they need not care about pinning.
+        pub fn from_poll_fns(read_fn: RF, write_fn: WF, flush_fn: FF, shutdown_fn: SF) -> Self {
+            ReadWriteTokio::new(
+                FnReaderTokio(read_fn),
+                FnWriterTokio::with_flush_and_close(write_fn, flush_fn, shutdown_fn),
+            )
+        }
+    }
+}
+#[cfg(feature = "tokio")]
+pub use self::tokio::{from_poll_fns_tokio, FnReaderTokio, FnWriterTokio};
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{noop_poll, NoopPoll};
+    use crate::ReadWriteAsyncstd;
+
+    /// Async reader implemented by a poll-style closure, `futures 0.3` version. See `from_poll_fns_asyncstd`.
+    pub struct FnReaderAsyncstd<F>(pub F);
+
+    impl<F> AsyncRead for FnReaderAsyncstd<F>
+    where
+        F: FnMut(&mut Context<'_>, &mut [u8]) -> Poll<std::io::Result<usize>>,
+    {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            (self.get_mut().0)(cx, buf)
+        }
+    }
+
+    // Closures are never pinned
+    impl<F> Unpin for FnReaderAsyncstd<F> {}
+
+    poll_fns_writer!(
+        FnWriterAsyncstd,
+        "`futures 0.3`",
+        "from_poll_fns_asyncstd",
+        poll_close
+    );
+
+    /// Build an async pseudo-socket from poll-style read and write callbacks, without defining any types.
+    /// Flushing and closing do nothing.
+    pub fn from_poll_fns_asyncstd<RF, WF>(
+        read_fn: RF,
+        write_fn: WF,
+    ) -> ReadWriteAsyncstd<FnReaderAsyncstd<RF>, FnWriterAsyncstd<WF>>
+    where
+        RF: FnMut(&mut Context<'_>, &mut [u8]) -> Poll<std::io::Result<usize>>,
+        WF: FnMut(&mut Context<'_>, &[u8]) -> Poll<std::io::Result<usize>>,
+    {
+        ReadWriteAsyncstd::new(FnReaderAsyncstd(read_fn), FnWriterAsyncstd::new(write_fn))
+    }
+
+    impl<RF, WF, FF, CF> ReadWriteAsyncstd<FnReaderAsyncstd<RF>, FnWriterAsyncstd<WF, FF, CF>>
+    where
+        RF: FnMut(&mut Context<'_>, &mut [u8]) -> Poll<std::io::Result<usize>>,
+        WF: FnMut(&mut Context<'_>, &[u8]) -> Poll<std::io::Result<usize>>,
+        FF: FnMut(&mut Context<'_>) -> Poll<std::io::Result<()>>,
+        CF: FnMut(&mut Context<'_>) -> Poll<std::io::Result<()>>,
+    {
+        /// Build an async pseudo-socket from poll-style callbacks, without defining any types.
+        /// See `from_poll_fns_asyncstd` for a version with no-op flush and close.
+        ///
+        /// The closures are stored unpinned, so they need not care about pinning.
+        pub fn from_poll_fns(read_fn: RF, write_fn: WF, flush_fn: FF, close_fn: CF) -> Self {
+            ReadWriteAsyncstd::new(
+                FnReaderAsyncstd(read_fn),
+                FnWriterAsyncstd::with_flush_and_close(write_fn, flush_fn, close_fn),
+            )
+        }
+    }
+}
+#[cfg(feature = "asyncstd")]
+pub use self::asyncstd::{from_poll_fns_asyncstd, FnReaderAsyncstd, FnWriterAsyncstd};
@@ -0,0 +1,157 @@
+use core::marker::PhantomData;
+
+/// Reader with an arbitrary error type, for use with `GenericReadWrite`.
+///
+/// With "std" Cargo feature, wrap `std::io::Read`ers in `StdReader` to use them as `GenericRead` with `std::io::Error`.
+/// Methods are prefixed with `generic_` to avoid ambiguity with `std::io::Read` ones when both traits are in scope.
+pub trait GenericRead {
+    /// Error type of this reader
+    type Error;
+    /// Read some bytes into `buf`, returning how many were read. Zero means end of stream.
+    fn generic_read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error>;
+}
+
+/// Writer with an arbitrary error type, for use with `GenericReadWrite`.
+///
+/// With "std" Cargo feature, wrap `std::io::Write`rs in `StdWriter` to use them as `GenericWrite` with `std::io::Error`.
+/// Methods are prefixed with `generic_` to avoid ambiguity with `std::io::Write` ones when both traits are in scope.
+pub trait GenericWrite {
+    /// Error type of this writer
+    type Error;
+    /// Write some bytes from `buf`, returning how many were written
+    fn generic_write(&mut self, buf: &[u8]) -> Result<usize, Self::Error>;
+    /// Flush buffered data, if any
+    fn generic_flush(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Adapter implementing `GenericRead` for a `std::io::Read`er, with `std::io::Error` as the error.
+///
+/// This is a wrapper instead of a blanket impl, so that enabling "std" Cargo feature
+/// does not conflict with `GenericRead` impls for other types.
+#[cfg(feature = "std")]
+pub struct StdReader<T>(pub T);
+
+#[cfg(feature = "std")]
+impl<T: std::io::Read> GenericRead for StdReader<T> {
+    type Error = std::io::Error;
+    fn generic_read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.0.read(buf)
+    }
+}
+
+/// Adapter implementing `GenericWrite` for a `std::io::Write`r, with `std::io::Error` as the error.
+///
+/// This is a wrapper instead of a blanket impl, so that enabling "std" Cargo feature
+/// does not conflict with `GenericWrite` impls for other types.
+#[cfg(feature = "std")]
+pub struct StdWriter<T>(pub T);
+
+#[cfg(feature = "std")]
+impl<T: std::io::Write> GenericWrite for StdWriter<T> {
+    type Error = std::io::Error;
+    fn generic_write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.0.write(buf)
+    }
+    fn generic_flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush()
+    }
+}
+
+/// Combined reader and writer whose halves may report errors of different types, not necessarily `std::io::Error`.
+/// Errors of both halves are converted into the common error type `E`.
+///
+/// Available without "std" Cargo feature as well.
+pub struct GenericReadWrite<R, W, E> {
+    r: R,
+    w: W,
+    e: PhantomData<fn() -> E>,
+}
+
+impl<R, W, E> GenericReadWrite<R, W, E>
+where
+    R: GenericRead,
+    W: GenericWrite,
+    R::Error: Into<E>,
+    W::Error: Into<E>,
+{
+    /// Bundle separate reader and writer into a combined pseudo-socket
+    pub fn new(r: R, w: W) -> Self {
+        GenericReadWrite {
+            r,
+            w,
+            e: PhantomData,
+        }
+    }
+    /// Borrow inner objects
+    pub fn borrow(&self) -> (&R, &W) {
+        (&self.r, &self.w)
+    }
+    /// Borrow the reader
+    pub fn borrow_read(&self) -> &R {
+        &self.r
+    }
+    /// Borrow the writer
+    pub fn borrow_write(&self) -> &W {
+        &self.w
+    }
+    /// Mutably borrow inner objects
+    pub fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+        (&mut self.r, &mut self.w)
+    }
+    /// Mutably borrow the reader
+    pub fn borrow_mut_read(&mut self) -> &mut R {
+        &mut self.r
+    }
+    /// Mutably borrow the writer
+    pub fn borrow_mut_write(&mut self) -> &mut W {
+        &mut self.w
+    }
+    /// Convert back into individual reader and writer pair
+    pub fn into_inner(self) -> (R, W) {
+        (self.r, self.w)
+    }
+    /// Convert back into the reader, dropping the writer
+    pub fn into_reader(self) -> R {
+        self.r
+    }
+    /// Convert back into the writer, dropping the reader
+    pub fn into_writer(self) -> W {
+        self.w
+    }
+
+    /// Read from the reader, converting its error into `E`
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, E> {
+        self.r.generic_read(buf).map_err(Into::into)
+    }
+    /// Write to the writer, converting its error into `E`
+    pub fn write(&mut self, buf: &[u8]) -> Result<usize, E> {
+        self.w.generic_write(buf).map_err(Into::into)
+    }
+    /// Flush the writer, converting its error into `E`
+    pub fn flush(&mut self) -> Result<(), E> {
+        self.w.generic_flush().map_err(Into::into)
+    }
+}
+
+impl<R, W, E> crate::Pair for GenericReadWrite<R, W, E>
+where
+    R: GenericRead,
+    W: GenericWrite,
+    R::Error: Into<E>,
+    W::Error: Into<E>,
+{
+    type Reader = R;
+    type Writer = W;
+    fn new(r: R, w: W) -> Self {
+        GenericReadWrite::new(r, w)
+    }
+    fn borrow(&self) -> (&R, &W) {
+        GenericReadWrite::borrow(self)
+    }
+    fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+        GenericReadWrite::borrow_mut(self)
+    }
+    fn into_inner(self) -> (R, W) {
+        GenericReadWrite::into_inner(self)
+    }
+}
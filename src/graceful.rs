@@ -0,0 +1,118 @@
+use std::io::{Read, Result};
+
+use crate::{ReadWrite, ShutdownWrite};
+
+const DRAIN_CHUNK: usize = 8192;
+
+fn drain_limit_exceeded(max_drain: usize) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!(
+            "peer sent more than {} bytes while draining the pseudo-socket",
+            max_drain
+        ),
+    )
+}
+
+/// How many bytes to request in the next drain read so that exceeding the limit is detected
+fn drain_chunk_len(drained: u64, max_drain: usize) -> usize {
+    let remaining = (max_drain as u64 + 1).saturating_sub(drained);
+    remaining.min(DRAIN_CHUNK as u64) as usize
+}
+
+impl<R: Read, W: ShutdownWrite> ReadWrite<R, W> {
+    /// Tear down the pseudo-socket: flush, shut down and drop the writer, then read and discard incoming data until EOF.
+    ///
+    /// The writer is shut down with `ShutdownWrite`, so the peer sees EOF even if the reader shares its connection.
+    ///
+    /// Returns the number of discarded bytes.
+    /// If the peer sends more than `max_drain` bytes, an `ErrorKind::InvalidData` error is returned instead of waiting for EOF.
+    pub fn close_graceful(self, max_drain: usize) -> Result<u64> {
+        let (mut r, mut w) = self.into_inner();
+        w.flush()?;
+        w.shutdown_write()?;
+        drop(w);
+        let mut buf = [0u8; DRAIN_CHUNK];
+        let mut drained: u64 = 0;
+        loop {
+            let len = drain_chunk_len(drained, max_drain);
+            match r.read(&mut buf[..len]) {
+                Ok(0) => return Ok(drained),
+                Ok(n) => drained += n as u64,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+            if drained > max_drain as u64 {
+                return Err(drain_limit_exceeded(max_drain));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+    use super::{drain_chunk_len, drain_limit_exceeded, DRAIN_CHUNK};
+    use crate::ReadWriteTokio;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Tear down the pseudo-socket: flush and shut down the writer, then read and discard incoming data until EOF.
+        ///
+        /// Returns the number of discarded bytes.
+        /// If the peer sends more than `max_drain` bytes, an `ErrorKind::InvalidData` error is returned instead of waiting for EOF.
+        pub async fn close_graceful(self, max_drain: usize) -> std::io::Result<u64> {
+            let mut this = std::pin::pin!(self);
+            this.flush().await?;
+            this.shutdown().await?;
+            let mut buf = [0u8; DRAIN_CHUNK];
+            let mut drained: u64 = 0;
+            loop {
+                let len = drain_chunk_len(drained, max_drain);
+                match this.read(&mut buf[..len]).await {
+                    Ok(0) => return Ok(drained),
+                    Ok(n) => drained += n as u64,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+                if drained > max_drain as u64 {
+                    return Err(drain_limit_exceeded(max_drain));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+    use super::{drain_chunk_len, drain_limit_exceeded, DRAIN_CHUNK};
+    use crate::ReadWriteAsyncstd;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstd<R, W> {
+        /// Tear down the pseudo-socket: flush and close the writer, then read and discard incoming data until EOF.
+        ///
+        /// Returns the number of discarded bytes.
+        /// If the peer sends more than `max_drain` bytes, an `ErrorKind::InvalidData` error is returned instead of waiting for EOF.
+        pub async fn close_graceful(self, max_drain: usize) -> std::io::Result<u64> {
+            let mut this = std::pin::pin!(self);
+            this.flush().await?;
+            this.close().await?;
+            let mut buf = [0u8; DRAIN_CHUNK];
+            let mut drained: u64 = 0;
+            loop {
+                let len = drain_chunk_len(drained, max_drain);
+                match this.read(&mut buf[..len]).await {
+                    Ok(0) => return Ok(drained),
+                    Ok(n) => drained += n as u64,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+                if drained > max_drain as u64 {
+                    return Err(drain_limit_exceeded(max_drain));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use std::io::{Read, Result, Write};
+
+use crate::{ReadWrite, ShutdownWrite};
+
+fn write_closed() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::BrokenPipe,
+        "write half of the pseudo-socket is closed",
+    )
+}
+
+/// Combined reader and writer where each half can be closed (dropped) in place,
+/// while the other half is still in use.
+///
+/// After `close_write` or `drop_write`, writes fail with `ErrorKind::BrokenPipe`.
+/// After `close_read`, reads return `Ok(0)`.
+///
+/// Note that dropping a handle does not always signal EOF to the peer,
+/// e.g. for a `TcpStream` obtained with `try_clone` the connection stays open while other clones are alive.
+/// `close_write` therefore shuts the writer down with `ShutdownWrite` before dropping it.
+pub struct ReadWriteHalfClosable<R: Read, W: Write> {
+    r: Option<R>,
+    w: Option<W>,
+}
+
+impl<R: Read, W: Write> From<ReadWrite<R, W>> for ReadWriteHalfClosable<R, W> {
+    fn from(rw: ReadWrite<R, W>) -> Self {
+        ReadWriteHalfClosable::new(rw.0, rw.1)
+    }
+}
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Convert into a pseudo-socket which allows closing each half in place
+    pub fn half_closable(self) -> ReadWriteHalfClosable<R, W> {
+        self.into()
+    }
+}
+impl<R: Read, W: Write> ReadWriteHalfClosable<R, W> {
+    /// Bundle separate reader and writer into a combined pseudo-socket
+    pub fn new(r: R, w: W) -> Self {
+        ReadWriteHalfClosable {
+            r: Some(r),
+            w: Some(w),
+        }
+    }
+    /// Flush and drop the writer, keeping the reader usable.
+    ///
+    /// This only signals EOF to the peer if the writer owns its connection exclusively.
+    /// Use `close_write` for writers sharing the connection with the reader, e.g. after `ReadWrite::from_stream`.
+    pub fn drop_write(&mut self) -> Result<()> {
+        if let Some(w) = self.w.as_mut() {
+            w.flush()?;
+        }
+        self.w = None;
+        Ok(())
+    }
+    /// Drop the reader, keeping the writer usable
+    pub fn close_read(&mut self) {
+        self.r = None;
+    }
+    /// Check whether `close_write` or `drop_write` was called
+    pub fn is_write_closed(&self) -> bool {
+        self.w.is_none()
+    }
+    /// Check whether `close_read` was called
+    pub fn is_read_closed(&self) -> bool {
+        self.r.is_none()
+    }
+    /// Borrow the reader, unless it is closed
+    pub fn borrow_read(&self) -> Option<&R> {
+        self.r.as_ref()
+    }
+    /// Borrow the writer, unless it is closed
+    pub fn borrow_write(&self) -> Option<&W> {
+        self.w.as_ref()
+    }
+    /// Mutably borrow the reader, unless it is closed
+    pub fn borrow_mut_read(&mut self) -> Option<&mut R> {
+        self.r.as_mut()
+    }
+    /// Mutably borrow the writer, unless it is closed
+    pub fn borrow_mut_write(&mut self) -> Option<&mut W> {
+        self.w.as_mut()
+    }
+    /// Convert back into whichever halves are still open
+    pub fn into_inner(self) -> (Option<R>, Option<W>) {
+        (self.r, self.w)
+    }
+}
+
+impl<R: Read, W: ShutdownWrite> ReadWriteHalfClosable<R, W> {
+    /// Flush the writer, signal EOF to the peer with `ShutdownWrite::shutdown_write` and drop the writer,
+    /// keeping the reader usable.
+    ///
+    /// Unlike `drop_write`, the peer sees EOF even if the reader is a clone of the same socket.
+    /// On error the writer is kept, so that closing can be retried.
+    pub fn close_write(&mut self) -> Result<()> {
+        if let Some(w) = self.w.as_mut() {
+            w.flush()?;
+            w.shutdown_write()?;
+        }
+        self.w = None;
+        Ok(())
+    }
+}
+
+impl<R: Read, W: Write> Read for ReadWriteHalfClosable<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self.r.as_mut() {
+            Some(r) => r.read(buf),
+            None => Ok(0),
+        }
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<usize> {
+        match self.r.as_mut() {
+            Some(r) => r.read_vectored(bufs),
+            None => Ok(0),
+        }
+    }
+}
+impl<R: Read, W: Write> Write for ReadWriteHalfClosable<R, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        match self.w.as_mut() {
+            Some(w) => w.write(buf),
+            None => Err(write_closed()),
+        }
+    }
+    fn flush(&mut self) -> Result<()> {
+        match self.w.as_mut() {
+            Some(w) => w.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize> {
+        match self.w.as_mut() {
+            Some(w) => w.write_vectored(bufs),
+            None => Err(write_closed()),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    pin_project_lite::pin_project! {
+        /// Combined async reader and writer where each half can be closed in place, `tokio 1` version.
+        ///
+        /// After the write half is closed, writes fail with `ErrorKind::BrokenPipe`.
+        /// After the read half is closed, reads signal EOF.
+        ///
+        /// There is no conversion from `ReadWriteTokio`, as reader teardown and shutdown notification
+        /// could not be honoured. Take its halves with `ReadWriteTokio::into_inner` instead.
+        pub struct ReadWriteTokioHalfClosable<R, W> {
+            #[pin]
+            r: Option<R>,
+            #[pin]
+            w: Option<W>,
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokioHalfClosable<R, W> {
+        /// Bundle separate async reader and writer into a combined pseudo-socket
+        pub fn new(r: R, w: W) -> Self {
+            ReadWriteTokioHalfClosable {
+                r: Some(r),
+                w: Some(w),
+            }
+        }
+        /// Drive `poll_shutdown` of the writer to completion, then drop it.
+        /// The reader stays usable.
+        pub fn poll_close_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let mut w = self.project().w;
+            if let Some(inner) = w.as_mut().as_pin_mut() {
+                std::task::ready!(inner.poll_shutdown(cx))?;
+            }
+            w.set(None);
+            Poll::Ready(Ok(()))
+        }
+        /// Shut down and drop the writer, keeping the reader usable
+        pub async fn close_write(&mut self) -> std::io::Result<()>
+        where
+            Self: Unpin,
+        {
+            std::future::poll_fn(|cx| Pin::new(&mut *self).poll_close_write(cx)).await
+        }
+        /// Drop the reader, keeping the writer usable
+        pub fn close_read(&mut self)
+        where
+            Self: Unpin,
+        {
+            Pin::new(self).close_read_pinned()
+        }
+        /// Like `close_read`, for pinned pseudo-sockets
+        pub fn close_read_pinned(self: Pin<&mut Self>) {
+            self.project().r.set(None);
+        }
+        /// Check whether the write half is closed
+        pub fn is_write_closed(&self) -> bool {
+            self.w.is_none()
+        }
+        /// Check whether the read half is closed
+        pub fn is_read_closed(&self) -> bool {
+            self.r.is_none()
+        }
+        /// Borrow the reader, unless it is closed
+        pub fn borrow_read(&self) -> Option<&R> {
+            self.r.as_ref()
+        }
+        /// Borrow the writer, unless it is closed
+        pub fn borrow_write(&self) -> Option<&W> {
+            self.w.as_ref()
+        }
+        /// Convert back into whichever halves are still open
+        pub fn into_inner(self) -> (Option<R>, Option<W>) {
+            (self.r, self.w)
+        }
+    }
+
+    impl<R: AsyncRead, W> AsyncRead for ReadWriteTokioHalfClosable<R, W> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut tokio_dep::io::ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            match self.project().r.as_pin_mut() {
+                Some(r) => r.poll_read(cx, buf),
+                None => Poll::Ready(Ok(())),
+            }
+        }
+    }
+
+    impl<R, W: AsyncWrite> AsyncWrite for ReadWriteTokioHalfClosable<R, W> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            match self.project().w.as_pin_mut() {
+                Some(w) => w.poll_write(cx, buf),
+                None => Poll::Ready(Err(super::write_closed())),
+            }
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            match self.project().w.as_pin_mut() {
+                Some(w) => w.poll_flush(cx),
+                None => Poll::Ready(Ok(())),
+            }
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            match self.project().w.as_pin_mut() {
+                Some(w) => w.poll_shutdown(cx),
+                None => Poll::Ready(Ok(())),
+            }
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[std::io::IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            match self.project().w.as_pin_mut() {
+                Some(w) => w.poll_write_vectored(cx, bufs),
+                None => Poll::Ready(Err(super::write_closed())),
+            }
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            match self.w.as_ref() {
+                Some(w) => w.is_write_vectored(),
+                None => false,
+            }
+        }
+    }
+}
+#[cfg(feature = "tokio")]
+pub use self::tokio::ReadWriteTokioHalfClosable;
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use crate::ReadWriteAsyncstd;
+
+    pin_project_lite::pin_project! {
+        /// Combined async reader and writer where each half can be closed in place, `futures 0.3` version.
+        ///
+        /// After the write half is closed, writes fail with `ErrorKind::BrokenPipe`.
+        /// After the read half is closed, reads signal EOF.
+        pub struct ReadWriteAsyncstdHalfClosable<R, W> {
+            #[pin]
+            r: Option<R>,
+            #[pin]
+            w: Option<W>,
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> From<ReadWriteAsyncstd<R, W>>
+        for ReadWriteAsyncstdHalfClosable<R, W>
+    {
+        fn from(rw: ReadWriteAsyncstd<R, W>) -> Self {
+            let (r, w) = rw.into_inner();
+            ReadWriteAsyncstdHalfClosable::new(r, w)
+        }
+    }
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstd<R, W> {
+        /// Convert into a pseudo-socket which allows closing each half in place
+        pub fn half_closable(self) -> ReadWriteAsyncstdHalfClosable<R, W> {
+            self.into()
+        }
+    }
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstdHalfClosable<R, W> {
+        /// Bundle separate async reader and writer into a combined pseudo-socket
+        pub fn new(r: R, w: W) -> Self {
+            ReadWriteAsyncstdHalfClosable {
+                r: Some(r),
+                w: Some(w),
+            }
+        }
+        /// Drive `poll_close` of the writer to completion, then drop it.
+        /// The reader stays usable.
+        pub fn poll_close_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let mut w = self.project().w;
+            if let Some(inner) = w.as_mut().as_pin_mut() {
+                std::task::ready!(inner.poll_close(cx))?;
+            }
+            w.set(None);
+            Poll::Ready(Ok(()))
+        }
+        /// Close and drop the writer, keeping the reader usable
+        pub async fn close_write(&mut self) -> std::io::Result<()>
+        where
+            Self: Unpin,
+        {
+            std::future::poll_fn(|cx| Pin::new(&mut *self).poll_close_write(cx)).await
+        }
+        /// Drop the reader, keeping the writer usable
+        pub fn close_read(&mut self)
+        where
+            Self: Unpin,
+        {
+            Pin::new(self).close_read_pinned()
+        }
+        /// Like `close_read`, for pinned pseudo-sockets
+        pub fn close_read_pinned(self: Pin<&mut Self>) {
+            self.project().r.set(None);
+        }
+        /// Check whether the write half is closed
+        pub fn is_write_closed(&self) -> bool {
+            self.w.is_none()
+        }
+        /// Check whether the read half is closed
+        pub fn is_read_closed(&self) -> bool {
+            self.r.is_none()
+        }
+        /// Borrow the reader, unless it is closed
+        pub fn borrow_read(&self) -> Option<&R> {
+            self.r.as_ref()
+        }
+        /// Borrow the writer, unless it is closed
+        pub fn borrow_write(&self) -> Option<&W> {
+            self.w.as_ref()
+        }
+        /// Convert back into whichever halves are still open
+        pub fn into_inner(self) -> (Option<R>, Option<W>) {
+            (self.r, self.w)
+        }
+    }
+
+    impl<R: AsyncRead, W> AsyncRead for ReadWriteAsyncstdHalfClosable<R, W> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            match self.project().r.as_pin_mut() {
+                Some(r) => r.poll_read(cx, buf),
+                None => Poll::Ready(Ok(0)),
+            }
+        }
+
+        fn poll_read_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &mut [std::io::IoSliceMut<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            match self.project().r.as_pin_mut() {
+                Some(r) => r.poll_read_vectored(cx, bufs),
+                None => Poll::Ready(Ok(0)),
+            }
+        }
+    }
+
+    impl<R, W: AsyncWrite> AsyncWrite for ReadWriteAsyncstdHalfClosable<R, W> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            match self.project().w.as_pin_mut() {
+                Some(w) => w.poll_write(cx, buf),
+                None => Poll::Ready(Err(super::write_closed())),
+            }
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            match self.project().w.as_pin_mut() {
+                Some(w) => w.poll_flush(cx),
+                None => Poll::Ready(Ok(())),
+            }
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            match self.project().w.as_pin_mut() {
+                Some(w) => w.poll_close(cx),
+                None => Poll::Ready(Ok(())),
+            }
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[std::io::IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            match self.project().w.as_pin_mut() {
+                Some(w) => w.poll_write_vectored(cx, bufs),
+                None => Poll::Ready(Err(super::write_closed())),
+            }
+        }
+    }
+}
+#[cfg(feature = "asyncstd")]
+pub use self::asyncstd::ReadWriteAsyncstdHalfClosable;
@@ -1,25 +1,247 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
 
 //! Given two things, one of which implements `std::io::Read` and other implements `std::io::Write`, make a single socket-like object which implmenets `Read + Write`. Note that you can't write to it while waiting for data to come from read part.
+//! For `ReadWriteTokio`, `split_async` gives separate handles that can be used from different tasks.
 //!
-//! There is also AsyncRead / AsyncWrite analogue, see `ReadWriteAsync` struct.
+//! There are also async analogues, each targeting one family of traits:
+//!
+//! * `ReadWriteTokio` ("tokio" Cargo feature): `tokio::io::AsyncRead` / `AsyncWrite`;
+//! * `ReadWriteAsyncstd` ("asyncstd" Cargo feature): `futures::io::AsyncRead` / `AsyncWrite`.
+//!   `async_std::io::Read` / `Write` are re-exports of the same traits, so no separate feature is needed for `async-std`.
+//!
+//! With "std" Cargo feature (enabled by default) turned off, the crate is `no_std`.
+//! Only `GenericReadWrite` and `ReadWriteEmbedded` for `embedded-io` traits are available then.
 
+#[cfg(feature = "std")]
 use std::io::{Read, Result, Write};
+#[cfg(feature = "std")]
+use std::process::{Child, ChildStderr, ChildStdin, ChildStdout};
+
+#[cfg(feature = "blocking")]
+mod blocking;
+#[cfg(feature = "std")]
+mod buffered;
+#[cfg(feature = "bytes")]
+mod bytes_io;
+#[cfg(any(feature = "tokio", feature = "asyncstd"))]
+mod cancel;
+#[cfg(feature = "compio")]
+mod compio;
+#[cfg(feature = "std")]
+mod copy;
+#[cfg(feature = "std")]
+mod cork;
+#[cfg(feature = "std")]
+mod echo;
+#[cfg(feature = "embedded-io")]
+mod embedded;
+#[cfg(feature = "std")]
+mod eof;
+#[cfg(feature = "std")]
+mod flush_on_drop;
+#[cfg(feature = "std")]
+mod fns;
+mod generic;
+#[cfg(feature = "std")]
+mod graceful;
+#[cfg(feature = "std")]
+mod half_close;
+#[cfg(feature = "std")]
+mod limit;
+#[cfg(feature = "test-util")]
+mod mock;
+#[cfg(feature = "monoio")]
+mod monoio;
+#[cfg(feature = "std")]
+mod one_sided;
+#[cfg(feature = "std")]
+mod peek;
+#[cfg(feature = "net")]
+mod ready;
+#[cfg(feature = "std")]
+mod retry;
+#[cfg(all(feature = "std", unix))]
+mod same;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+mod shutdown;
+#[cfg(all(feature = "splice", target_os = "linux"))]
+mod splice;
+#[cfg(feature = "std")]
+mod sync_timeout;
+#[cfg(feature = "std")]
+mod tagged;
+#[cfg(feature = "std")]
+mod tee;
+#[cfg(feature = "std")]
+mod throttle;
+#[cfg(all(feature = "timeout", any(feature = "tokio", feature = "asyncstd")))]
+mod timeout;
+#[cfg(feature = "tracing")]
+mod traced;
+#[cfg(feature = "std")]
+mod transcript;
+#[cfg(feature = "std")]
+mod unsplit;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "std")]
+mod write_error;
+#[cfg(feature = "blocking")]
+pub use blocking::ReadWriteTokioBlocking;
+#[cfg(feature = "std")]
+pub use buffered::UnbufferError;
+#[cfg(any(feature = "tokio", feature = "asyncstd"))]
+pub use cancel::Cancellable;
+#[cfg(feature = "compio")]
+pub use compio::ReadWriteCompio;
+#[cfg(feature = "tokio")]
+pub use copy::copy_bidirectional_tokio;
+#[cfg(feature = "std")]
+pub use copy::{copy_bidirectional, CopyBidirectionalError};
+#[cfg(feature = "std")]
+pub use cork::Corkable;
+#[cfg(feature = "asyncstd")]
+pub use cork::CorkableAsyncstd;
+#[cfg(feature = "tokio")]
+pub use cork::CorkableTokio;
+#[cfg(feature = "std")]
+pub use echo::{echo, echo_with, Echo};
+#[cfg(feature = "embedded-io")]
+pub use embedded::{EitherError, ReadWriteEmbedded};
+#[cfg(feature = "std")]
+pub use eof::EofAsError;
+#[cfg(feature = "std")]
+pub use flush_on_drop::FlushOnDrop;
+#[cfg(any(feature = "tokio", feature = "asyncstd"))]
+pub use fns::NoopPoll;
+#[cfg(feature = "std")]
+pub use fns::{from_fns, from_fns_with_flush, FnReader, FnWriter, NoopFlush};
+#[cfg(feature = "asyncstd")]
+pub use fns::{from_poll_fns_asyncstd, FnReaderAsyncstd, FnWriterAsyncstd};
+#[cfg(feature = "tokio")]
+pub use fns::{from_poll_fns_tokio, FnReaderTokio, FnWriterTokio};
+pub use generic::{GenericRead, GenericReadWrite, GenericWrite};
+#[cfg(feature = "std")]
+pub use generic::{StdReader, StdWriter};
+#[cfg(feature = "asyncstd")]
+pub use half_close::ReadWriteAsyncstdHalfClosable;
+#[cfg(feature = "std")]
+pub use half_close::ReadWriteHalfClosable;
+#[cfg(feature = "tokio")]
+pub use half_close::ReadWriteTokioHalfClosable;
+#[cfg(feature = "std")]
+pub use limit::{Limited, QuotaHandle};
+#[cfg(feature = "test-util")]
+pub use mock::MockReadWrite;
+#[cfg(feature = "monoio")]
+pub use monoio::ReadWriteMonoio;
+#[cfg(feature = "std")]
+pub use one_sided::{NoReader, NoWriter, OnMissingRead, OnMissingWrite};
+#[cfg(feature = "std")]
+pub use peek::Peek;
+#[cfg(feature = "tokio")]
+pub use peek::{AsyncPeek, PeekBuffer};
+#[cfg(feature = "net")]
+pub use ready::{PollReadReady, PollWriteReady};
+#[cfg(feature = "std")]
+pub use retry::RetryingReadWrite;
+#[cfg(feature = "std")]
+pub use shared::{SharedReadWrite, SharedReader, SharedWriter};
+#[cfg(feature = "std")]
+pub use shutdown::ShutdownWrite;
+#[cfg(feature = "std")]
+pub use sync_timeout::{SetReadTimeout, SetWriteTimeout};
+#[cfg(feature = "std")]
+pub use tagged::{DirectionalError, Tagged};
+#[cfg(feature = "std")]
+pub use tee::TeeWriter;
+#[cfg(feature = "std")]
+pub use throttle::ThrottledReadWrite;
+#[cfg(all(feature = "timeout", feature = "asyncstd"))]
+pub use timeout::ReadWriteAsyncstdWithTimeout;
+#[cfg(all(feature = "timeout", feature = "tokio"))]
+pub use timeout::ReadWriteTokioWithTimeout;
+#[cfg(feature = "tracing")]
+pub use traced::Traced;
+#[cfg(feature = "std")]
+pub use transcript::{
+    Direction, Recorder, ReplayMismatch, Replayer, TranscriptReader, TranscriptRecord,
+    TranscriptWriter,
+};
+#[cfg(feature = "std")]
+pub use unsplit::{Unsplit, UnsplitError};
+#[cfg(feature = "wasm")]
+pub use wasm::ReadWriteWasm;
+#[cfg(feature = "std")]
+pub use write_error::WriteError;
+
+/// Common interface of all combined reader and writer structs in this crate,
+/// for generic code that works regardless of the I/O traits or runtime in use.
+///
+/// Inherent methods of the same names remain available on each struct.
+/// The trait is not sealed, so it may also be implemented for other pair-like types.
+pub trait Pair: Sized {
+    /// Type of the reader half
+    type Reader;
+    /// Type of the writer half
+    type Writer;
+    /// Bundle separate reader and writer into a combined pseudo-socket
+    fn new(r: Self::Reader, w: Self::Writer) -> Self;
+    /// Borrow inner objects
+    fn borrow(&self) -> (&Self::Reader, &Self::Writer);
+    /// Mutably borrow inner objects
+    fn borrow_mut(&mut self) -> (&mut Self::Reader, &mut Self::Writer);
+    /// Convert back into individual reader and writer pair
+    fn into_inner(self) -> (Self::Reader, Self::Writer);
+    /// Convert back into the reader, dropping the writer
+    fn into_reader(self) -> Self::Reader {
+        self.into_inner().0
+    }
+    /// Convert back into the writer, dropping the reader
+    fn into_writer(self) -> Self::Writer {
+        self.into_inner().1
+    }
+}
 
 /// Combined reader and writer
+///
+/// `ReadWrite<R, W>` is `Send` if both `R` and `W` are `Send`, and `Sync` if both are `Sync`.
+/// Use `ReadWrite::new_send` to have the compiler check the former at construction.
+#[cfg(feature = "std")]
 pub struct ReadWrite<R: Read, W: Write>(pub R, pub W);
 
+#[cfg(feature = "std")]
 impl<R: Read, W: Write> From<(R, W)> for ReadWrite<R, W> {
     fn from((r, w): (R, W)) -> Self {
         ReadWrite(r, w)
     }
 }
+#[cfg(feature = "std")]
 impl<R: Read, W: Write> ReadWrite<R, W> {
     /// Bundle separate reader and writer into a combined pseudo-socket
     pub fn new(r: R, w: W) -> Self {
         ReadWrite(r, w)
     }
+    /// Like `new`, but only accepts halves that are `Send`, so the result is guaranteed to be `Send`.
+    /// Useful to make the intent explicit in generic code.
+    pub fn new_send(r: R, w: W) -> Self
+    where
+        R: Send,
+        W: Send,
+    {
+        ReadWrite(r, w)
+    }
+    /// Bundle fallibly constructed reader and writer, returning the first error encountered
+    pub fn try_new<E>(
+        r: std::result::Result<R, E>,
+        w: std::result::Result<W, E>,
+    ) -> std::result::Result<Self, E> {
+        Ok(ReadWrite(r?, w?))
+    }
     /// Borrow inner objects
     pub fn borrow(&self) -> (&R, &W) {
         (&self.0, &self.1)
@@ -44,6 +266,12 @@ impl<R: Read, W: Write> ReadWrite<R, W> {
     pub fn borrow_mut_write(&mut self) -> &mut W {
         &mut self.1
     }
+    /// Mutably borrow inner objects as trait objects, e.g. for APIs taking `&mut dyn Read` and `&mut dyn Write`.
+    ///
+    /// There is no shared-reference counterpart, as `&dyn Read` cannot be read from.
+    pub fn as_dyn_mut(&mut self) -> (&mut dyn Read, &mut dyn Write) {
+        (&mut self.0, &mut self.1)
+    }
     /// Convert ReadWrite back into individual reader and writer pair
     pub fn into_inner(self) -> (R, W) {
         (self.0, self.1)
@@ -56,8 +284,301 @@ impl<R: Read, W: Write> ReadWrite<R, W> {
     pub fn into_writer(self) -> W {
         self.1
     }
+    /// Replace the reader with a transformed one, e.g. wrap it in a `BufReader`
+    pub fn map_read<R2: Read>(self, f: impl FnOnce(R) -> R2) -> ReadWrite<R2, W> {
+        ReadWrite(f(self.0), self.1)
+    }
+    /// Replace the writer with a transformed one, e.g. wrap it in a `BufWriter`
+    pub fn map_write<W2: Write>(self, f: impl FnOnce(W) -> W2) -> ReadWrite<R, W2> {
+        ReadWrite(self.0, f(self.1))
+    }
+    /// Continue reading from `next` once the current reader reaches EOF, using `Read::chain`.
+    /// The writer is left intact.
+    pub fn chain_read<R2: Read>(self, next: R2) -> ReadWrite<std::io::Chain<R, R2>, W> {
+        self.map_read(|r| r.chain(next))
+    }
+    /// Transform both the reader and the writer
+    pub fn map_both<R2: Read, W2: Write>(
+        self,
+        fr: impl FnOnce(R) -> R2,
+        fw: impl FnOnce(W) -> W2,
+    ) -> ReadWrite<R2, W2> {
+        ReadWrite(fr(self.0), fw(self.1))
+    }
+    /// Read until all of `bufs` are completely filled, using as many `read_vectored` calls as needed.
+    ///
+    /// Fails with `ErrorKind::UnexpectedEof` if the reader ends before that, in which case the contents of `bufs` are unspecified.
+    /// Note that `bufs` is modified to track the progress.
+    pub fn read_exact_vectored(&mut self, mut bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<()> {
+        std::io::IoSliceMut::advance_slices(&mut bufs, 0);
+        while !bufs.is_empty() {
+            match self.0.read_vectored(bufs) {
+                Ok(0) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffers",
+                    ))
+                }
+                Ok(n) => std::io::IoSliceMut::advance_slices(&mut bufs, n),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+    /// Read the rest of the data from the reader until EOF and return it.
+    /// Only the reader is used, so the writer stays usable, e.g. to reply afterwards.
+    pub fn drain_reader(&mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.0.read_to_end(&mut data)?;
+        Ok(data)
+    }
+    /// Write the whole buffer, then flush the writer, so that buffered data is not left behind.
+    /// An error from either step is returned.
+    pub fn write_all_and_flush(&mut self, buf: &[u8]) -> Result<()> {
+        self.write_all(buf)?;
+        self.1.flush()
+    }
+}
+
+/// `ReadWrite` with type-erased halves, so that different transports can be handled as one concrete type
+#[cfg(feature = "std")]
+pub type BoxedReadWrite<'a> = ReadWrite<Box<dyn Read + Send + 'a>, Box<dyn Write + Send + 'a>>;
+
+#[cfg(feature = "std")]
+impl<R: Read, W: Write> Pair for ReadWrite<R, W> {
+    type Reader = R;
+    type Writer = W;
+    fn new(r: R, w: W) -> Self {
+        ReadWrite::new(r, w)
+    }
+    fn borrow(&self) -> (&R, &W) {
+        ReadWrite::borrow(self)
+    }
+    fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+        ReadWrite::borrow_mut(self)
+    }
+    fn into_inner(self) -> (R, W) {
+        ReadWrite::into_inner(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read + Send, W: Write + Send> ReadWrite<R, W> {
+    /// Erase concrete reader and writer types by boxing each half.
+    /// Useful for storing heterogeneous streams in one collection.
+    pub fn boxed<'a>(self) -> BoxedReadWrite<'a>
+    where
+        R: 'a,
+        W: 'a,
+    {
+        ReadWrite(Box::new(self.0), Box::new(self.1))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Like `boxed`, but for halves that are not `Send`
+    pub fn boxed_local<'a>(self) -> ReadWrite<Box<dyn Read + 'a>, Box<dyn Write + 'a>>
+    where
+        R: 'a,
+        W: 'a,
+    {
+        ReadWrite(Box::new(self.0), Box::new(self.1))
+    }
+}
+
+#[cfg(feature = "std")]
+fn missing_child_pipe(name: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!(
+            "child process has no {} pipe (was it spawned with Stdio::piped()?)",
+            name
+        ),
+    )
+}
+
+#[cfg(feature = "std")]
+impl ReadWrite<ChildStdout, ChildStdin> {
+    /// Take stdout and stdin pipes out of a child process and combine them.
+    /// Both must have been configured with `Stdio::piped()`, otherwise an error is returned and the child is left untouched.
+    pub fn from_child(child: &mut Child) -> Result<Self> {
+        if child.stdout.is_none() {
+            return Err(missing_child_pipe("stdout"));
+        }
+        if child.stdin.is_none() {
+            return Err(missing_child_pipe("stdin"));
+        }
+        Ok(ReadWrite(
+            child.stdout.take().unwrap(),
+            child.stdin.take().unwrap(),
+        ))
+    }
+    /// Like `from_child`, but also take the stderr pipe out of the child process
+    pub fn from_child_with_stderr(child: &mut Child) -> Result<(Self, ChildStderr)> {
+        if child.stderr.is_none() {
+            return Err(missing_child_pipe("stderr"));
+        }
+        let rw = ReadWrite::from_child(child)?;
+        Ok((rw, child.stderr.take().unwrap()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl ReadWrite<std::io::StdinLock<'static>, std::io::StdoutLock<'static>> {
+    /// Combine locked stdin and stdout of the current process, e.g. for filters or language servers.
+    /// Other threads cannot use stdin or stdout while this object is alive.
+    pub fn stdio() -> Self {
+        ReadWrite(std::io::stdin().lock(), std::io::stdout().lock())
+    }
+}
+
+#[cfg(feature = "std")]
+impl ReadWrite<std::io::Stdin, std::io::Stdout> {
+    /// Combine stdin and stdout of the current process without holding their locks
+    pub fn stdio_unlocked() -> Self {
+        ReadWrite(std::io::stdin(), std::io::stdout())
+    }
+}
+
+#[cfg(feature = "std")]
+impl ReadWrite<std::net::TcpStream, std::net::TcpStream> {
+    /// Connect to a TCP server and split the connection into a pseudo-socket with `from_stream`
+    pub fn connect<A: std::net::ToSocketAddrs>(addr: A) -> Result<Self> {
+        ReadWrite::from_stream(std::net::TcpStream::connect(addr)?)
+    }
+    /// Like `connect`, but each resolved address is tried with the given connection timeout.
+    /// The error of the last attempt is returned if none succeeds.
+    pub fn connect_timeout<A: std::net::ToSocketAddrs>(
+        addr: A,
+        timeout: std::time::Duration,
+    ) -> Result<Self> {
+        let mut last_error = None;
+        for addr in addr.to_socket_addrs()? {
+            match std::net::TcpStream::connect_timeout(&addr, timeout) {
+                Ok(s) => return ReadWrite::from_stream(s),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "could not resolve to any addresses",
+            )
+        }))
+    }
+}
+
+/// Objects that can produce an independently owned handle to the same underlying resource, like `TcpStream::try_clone`
+///
+/// Implemented for std sockets, files and child process pipes.
+/// Implement it for your own transports to use them with `ReadWrite::from_stream` and `ReadWrite::try_clone`.
+#[cfg(feature = "std")]
+pub trait TryClone: Sized {
+    /// Create a new handle referring to the same underlying resource
+    fn try_clone(&self) -> Result<Self>;
+}
+
+#[cfg(feature = "std")]
+impl TryClone for std::net::TcpStream {
+    fn try_clone(&self) -> Result<Self> {
+        std::net::TcpStream::try_clone(self)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(unix)]
+impl TryClone for std::os::unix::net::UnixStream {
+    fn try_clone(&self) -> Result<Self> {
+        std::os::unix::net::UnixStream::try_clone(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryClone for std::net::UdpSocket {
+    fn try_clone(&self) -> Result<Self> {
+        std::net::UdpSocket::try_clone(self)
+    }
 }
 
+#[cfg(feature = "std")]
+impl TryClone for std::fs::File {
+    fn try_clone(&self) -> Result<Self> {
+        std::fs::File::try_clone(self)
+    }
+}
+
+/// Implement `TryClone` for child process pipes by duplicating the underlying file descriptor or handle
+#[cfg(feature = "std")]
+macro_rules! try_clone_child_pipe {
+    ($t:ty) => {
+        #[cfg(unix)]
+        impl TryClone for $t {
+            fn try_clone(&self) -> Result<Self> {
+                use std::os::unix::io::AsFd;
+                Ok(self.as_fd().try_clone_to_owned()?.into())
+            }
+        }
+        #[cfg(windows)]
+        impl TryClone for $t {
+            fn try_clone(&self) -> Result<Self> {
+                use std::os::windows::io::AsHandle;
+                Ok(self.as_handle().try_clone_to_owned()?.into())
+            }
+        }
+    };
+}
+#[cfg(feature = "std")]
+try_clone_child_pipe!(ChildStdin);
+#[cfg(feature = "std")]
+try_clone_child_pipe!(ChildStdout);
+#[cfg(feature = "std")]
+try_clone_child_pipe!(ChildStderr);
+
+#[cfg(feature = "std")]
+impl<R: Read + TryClone, W: Write + TryClone> ReadWrite<R, W> {
+    /// Create another pseudo-socket referring to the same reader and writer resources,
+    /// e.g. to hand it to a logger thread
+    pub fn try_clone(&self) -> Result<Self> {
+        Ok(ReadWrite(self.0.try_clone()?, self.1.try_clone()?))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read + TryClone, W: Write + TryClone> TryClone for ReadWrite<R, W> {
+    fn try_clone(&self) -> Result<Self> {
+        ReadWrite::try_clone(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Read + Write + TryClone> ReadWrite<S, S> {
+    /// Split a single bidirectional stream into independent read and write handles by cloning it.
+    /// The reader is the clone, the writer is the original.
+    ///
+    /// For `TcpStream` both handles refer to the same socket, which is only closed when the last of them is dropped,
+    /// so dropping one half (e.g. with `into_reader`) does not close the connection prematurely.
+    /// Use `TcpStream::shutdown` to signal EOF to the peer explicitly.
+    pub fn from_stream(s: S) -> Result<Self> {
+        Ok(ReadWrite(s.try_clone()?, s))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Read + Write + Clone> ReadWrite<S, S> {
+    /// Use a single bidirectional stream as both halves, putting a clone of it into each slot.
+    /// The reader is the clone, the writer is the original.
+    ///
+    /// Meant for handles that are cheap to clone and share the underlying connection, like `&TcpStream`
+    /// or `Arc`-based streams. `flush` only affects the writer slot.
+    /// The connection stays open as long as either clone is alive, so dropping one half does not close it.
+    pub fn duplex_single(s: S) -> Self {
+        ReadWrite(s.clone(), s)
+    }
+}
+
+#[cfg(feature = "std")]
 impl<R: Read, W: Write> Read for ReadWrite<R, W> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
         self.0.read(buf)
@@ -67,6 +588,7 @@ impl<R: Read, W: Write> Read for ReadWrite<R, W> {
         self.0.read_vectored(bufs)
     }
 }
+#[cfg(feature = "std")]
 impl<R: Read, W: Write> Write for ReadWrite<R, W> {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
         self.1.write(buf)
@@ -78,34 +600,159 @@ impl<R: Read, W: Write> Write for ReadWrite<R, W> {
     fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize> {
         self.1.write_vectored(bufs)
     }
+
+    /// Fails with `ErrorKind::WriteZero` as soon as the writer returns `Ok(0)` mid-buffer.
+    /// See `ReadWrite::write_retry` for tolerating such writers.
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        retry::write_all_retrying(&mut self.1, buf, 0)
+    }
+}
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, W: Write> std::io::BufRead for ReadWrite<R, W> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.0.fill_buf()
+    }
+    fn consume(&mut self, amt: usize) {
+        self.0.consume(amt)
+    }
+}
+
+/// Shared-reference I/O, like `Read for &TcpStream`.
+/// Allows reading from one thread and writing from another, e.g. with `ReadWrite<TcpStream, TcpStream>` behind an `Arc`.
+#[cfg(feature = "std")]
+impl<'a, R: Read, W: Write> Read for &'a ReadWrite<R, W>
+where
+    &'a R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (&self.0).read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<usize> {
+        (&self.0).read_vectored(bufs)
+    }
 }
+/// Shared-reference I/O, like `Write for &TcpStream`
+#[cfg(feature = "std")]
+impl<'a, R: Read, W: Write> Write for &'a ReadWrite<R, W>
+where
+    &'a W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        (&self.1).write(buf)
+    }
+    fn flush(&mut self) -> Result<()> {
+        (&self.1).flush()
+    }
 
-#[cfg(all(feature = "tokio"))]
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize> {
+        (&self.1).write_vectored(bufs)
+    }
+}
+
+#[cfg(feature = "tokio")]
 mod tokio {
-    use tokio_dep::io::{AsyncRead, AsyncWrite};
+    use tokio_dep::io::{AsyncRead, AsyncWrite, AsyncWriteExt, DuplexStream, ReadHalf, WriteHalf};
+    #[cfg(feature = "stdio")]
+    use tokio_dep::io::{Stdin, Stdout};
+    #[cfg(feature = "from_child")]
+    use tokio_dep::process::{Child, ChildStderr, ChildStdin, ChildStdout};
 
     use std::pin::Pin;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll};
 
     pin_project_lite::pin_project! {
         /// Combined async reader and writer, `tokio 1` version.
         /// Note that this struct is only present in `readwrite` if "tokio" Cargo feature is enabled.
+        ///
+        /// Vectored writes are forwarded to the writer, and `is_write_vectored` reports the writer's capability,
+        /// so callers can avoid coalescing buffers when the writer handles them natively.
+        ///
+        /// `poll_flush` is skipped if nothing was written since the last successful flush.
+        /// Any mutable access to the writer (e.g. `borrow_mut_write`, `borrow_pin` or `project_halves`) counts as writing.
+        /// Reader-only accessors like `borrow_pin_read` do not, and keep the elision working.
+        /// `poll_shutdown` always flushes the writer before shutting it down.
+        ///
+        /// `ReadWriteTokio<R, W>` is `Unpin` if both `R` and `W` are `Unpin`.
         pub struct ReadWriteTokio<R, W> {
             #[pin]
             r: R,
             #[pin]
             w: W,
+            read_teardown: Option<ReadTeardown<R>>,
+            read_shutdown_hook: Option<ReadShutdownHook<R>>,
+            // Whether the writer may hold unflushed data
+            write_dirty: bool,
         }
     }
 
+    type ReadShutdownHook<R> = fn(Pin<&mut R>, &mut Context<'_>) -> Poll<std::io::Result<()>>;
+
+    /// Readers which need finalization when the combined object is shut down,
+    /// e.g. a decompressor that buffers data.
+    ///
+    /// Rust cannot detect whether the reader implements this trait,
+    /// so it has to be enabled explicitly with `ReadWriteTokio::notify_reader_on_shutdown`.
+    pub trait OnShutdown {
+        /// Called from `ReadWriteTokio::poll_shutdown` until it returns `Ready`, before the writer is shut down
+        fn poll_on_shutdown(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<std::io::Result<()>>;
+    }
+
+    /// Boxed closure applied to the reader after the writer is shut down.
+    /// Wrapped in a `Mutex` only to keep `ReadWriteTokio` `Sync`; it is never actually locked.
+    type ReadTeardown<R> = Mutex<Box<dyn FnOnce(Pin<&mut R>) + Send>>;
+
     impl<R: AsyncRead, W: AsyncWrite> From<(R, W)> for ReadWriteTokio<R, W> {
         fn from((r, w): (R, W)) -> Self {
-            ReadWriteTokio { r, w }
+            ReadWriteTokio {
+                r,
+                w,
+                read_teardown: None,
+                read_shutdown_hook: None,
+                write_dirty: true,
+            }
         }
     }
     impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
         /// Bundle separate async reader and writer into a combined pseudo-socket
         pub fn new(r: R, w: W) -> Self {
-            ReadWriteTokio { r, w }
+            ReadWriteTokio {
+                r,
+                w,
+                read_teardown: None,
+                read_shutdown_hook: None,
+                write_dirty: true,
+            }
+        }
+        /// Bundle separate async reader and writer into a combined pseudo-socket
+        /// which also tears down the reader when shut down.
+        ///
+        /// After `poll_shutdown` of the writer completes successfully, `teardown` is applied to the reader (only once).
+        /// This is useful when the other side only exits after seeing both halves closed, e.g. for child processes.
+        pub fn new_shutdown_both<F>(r: R, w: W, teardown: F) -> Self
+        where
+            F: FnOnce(Pin<&mut R>) + Send + 'static,
+        {
+            ReadWriteTokio {
+                r,
+                w,
+                read_teardown: Some(Mutex::new(Box::new(teardown))),
+                read_shutdown_hook: None,
+                write_dirty: true,
+            }
+        }
+        /// Call `OnShutdown::poll_on_shutdown` of the reader when this object is shut down,
+        /// before shutting down the writer.
+        pub fn notify_reader_on_shutdown(mut self) -> Self
+        where
+            R: OnShutdown,
+        {
+            self.read_shutdown_hook = Some(R::poll_on_shutdown);
+            self
         }
         /// Borrow inner objects
         pub fn borrow(&self) -> (&R, &W) {
@@ -121,6 +768,7 @@ mod tokio {
         }
         /// Mutably borrow inner objects
         pub fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+            self.write_dirty = true;
             (&mut self.r, &mut self.w)
         }
         /// Mutably borrow the reader
@@ -129,6 +777,7 @@ mod tokio {
         }
         /// Mutably borrow the writer
         pub fn borrow_mut_write(&mut self) -> &mut W {
+            self.write_dirty = true;
             &mut self.w
         }
         /// Convert ReadWrite back into individual reader and writer pair
@@ -143,10 +792,58 @@ mod tokio {
         pub fn into_writer(self) -> W {
             self.w
         }
+        /// Replace the reader with a transformed one, e.g. wrap it in a `BufReader`.
+        /// Reader teardown or shutdown notification set up for the old reader is discarded.
+        pub fn map_read<R2>(self, f: impl FnOnce(R) -> R2) -> ReadWriteTokio<R2, W> {
+            ReadWriteTokio {
+                r: f(self.r),
+                w: self.w,
+                read_teardown: None,
+                read_shutdown_hook: None,
+                write_dirty: self.write_dirty,
+            }
+        }
+        /// Replace the writer with a transformed one, e.g. wrap it in a `BufWriter`
+        pub fn map_write<W2>(self, f: impl FnOnce(W) -> W2) -> ReadWriteTokio<R, W2> {
+            ReadWriteTokio {
+                r: self.r,
+                w: f(self.w),
+                read_teardown: self.read_teardown,
+                read_shutdown_hook: self.read_shutdown_hook,
+                write_dirty: true,
+            }
+        }
+        /// Transform both the reader and the writer.
+        /// Reader teardown or shutdown notification set up for the old reader is discarded.
+        pub fn map_both<R2, W2>(
+            self,
+            fr: impl FnOnce(R) -> R2,
+            fw: impl FnOnce(W) -> W2,
+        ) -> ReadWriteTokio<R2, W2> {
+            ReadWriteTokio {
+                r: fr(self.r),
+                w: fw(self.w),
+                read_teardown: None,
+                read_shutdown_hook: None,
+                write_dirty: true,
+            }
+        }
 
         /// Borrow pinned reader and writer
         pub fn borrow_pin(self: Pin<&mut Self>) -> (Pin<&mut R>, Pin<&mut W>) {
+            self.project_halves()
+        }
+        /// Project pinned pseudo-socket into its pinned halves, for hand-written `poll_*` implementations.
+        ///
+        /// The reader and the writer are structurally pinned: once the combined object is pinned,
+        /// neither half is ever moved until it is dropped, unless it is `Unpin`.
+        /// Both projections may be used at the same time, as they refer to disjoint fields.
+        ///
+        /// As the writer may be written through, the next `poll_flush` is forwarded to it.
+        /// Use `borrow_pin_read` for reader-only access.
+        pub fn project_halves(self: Pin<&mut Self>) -> (Pin<&mut R>, Pin<&mut W>) {
             let p = self.project();
+            *p.write_dirty = true;
             (p.r, p.w)
         }
         /// Borrow pinned reader
@@ -155,7 +852,39 @@ mod tokio {
         }
         /// Borrow pinned writer
         pub fn borrow_pin_write(self: Pin<&mut Self>) -> Pin<&mut W> {
-            self.project().w
+            let p = self.project();
+            *p.write_dirty = true;
+            p.w
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Split into independently owned read and write handles with `tokio::io::split`,
+        /// so that one task can read while another one writes.
+        ///
+        /// Both handles share this object through an `Arc`, so reader teardown and other settings are kept.
+        /// Join them back with `ReadHalf::unsplit`, or by combining them with `ReadWriteTokio::new` and calling `unsplit`.
+        pub fn split_async(self) -> (ReadHalf<Self>, WriteHalf<Self>) {
+            tokio_dep::io::split(self)
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite + Unpin> ReadWriteTokio<R, W> {
+        /// Write the whole buffer, then flush the writer, so that buffered data is not left behind.
+        /// An error from either step is returned.
+        pub async fn write_all_and_flush(&mut self, buf: &[u8]) -> std::io::Result<()> {
+            self.w.write_all(buf).await?;
+            self.w.flush().await
+        }
+    }
+
+    #[cfg(feature = "bytes")]
+    impl<R: AsyncRead + Unpin, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Read from the reader into a possibly uninitialized `BufMut`, advancing it by the number of bytes read.
+        /// Returns that number, zero meaning EOF (or a full buffer).
+        /// Note that this method is only present in `readwrite` if "bytes" Cargo feature is enabled.
+        pub async fn read_buf(&mut self, buf: &mut impl bytes::BufMut) -> std::io::Result<usize> {
+            tokio_dep::io::AsyncReadExt::read_buf(&mut self.r, buf).await
         }
     }
 
@@ -175,21 +904,42 @@ mod tokio {
             cx: &mut std::task::Context<'_>,
             buf: &[u8],
         ) -> std::task::Poll<Result<usize, std::io::Error>> {
-            self.project().w.poll_write(cx, buf)
+            let p = self.project();
+            *p.write_dirty = true;
+            p.w.poll_write(cx, buf)
         }
 
+        /// Does nothing if nothing was written since the last successful flush
         fn poll_flush(
             self: Pin<&mut Self>,
             cx: &mut std::task::Context<'_>,
         ) -> std::task::Poll<Result<(), std::io::Error>> {
-            self.project().w.poll_flush(cx)
+            let p = self.project();
+            if !*p.write_dirty {
+                return std::task::Poll::Ready(Ok(()));
+            }
+            std::task::ready!(p.w.poll_flush(cx))?;
+            *p.write_dirty = false;
+            std::task::Poll::Ready(Ok(()))
         }
 
         fn poll_shutdown(
             self: Pin<&mut Self>,
             cx: &mut std::task::Context<'_>,
         ) -> std::task::Poll<Result<(), std::io::Error>> {
-            self.project().w.poll_shutdown(cx)
+            let mut p = self.project();
+            if let Some(hook) = *p.read_shutdown_hook {
+                std::task::ready!(hook(p.r.as_mut(), cx))?;
+                *p.read_shutdown_hook = None;
+            }
+            std::task::ready!(p.w.as_mut().poll_flush(cx))?;
+            *p.write_dirty = false;
+            std::task::ready!(p.w.poll_shutdown(cx))?;
+            if let Some(teardown) = p.read_teardown.take() {
+                let teardown = teardown.into_inner().unwrap_or_else(|e| e.into_inner());
+                teardown(p.r);
+            }
+            std::task::Poll::Ready(Ok(()))
         }
 
         fn poll_write_vectored(
@@ -197,43 +947,159 @@ mod tokio {
             cx: &mut std::task::Context<'_>,
             bufs: &[std::io::IoSlice<'_>],
         ) -> std::task::Poll<Result<usize, std::io::Error>> {
-            self.project().w.poll_write_vectored(cx, bufs)
+            let p = self.project();
+            *p.write_dirty = true;
+            p.w.poll_write_vectored(cx, bufs)
         }
 
         fn is_write_vectored(&self) -> bool {
             self.w.is_write_vectored()
         }
     }
+
+    #[cfg(feature = "from_child")]
+    impl ReadWriteTokio<ChildStdout, ChildStdin> {
+        /// Take stdout and stdin pipes out of a tokio child process and combine them.
+        /// Both must have been configured with `Stdio::piped()`, otherwise an error is returned and the child is left untouched.
+        ///
+        /// Requires "from_child" Cargo feature.
+        pub fn from_child(child: &mut Child) -> std::io::Result<Self> {
+            if child.stdout.is_none() {
+                return Err(crate::missing_child_pipe("stdout"));
+            }
+            if child.stdin.is_none() {
+                return Err(crate::missing_child_pipe("stdin"));
+            }
+            Ok(ReadWriteTokio::new(
+                child.stdout.take().unwrap(),
+                child.stdin.take().unwrap(),
+            ))
+        }
+        /// Like `from_child`, but also take the stderr pipe out of the child process
+        pub fn from_child_with_stderr(child: &mut Child) -> std::io::Result<(Self, ChildStderr)> {
+            if child.stderr.is_none() {
+                return Err(crate::missing_child_pipe("stderr"));
+            }
+            let rw = ReadWriteTokio::from_child(child)?;
+            Ok((rw, child.stderr.take().unwrap()))
+        }
+    }
+
+    #[cfg(feature = "stdio")]
+    impl ReadWriteTokio<Stdin, Stdout> {
+        /// Combine `tokio::io::stdin()` and `tokio::io::stdout()` of the current process.
+        /// Requires "stdio" Cargo feature.
+        ///
+        /// Note that tokio implements stdin using blocking reads on a separate thread.
+        /// A pending read cannot be cancelled, so an abandoned read may still consume input
+        /// and may keep the runtime from shutting down until a line arrives or stdin is closed.
+        pub fn stdio() -> Self {
+            ReadWriteTokio::new(tokio_dep::io::stdin(), tokio_dep::io::stdout())
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> crate::Pair for ReadWriteTokio<R, W> {
+        type Reader = R;
+        type Writer = W;
+        fn new(r: R, w: W) -> Self {
+            ReadWriteTokio::new(r, w)
+        }
+        fn borrow(&self) -> (&R, &W) {
+            ReadWriteTokio::borrow(self)
+        }
+        fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+            ReadWriteTokio::borrow_mut(self)
+        }
+        fn into_inner(self) -> (R, W) {
+            ReadWriteTokio::into_inner(self)
+        }
+    }
+
+    /// `ReadWriteTokio` with type-erased halves, so that different transports can be handled as one concrete type
+    pub type BoxedReadWriteTokio<'a> =
+        ReadWriteTokio<Pin<Box<dyn AsyncRead + Send + 'a>>, Pin<Box<dyn AsyncWrite + Send + 'a>>>;
+
+    impl<R: AsyncRead + Send, W: AsyncWrite + Send> ReadWriteTokio<R, W> {
+        /// Erase concrete reader and writer types by pinning each half in a `Box`.
+        /// Reader teardown or shutdown notification is discarded.
+        pub fn boxed<'a>(self) -> BoxedReadWriteTokio<'a>
+        where
+            R: 'a,
+            W: 'a,
+        {
+            ReadWriteTokio::new(Box::pin(self.r), Box::pin(self.w))
+        }
+    }
+
+    /// Create two connected in-memory async pseudo-sockets, built on `tokio::io::duplex`.
+    /// Data written to one endpoint can be read from the other one, in both directions.
+    ///
+    /// `max_buf_size` limits the amount of buffered data in each direction.
+    pub fn duplex_tokio(
+        max_buf_size: usize,
+    ) -> (
+        ReadWriteTokio<DuplexStream, DuplexStream>,
+        ReadWriteTokio<DuplexStream, DuplexStream>,
+    ) {
+        let (a_to_b_w, a_to_b_r) = tokio_dep::io::duplex(max_buf_size);
+        let (b_to_a_w, b_to_a_r) = tokio_dep::io::duplex(max_buf_size);
+        (
+            ReadWriteTokio::new(b_to_a_r, a_to_b_w),
+            ReadWriteTokio::new(a_to_b_r, b_to_a_w),
+        )
+    }
 }
-#[cfg(all(feature = "tokio"))]
-pub use tokio::ReadWriteTokio;
+#[cfg(feature = "tokio")]
+pub use tokio::{duplex_tokio, BoxedReadWriteTokio, OnShutdown, ReadWriteTokio};
 
-#[cfg(all(feature = "asyncstd"))]
+#[cfg(feature = "asyncstd")]
 mod asyncstd {
-    use futures::io::{AsyncRead, AsyncWrite};
+    use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 
     use std::pin::Pin;
 
     pin_project_lite::pin_project! {
         /// Combined async reader and writer, `futures 0.3` version.
         /// Note that this struct is only present in `readwrite` if "asyncstd" Cargo feature is enabled.
+        ///
+        /// It also works with `async-std`, whose `async_std::io::Read` and `Write` traits are
+        /// re-exports of `futures::io::AsyncRead` and `AsyncWrite`.
+        ///
+        /// `ReadWriteAsyncstd<R, W>` is `Unpin` if both `R` and `W` are `Unpin`.
+        ///
+        /// `poll_close` flushes the writer before closing it.
+        /// Once closing succeeds, further `poll_close` calls return `Ready(Ok(()))` without touching the writer.
         pub struct ReadWriteAsyncstd<R, W> {
             #[pin]
             r: R,
             #[pin]
             w: W,
+            // Set once the flush preceding `poll_close` completed, cleared again by writes
+            flushed_before_close: bool,
+            // Set after a successful `poll_close`, making further closes no-ops
+            closed: bool,
         }
     }
 
     impl<R: AsyncRead, W: AsyncWrite> From<(R, W)> for ReadWriteAsyncstd<R, W> {
         fn from((r, w): (R, W)) -> Self {
-            ReadWriteAsyncstd { r, w }
+            ReadWriteAsyncstd {
+                r,
+                w,
+                flushed_before_close: false,
+                closed: false,
+            }
         }
     }
     impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstd<R, W> {
         /// Bundle separate async reader and writer into a combined pseudo-socket
         pub fn new(r: R, w: W) -> Self {
-            ReadWriteAsyncstd { r, w }
+            ReadWriteAsyncstd {
+                r,
+                w,
+                flushed_before_close: false,
+                closed: false,
+            }
         }
         /// Borrow inner objects
         pub fn borrow(&self) -> (&R, &W) {
@@ -249,6 +1115,7 @@ mod asyncstd {
         }
         /// Mutably borrow inner objects
         pub fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+            self.flushed_before_close = false;
             (&mut self.r, &mut self.w)
         }
         /// Mutably borrow the reader
@@ -257,6 +1124,7 @@ mod asyncstd {
         }
         /// Mutably borrow the writer
         pub fn borrow_mut_write(&mut self) -> &mut W {
+            self.flushed_before_close = false;
             &mut self.w
         }
         /// Convert ReadWrite back into individual reader and writer pair
@@ -271,9 +1139,49 @@ mod asyncstd {
         pub fn into_writer(self) -> W {
             self.w
         }
+        /// Replace the reader with a transformed one, e.g. wrap it in a `BufReader`.
+        /// The writer is kept along with its flushed and closed state.
+        pub fn map_read<R2>(self, f: impl FnOnce(R) -> R2) -> ReadWriteAsyncstd<R2, W> {
+            ReadWriteAsyncstd {
+                r: f(self.r),
+                w: self.w,
+                flushed_before_close: self.flushed_before_close,
+                closed: self.closed,
+            }
+        }
+        /// Replace the writer with a transformed one, e.g. wrap it in a `BufWriter`
+        pub fn map_write<W2>(self, f: impl FnOnce(W) -> W2) -> ReadWriteAsyncstd<R, W2> {
+            ReadWriteAsyncstd {
+                r: self.r,
+                w: f(self.w),
+                flushed_before_close: false,
+                closed: false,
+            }
+        }
+        /// Transform both the reader and the writer
+        pub fn map_both<R2, W2>(
+            self,
+            fr: impl FnOnce(R) -> R2,
+            fw: impl FnOnce(W) -> W2,
+        ) -> ReadWriteAsyncstd<R2, W2> {
+            ReadWriteAsyncstd {
+                r: fr(self.r),
+                w: fw(self.w),
+                flushed_before_close: false,
+                closed: false,
+            }
+        }
 
         /// Borrow pinned reader and writer
         pub fn borrow_pin(self: Pin<&mut Self>) -> (Pin<&mut R>, Pin<&mut W>) {
+            self.project_halves()
+        }
+        /// Project pinned pseudo-socket into its pinned halves, for hand-written `poll_*` implementations.
+        ///
+        /// The reader and the writer are structurally pinned: once the combined object is pinned,
+        /// neither half is ever moved until it is dropped, unless it is `Unpin`.
+        /// Both projections may be used at the same time, as they refer to disjoint fields.
+        pub fn project_halves(self: Pin<&mut Self>) -> (Pin<&mut R>, Pin<&mut W>) {
             let p = self.project();
             (p.r, p.w)
         }
@@ -283,7 +1191,52 @@ mod asyncstd {
         }
         /// Borrow pinned writer
         pub fn borrow_pin_write(self: Pin<&mut Self>) -> Pin<&mut W> {
-            self.project().w
+            let p = self.project();
+            *p.flushed_before_close = false;
+            p.w
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite + Unpin> ReadWriteAsyncstd<R, W> {
+        /// Write the whole buffer, then flush the writer, so that buffered data is not left behind.
+        /// An error from either step is returned.
+        pub async fn write_all_and_flush(&mut self, buf: &[u8]) -> std::io::Result<()> {
+            self.w.write_all(buf).await?;
+            self.w.flush().await
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> crate::Pair for ReadWriteAsyncstd<R, W> {
+        type Reader = R;
+        type Writer = W;
+        fn new(r: R, w: W) -> Self {
+            ReadWriteAsyncstd::new(r, w)
+        }
+        fn borrow(&self) -> (&R, &W) {
+            ReadWriteAsyncstd::borrow(self)
+        }
+        fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+            ReadWriteAsyncstd::borrow_mut(self)
+        }
+        fn into_inner(self) -> (R, W) {
+            ReadWriteAsyncstd::into_inner(self)
+        }
+    }
+
+    /// `ReadWriteAsyncstd` with type-erased halves, so that different transports can be handled as one concrete type
+    pub type BoxedReadWriteAsyncstd<'a> = ReadWriteAsyncstd<
+        Pin<Box<dyn AsyncRead + Send + 'a>>,
+        Pin<Box<dyn AsyncWrite + Send + 'a>>,
+    >;
+
+    impl<R: AsyncRead + Send, W: AsyncWrite + Send> ReadWriteAsyncstd<R, W> {
+        /// Erase concrete reader and writer types by pinning each half in a `Box`
+        pub fn boxed<'a>(self) -> BoxedReadWriteAsyncstd<'a>
+        where
+            R: 'a,
+            W: 'a,
+        {
+            ReadWriteAsyncstd::new(Box::pin(self.r), Box::pin(self.w))
         }
     }
 
@@ -311,7 +1264,9 @@ mod asyncstd {
             cx: &mut std::task::Context<'_>,
             buf: &[u8],
         ) -> std::task::Poll<std::io::Result<usize>> {
-            self.project().w.poll_write(cx, buf)
+            let p = self.project();
+            *p.flushed_before_close = false;
+            p.w.poll_write(cx, buf)
         }
 
         fn poll_flush(
@@ -325,7 +1280,17 @@ mod asyncstd {
             self: Pin<&mut Self>,
             cx: &mut std::task::Context<'_>,
         ) -> std::task::Poll<std::io::Result<()>> {
-            self.project().w.poll_close(cx)
+            let mut p = self.project();
+            if *p.closed {
+                return std::task::Poll::Ready(Ok(()));
+            }
+            if !*p.flushed_before_close {
+                std::task::ready!(p.w.as_mut().poll_flush(cx))?;
+                *p.flushed_before_close = true;
+            }
+            std::task::ready!(p.w.poll_close(cx))?;
+            *p.closed = true;
+            std::task::Poll::Ready(Ok(()))
         }
 
         fn poll_write_vectored(
@@ -333,9 +1298,44 @@ mod asyncstd {
             cx: &mut std::task::Context<'_>,
             bufs: &[std::io::IoSlice<'_>],
         ) -> std::task::Poll<std::io::Result<usize>> {
-            self.project().w.poll_write_vectored(cx, bufs)
+            let p = self.project();
+            *p.flushed_before_close = false;
+            p.w.poll_write_vectored(cx, bufs)
+        }
+    }
+}
+#[cfg(feature = "asyncstd")]
+pub use asyncstd::{BoxedReadWriteAsyncstd, ReadWriteAsyncstd};
+
+#[cfg(test)]
+mod tests {
+    #[allow(dead_code)]
+    fn is_unpin<T: Unpin>() {}
+
+    // Compile-time check that auto traits are derived from the halves
+    #[cfg(feature = "std")]
+    #[test]
+    fn send_sync() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<crate::ReadWrite<std::io::Cursor<Vec<u8>>, Vec<u8>>>();
+    }
+
+    // Compile-time checks that pin projection keeps `Unpin` derivable
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn tokio_unpin() {
+        fn assert_unpin<R: Unpin, W: Unpin>() {
+            is_unpin::<crate::ReadWriteTokio<R, W>>();
+        }
+        assert_unpin::<tokio_dep::io::Empty, tokio_dep::io::Sink>();
+    }
+
+    #[cfg(feature = "asyncstd")]
+    #[test]
+    fn asyncstd_unpin() {
+        fn assert_unpin<R: Unpin, W: Unpin>() {
+            is_unpin::<crate::ReadWriteAsyncstd<R, W>>();
         }
+        assert_unpin::<futures::io::Empty, futures::io::Sink>();
     }
 }
-#[cfg(all(feature = "asyncstd"))]
-pub use asyncstd::ReadWriteAsyncstd;
@@ -0,0 +1,319 @@
+use std::io::{IoSlice, IoSliceMut, Read, Result, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::ReadWrite;
+
+struct Quotas {
+    read: Option<AtomicU64>,
+    write: Option<AtomicU64>,
+}
+
+/// Shared handle to the remaining byte quotas of a `Limited` pseudo-socket.
+/// Can be used to inspect or refill the quotas while the pseudo-socket is in use.
+#[derive(Clone)]
+pub struct QuotaHandle(Arc<Quotas>);
+
+impl QuotaHandle {
+    /// How many bytes can still be read, `None` if reading is not limited
+    pub fn remaining_read(&self) -> Option<u64> {
+        self.0.read.as_ref().map(|x| x.load(Ordering::SeqCst))
+    }
+    /// How many bytes can still be written, `None` if writing is not limited
+    pub fn remaining_write(&self) -> Option<u64> {
+        self.0.write.as_ref().map(|x| x.load(Ordering::SeqCst))
+    }
+    /// Allow reading `n` more bytes. Does nothing if reading is not limited.
+    pub fn add_read(&self, n: u64) {
+        if let Some(x) = self.0.read.as_ref() {
+            add(x, n)
+        }
+    }
+    /// Allow writing `n` more bytes. Does nothing if writing is not limited.
+    pub fn add_write(&self, n: u64) {
+        if let Some(x) = self.0.write.as_ref() {
+            add(x, n)
+        }
+    }
+    /// Set the remaining read quota. Does nothing if reading is not limited.
+    pub fn set_read(&self, n: u64) {
+        if let Some(x) = self.0.read.as_ref() {
+            x.store(n, Ordering::SeqCst)
+        }
+    }
+    /// Set the remaining write quota. Does nothing if writing is not limited.
+    pub fn set_write(&self, n: u64) {
+        if let Some(x) = self.0.write.as_ref() {
+            x.store(n, Ordering::SeqCst)
+        }
+    }
+}
+
+fn add(x: &AtomicU64, n: u64) {
+    let _ = x.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| {
+        Some(v.saturating_add(n))
+    });
+}
+
+fn consume(x: &Option<AtomicU64>, n: usize) {
+    if let Some(x) = x {
+        let _ = x.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| {
+            Some(v.saturating_sub(n as u64))
+        });
+    }
+}
+
+/// Number of bytes allowed for the next operation, `None` if not limited
+fn allowance(x: &Option<AtomicU64>, wanted: usize) -> Option<usize> {
+    x.as_ref()
+        .map(|x| x.load(Ordering::SeqCst).min(wanted as u64) as usize)
+}
+
+fn read_quota_exhausted() -> std::io::Error {
+    std::io::Error::other("read quota of the pseudo-socket is exhausted")
+}
+
+fn write_quota_exhausted() -> std::io::Error {
+    std::io::Error::other("write quota of the pseudo-socket is exhausted")
+}
+
+fn total_len(bufs: &[IoSlice<'_>]) -> usize {
+    bufs.iter().map(|b| b.len()).sum()
+}
+
+fn truncate_slices<'a>(bufs: &'a [IoSlice<'a>], mut limit: usize) -> Vec<IoSlice<'a>> {
+    let mut ret = Vec::new();
+    for b in bufs {
+        if limit == 0 {
+            break;
+        }
+        let n = b.len().min(limit);
+        ret.push(IoSlice::new(&b[..n]));
+        limit -= n;
+    }
+    ret
+}
+
+/// Pseudo-socket with hard caps on the number of bytes that can be read and written.
+/// Created by `ReadWrite::limited` or `ReadWriteTokio::limited`.
+///
+/// Writes crossing the write quota are truncated to the remaining allowance,
+/// and further writes fail with an `ErrorKind::Other` error.
+/// Reads are truncated the same way; once the read quota is exhausted,
+/// reads return `Ok(0)` (or an `ErrorKind::Other` error, see `set_error_on_read_exhausted`).
+///
+/// Use `quota` to get a handle for inspecting and refilling the quotas.
+///
+/// Async traits are implemented only for `Unpin` inner objects; use `Box::pin` for others.
+pub struct Limited<T> {
+    inner: T,
+    quotas: QuotaHandle,
+    error_on_read_exhausted: bool,
+}
+
+impl<T> Limited<T> {
+    /// Limit reads and writes of the inner pseudo-socket. `None` means no limit for that direction.
+    pub fn new(inner: T, read_quota: Option<u64>, write_quota: Option<u64>) -> Self {
+        Limited {
+            inner,
+            quotas: QuotaHandle(Arc::new(Quotas {
+                read: read_quota.map(AtomicU64::new),
+                write: write_quota.map(AtomicU64::new),
+            })),
+            error_on_read_exhausted: false,
+        }
+    }
+    /// Choose whether reads with exhausted quota should fail instead of signaling EOF
+    pub fn set_error_on_read_exhausted(&mut self, enabled: bool) {
+        self.error_on_read_exhausted = enabled;
+    }
+    /// Get a handle for inspecting and refilling the quotas
+    pub fn quota(&self) -> QuotaHandle {
+        self.quotas.clone()
+    }
+    /// Borrow the limited pseudo-socket
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+    /// Mutably borrow the limited pseudo-socket. I/O done through it is not counted.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+    /// Remove the limits, returning the inner pseudo-socket
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn read_exhausted<X: Default>(&self) -> Result<X> {
+        if self.error_on_read_exhausted {
+            Err(read_quota_exhausted())
+        } else {
+            Ok(X::default())
+        }
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Cap the number of bytes which can be read and written. `None` means no limit for that direction.
+    pub fn limited(self, read_quota: Option<u64>, write_quota: Option<u64>) -> Limited<Self> {
+        Limited::new(self, read_quota, write_quota)
+    }
+}
+
+impl<T: Read> Read for Limited<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = match allowance(&self.quotas.0.read, buf.len()) {
+            None => return self.inner.read(buf),
+            Some(0) if !buf.is_empty() => return self.read_exhausted(),
+            Some(n) => n,
+        };
+        let n = self.inner.read(&mut buf[..n])?;
+        consume(&self.quotas.0.read, n);
+        Ok(n)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        let wanted = bufs.iter().map(|b| b.len()).sum();
+        let mut limit = match allowance(&self.quotas.0.read, wanted) {
+            None => return self.inner.read_vectored(bufs),
+            Some(0) if wanted > 0 => return self.read_exhausted(),
+            Some(n) => n,
+        };
+        let mut truncated = Vec::new();
+        for b in bufs.iter_mut() {
+            if limit == 0 {
+                break;
+            }
+            let n = b.len().min(limit);
+            truncated.push(IoSliceMut::new(&mut b[..n]));
+            limit -= n;
+        }
+        let n = self.inner.read_vectored(&mut truncated)?;
+        consume(&self.quotas.0.read, n);
+        Ok(n)
+    }
+}
+
+impl<T: Write> Write for Limited<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = match allowance(&self.quotas.0.write, buf.len()) {
+            None => return self.inner.write(buf),
+            Some(0) if !buf.is_empty() => return Err(write_quota_exhausted()),
+            Some(n) => n,
+        };
+        let n = self.inner.write(&buf[..n])?;
+        consume(&self.quotas.0.write, n);
+        Ok(n)
+    }
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        let wanted = total_len(bufs);
+        let n = match allowance(&self.quotas.0.write, wanted) {
+            None => return self.inner.write_vectored(bufs),
+            Some(0) if wanted > 0 => return Err(write_quota_exhausted()),
+            Some(n) => n,
+        };
+        let n = self.inner.write_vectored(&truncate_slices(bufs, n))?;
+        consume(&self.quotas.0.write, n);
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use std::io::IoSlice;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{allowance, consume, total_len, truncate_slices, write_quota_exhausted, Limited};
+    use crate::ReadWriteTokio;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Cap the number of bytes which can be read and written. `None` means no limit for that direction.
+        pub fn limited(self, read_quota: Option<u64>, write_quota: Option<u64>) -> Limited<Self> {
+            Limited::new(self, read_quota, write_quota)
+        }
+    }
+
+    impl<T: AsyncRead + Unpin> AsyncRead for Limited<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            let wanted = buf.remaining();
+            let n = match allowance(&this.quotas.0.read, wanted) {
+                None => return Pin::new(&mut this.inner).poll_read(cx, buf),
+                Some(0) if wanted > 0 => return Poll::Ready(this.read_exhausted()),
+                Some(n) => n,
+            };
+            if n == wanted {
+                let before = buf.filled().len();
+                std::task::ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+                consume(&this.quotas.0.read, buf.filled().len() - before);
+                return Poll::Ready(Ok(()));
+            }
+            let mut limited = ReadBuf::new(buf.initialize_unfilled_to(n));
+            std::task::ready!(Pin::new(&mut this.inner).poll_read(cx, &mut limited))?;
+            let filled = limited.filled().len();
+            buf.advance(filled);
+            consume(&this.quotas.0.read, filled);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl<T: AsyncWrite + Unpin> AsyncWrite for Limited<T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            let n = match allowance(&this.quotas.0.write, buf.len()) {
+                None => return Pin::new(&mut this.inner).poll_write(cx, buf),
+                Some(0) if !buf.is_empty() => return Poll::Ready(Err(write_quota_exhausted())),
+                Some(n) => n,
+            };
+            let n = std::task::ready!(Pin::new(&mut this.inner).poll_write(cx, &buf[..n]))?;
+            consume(&this.quotas.0.write, n);
+            Poll::Ready(Ok(n))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            let wanted = total_len(bufs);
+            let n = match allowance(&this.quotas.0.write, wanted) {
+                None => return Pin::new(&mut this.inner).poll_write_vectored(cx, bufs),
+                Some(0) if wanted > 0 => return Poll::Ready(Err(write_quota_exhausted())),
+                Some(n) => n,
+            };
+            let truncated = truncate_slices(bufs, n);
+            let n =
+                std::task::ready!(Pin::new(&mut this.inner).poll_write_vectored(cx, &truncated))?;
+            consume(&this.quotas.0.write, n);
+            Poll::Ready(Ok(n))
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+use std::io::{Read, Result, Write};
+
+/// Scripted fake pseudo-socket for testing protocol code.
+/// Note that this struct is only present in `readwrite` if "test-util" Cargo feature is enabled.
+///
+/// Reads return queued responses in order, then EOF.
+/// Writes must match expected payloads in order, possibly split across several `write` calls;
+/// any unexpected byte causes a panic. Dropping the mock with expected writes still pending also panics.
+#[derive(Debug, Default)]
+pub struct MockReadWrite {
+    reads: VecDeque<Vec<u8>>,
+    writes: VecDeque<Vec<u8>>,
+    written: u64,
+}
+
+struct Escaped<'a>(&'a [u8]);
+
+impl std::fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("b\"")?;
+        for &b in self.0 {
+            write!(f, "{}", std::ascii::escape_default(b))?;
+        }
+        f.write_str("\"")
+    }
+}
+
+impl MockReadWrite {
+    /// Create a mock with nothing scripted: reads return EOF and any write panics
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Queue a response to be returned by reads.
+    /// Empty responses are ignored, EOF is only returned after all responses were read.
+    pub fn with_read(mut self, data: impl Into<Vec<u8>>) -> Self {
+        let data = data.into();
+        if !data.is_empty() {
+            self.reads.push_back(data);
+        }
+        self
+    }
+    /// Expect `data` to be written next. Empty payloads are ignored, as no write can consume them.
+    pub fn expect_write(mut self, data: impl Into<Vec<u8>>) -> Self {
+        let data = data.into();
+        if !data.is_empty() {
+            self.writes.push_back(data);
+        }
+        self
+    }
+    /// Whether all queued responses were read and all expected writes were performed
+    pub fn is_done(&self) -> bool {
+        self.reads.is_empty() && self.writes.is_empty()
+    }
+}
+
+impl Read for MockReadWrite {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let Some(chunk) = self.reads.front_mut() else {
+            return Ok(0);
+        };
+        let n = buf.len().min(chunk.len());
+        buf[..n].copy_from_slice(&chunk[..n]);
+        chunk.drain(..n);
+        if chunk.is_empty() {
+            self.reads.pop_front();
+        }
+        Ok(n)
+    }
+}
+
+impl Write for MockReadWrite {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let Some(expected) = self.writes.front_mut() else {
+            panic!(
+                "MockReadWrite: unexpected write of {} after {} bytes, no more writes were scripted",
+                Escaped(buf),
+                self.written,
+            );
+        };
+        let n = buf.len().min(expected.len());
+        if buf[..n] != expected[..n] {
+            panic!(
+                "MockReadWrite: unexpected write payload at offset {}: expected {}, got {}",
+                self.written,
+                Escaped(expected),
+                Escaped(buf),
+            );
+        }
+        expected.drain(..n);
+        if expected.is_empty() {
+            self.writes.pop_front();
+        }
+        self.written += n as u64;
+        Ok(n)
+    }
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for MockReadWrite {
+    fn drop(&mut self) {
+        if !self.writes.is_empty() && !std::thread::panicking() {
+            let pending: Vec<String> = self.writes.iter().map(|w| Escaped(w).to_string()).collect();
+            panic!(
+                "MockReadWrite dropped with {} scripted writes not performed: {}",
+                pending.len(),
+                pending.join(", "),
+            );
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use monoio_dep::buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut};
+use monoio_dep::io::{AsyncReadRent, AsyncWriteRent};
+use monoio_dep::BufResult;
+
+/// Combined async reader and writer, `monoio` version.
+/// Note that this struct is only present in `readwrite` if "monoio" Cargo feature is enabled.
+///
+/// Like with `compio`, `monoio` traits take ownership of buffers, so each operation
+/// is simply forwarded to the respective half.
+pub struct ReadWriteMonoio<R, W> {
+    r: R,
+    w: W,
+}
+
+impl<R: AsyncReadRent, W: AsyncWriteRent> From<(R, W)> for ReadWriteMonoio<R, W> {
+    fn from((r, w): (R, W)) -> Self {
+        ReadWriteMonoio { r, w }
+    }
+}
+impl<R: AsyncReadRent, W: AsyncWriteRent> ReadWriteMonoio<R, W> {
+    /// Bundle separate async reader and writer into a combined pseudo-socket
+    pub fn new(r: R, w: W) -> Self {
+        ReadWriteMonoio { r, w }
+    }
+    /// Borrow inner objects
+    pub fn borrow(&self) -> (&R, &W) {
+        (&self.r, &self.w)
+    }
+    /// Borrow the reader
+    pub fn borrow_read(&self) -> &R {
+        &self.r
+    }
+    /// Borrow the writer
+    pub fn borrow_write(&self) -> &W {
+        &self.w
+    }
+    /// Mutably borrow inner objects
+    pub fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+        (&mut self.r, &mut self.w)
+    }
+    /// Mutably borrow the reader
+    pub fn borrow_mut_read(&mut self) -> &mut R {
+        &mut self.r
+    }
+    /// Mutably borrow the writer
+    pub fn borrow_mut_write(&mut self) -> &mut W {
+        &mut self.w
+    }
+    /// Convert ReadWrite back into individual reader and writer pair
+    pub fn into_inner(self) -> (R, W) {
+        (self.r, self.w)
+    }
+    /// Convert ReadWrite back into the reader, dropping the writer
+    pub fn into_reader(self) -> R {
+        self.r
+    }
+    /// Convert ReadWrite back into the writer, dropping the reader
+    pub fn into_writer(self) -> W {
+        self.w
+    }
+}
+
+impl<R: AsyncReadRent, W: AsyncWriteRent> crate::Pair for ReadWriteMonoio<R, W> {
+    type Reader = R;
+    type Writer = W;
+    fn new(r: R, w: W) -> Self {
+        ReadWriteMonoio::new(r, w)
+    }
+    fn borrow(&self) -> (&R, &W) {
+        ReadWriteMonoio::borrow(self)
+    }
+    fn borrow_mut(&mut self) -> (&mut R, &mut W) {
+        ReadWriteMonoio::borrow_mut(self)
+    }
+    fn into_inner(self) -> (R, W) {
+        ReadWriteMonoio::into_inner(self)
+    }
+}
+
+impl<R: AsyncReadRent, W> AsyncReadRent for ReadWriteMonoio<R, W> {
+    async fn read<B: IoBufMut>(&mut self, buf: B) -> BufResult<usize, B> {
+        self.r.read(buf).await
+    }
+
+    async fn readv<B: IoVecBufMut>(&mut self, buf: B) -> BufResult<usize, B> {
+        self.r.readv(buf).await
+    }
+}
+
+impl<R, W: AsyncWriteRent> AsyncWriteRent for ReadWriteMonoio<R, W> {
+    async fn write<T: IoBuf>(&mut self, buf: T) -> BufResult<usize, T> {
+        self.w.write(buf).await
+    }
+
+    async fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> BufResult<usize, T> {
+        self.w.writev(buf_vec).await
+    }
+
+    async fn flush(&mut self) -> std::io::Result<()> {
+        self.w.flush().await
+    }
+
+    async fn shutdown(&mut self) -> std::io::Result<()> {
+        self.w.shutdown().await
+    }
+}
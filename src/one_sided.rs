@@ -0,0 +1,213 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::ReadWrite;
+
+/// What reads do in a pseudo-socket created with `write_only`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnMissingRead {
+    /// Return end of stream immediately
+    Eof,
+    /// Never complete: sync reads park the thread forever,
+    /// async reads stay pending forever like `std::future::pending`.
+    /// The waker is not stored, as there is nothing that could ever use it.
+    Never,
+    /// Fail with `ErrorKind::Unsupported`
+    Unsupported,
+}
+
+/// What writes do in a pseudo-socket created with `read_only`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnMissingWrite {
+    /// Succeed, discarding the data, like `std::io::sink`
+    Discard,
+    /// Fail with `ErrorKind::BrokenPipe`
+    BrokenPipe,
+}
+
+/// Stand-in for the absent reader of a `write_only` pseudo-socket
+#[derive(Debug, Clone, Copy)]
+pub struct NoReader(pub OnMissingRead);
+
+/// Stand-in for the absent writer of a `read_only` pseudo-socket
+#[derive(Debug, Clone, Copy)]
+pub struct NoWriter(pub OnMissingWrite);
+
+fn read_unsupported() -> Error {
+    Error::new(
+        ErrorKind::Unsupported,
+        "reading from a write-only pseudo-socket",
+    )
+}
+
+fn write_broken_pipe() -> Error {
+    Error::new(
+        ErrorKind::BrokenPipe,
+        "writing to a read-only pseudo-socket",
+    )
+}
+
+impl NoWriter {
+    fn write_len(&self, len: usize) -> Result<usize> {
+        match self.0 {
+            OnMissingWrite::Discard => Ok(len),
+            OnMissingWrite::BrokenPipe => Err(write_broken_pipe()),
+        }
+    }
+}
+
+impl Read for NoReader {
+    fn read(&mut self, _buf: &mut [u8]) -> Result<usize> {
+        match self.0 {
+            OnMissingRead::Eof => Ok(0),
+            OnMissingRead::Never => loop {
+                std::thread::park();
+            },
+            OnMissingRead::Unsupported => Err(read_unsupported()),
+        }
+    }
+}
+
+impl Write for NoWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.write_len(buf.len())
+    }
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<R: Read> ReadWrite<R, NoWriter> {
+    /// Make a pseudo-socket out of just a reader. Writes behave according to `on_write`.
+    pub fn read_only(r: R, on_write: OnMissingWrite) -> Self {
+        ReadWrite::new(r, NoWriter(on_write))
+    }
+}
+
+impl<W: Write> ReadWrite<NoReader, W> {
+    /// Make a pseudo-socket out of just a writer. Reads behave according to `on_read`.
+    ///
+    /// With `OnMissingRead::Never`, reads park the thread forever.
+    pub fn write_only(w: W, on_read: OnMissingRead) -> Self {
+        ReadWrite::new(NoReader(on_read), w)
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{read_unsupported, NoReader, NoWriter, OnMissingRead, OnMissingWrite};
+    use crate::ReadWriteTokio;
+
+    impl<R: AsyncRead> ReadWriteTokio<R, NoWriter> {
+        /// Make a pseudo-socket out of just a reader. Writes behave according to `on_write`.
+        pub fn read_only(r: R, on_write: OnMissingWrite) -> Self {
+            ReadWriteTokio::new(r, NoWriter(on_write))
+        }
+    }
+
+    impl<W: AsyncWrite> ReadWriteTokio<NoReader, W> {
+        /// Make a pseudo-socket out of just a writer. Reads behave according to `on_read`.
+        ///
+        /// With `OnMissingRead::Never`, reads never complete, see `std::future::pending`.
+        pub fn write_only(w: W, on_read: OnMissingRead) -> Self {
+            ReadWriteTokio::new(NoReader(on_read), w)
+        }
+    }
+
+    impl AsyncRead for NoReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            _buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            match self.0 {
+                OnMissingRead::Eof => Poll::Ready(Ok(())),
+                OnMissingRead::Never => Pin::new(&mut std::future::pending()).poll(cx),
+                OnMissingRead::Unsupported => Poll::Ready(Err(read_unsupported())),
+            }
+        }
+    }
+
+    impl AsyncWrite for NoWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(self.write_len(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite};
+
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{read_unsupported, NoReader, NoWriter, OnMissingRead, OnMissingWrite};
+    use crate::ReadWriteAsyncstd;
+
+    impl<R: AsyncRead> ReadWriteAsyncstd<R, NoWriter> {
+        /// Make a pseudo-socket out of just a reader. Writes behave according to `on_write`.
+        pub fn read_only(r: R, on_write: OnMissingWrite) -> Self {
+            ReadWriteAsyncstd::new(r, NoWriter(on_write))
+        }
+    }
+
+    impl<W: AsyncWrite> ReadWriteAsyncstd<NoReader, W> {
+        /// Make a pseudo-socket out of just a writer. Reads behave according to `on_read`.
+        ///
+        /// With `OnMissingRead::Never`, reads never complete, see `std::future::pending`.
+        pub fn write_only(w: W, on_read: OnMissingRead) -> Self {
+            ReadWriteAsyncstd::new(NoReader(on_read), w)
+        }
+    }
+
+    impl AsyncRead for NoReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            _buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            match self.0 {
+                OnMissingRead::Eof => Poll::Ready(Ok(0)),
+                OnMissingRead::Never => Pin::new(&mut std::future::pending()).poll(cx),
+                OnMissingRead::Unsupported => Poll::Ready(Err(read_unsupported())),
+            }
+        }
+    }
+
+    impl AsyncWrite for NoWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(self.write_len(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::io::{Read, Result, Write};
+
+use crate::ReadWrite;
+
+/// Readers which can look at incoming data without consuming it, e.g. for protocol sniffing.
+///
+/// Implemented for `TcpStream` and `UdpSocket` (also behind shared references), and forwarded by `ReadWrite`.
+/// `UnixStream` is not covered, as its `peek` is not stable in std yet.
+///
+/// `peek` takes `&mut self` rather than `&self` like `TcpStream::peek`, because the trait is meant to be
+/// implemented by readers without kernel support as well, which have to fill a lookahead buffer to peek.
+/// To peek through a shared handle, combine `&TcpStream` halves, e.g. with `ReadWrite::duplex_single(&stream)`.
+pub trait Peek {
+    /// Receive data without removing it from the queue, so the next `read` returns it again
+    fn peek(&mut self, buf: &mut [u8]) -> Result<usize>;
+}
+
+impl Peek for std::net::TcpStream {
+    fn peek(&mut self, buf: &mut [u8]) -> Result<usize> {
+        std::net::TcpStream::peek(self, buf)
+    }
+}
+
+impl Peek for &std::net::TcpStream {
+    fn peek(&mut self, buf: &mut [u8]) -> Result<usize> {
+        std::net::TcpStream::peek(self, buf)
+    }
+}
+
+impl Peek for std::net::UdpSocket {
+    fn peek(&mut self, buf: &mut [u8]) -> Result<usize> {
+        std::net::UdpSocket::peek(self, buf)
+    }
+}
+
+impl Peek for &std::net::UdpSocket {
+    fn peek(&mut self, buf: &mut [u8]) -> Result<usize> {
+        std::net::UdpSocket::peek(self, buf)
+    }
+}
+
+impl<R: Read + Peek, W: Write> Peek for ReadWrite<R, W> {
+    fn peek(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.0.peek(buf)
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use crate::ReadWriteTokio;
+
+    /// Async readers which can look at incoming data without consuming it, `tokio 1` version.
+    ///
+    /// Implemented for `PeekBuffer`, which makes any reader peekable,
+    /// and for `tokio::net::TcpStream` if "net" Cargo feature is enabled.
+    pub trait AsyncPeek {
+        /// Receive data into `buf` without removing it from the queue, returning the number of bytes peeked.
+        /// The next read returns the same data again.
+        fn poll_peek(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<usize>>;
+    }
+
+    #[cfg(feature = "net")]
+    impl AsyncPeek for tokio_dep::net::TcpStream {
+        fn poll_peek(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<usize>> {
+            tokio_dep::net::TcpStream::poll_peek(&self, cx, buf)
+        }
+    }
+
+    pin_project_lite::pin_project! {
+        /// Async reader with a lookahead buffer, making any reader peekable.
+        /// Created by `ReadWriteTokio::with_peek_buffer`.
+        ///
+        /// Peeking reads from the inner reader into the buffer, up to its capacity.
+        /// Subsequent reads drain the buffer before reading from the inner reader again.
+        pub struct PeekBuffer<R> {
+            #[pin]
+            inner: R,
+            buf: Vec<u8>,
+            capacity: usize,
+        }
+    }
+
+    impl<R: AsyncRead> PeekBuffer<R> {
+        /// Make `inner` peekable with up to `capacity` bytes of lookahead
+        pub fn new(inner: R, capacity: usize) -> Self {
+            PeekBuffer {
+                inner,
+                buf: Vec::with_capacity(capacity),
+                capacity,
+            }
+        }
+        /// Data peeked, but not read yet
+        pub fn buffer(&self) -> &[u8] {
+            &self.buf
+        }
+        /// Borrow the inner reader
+        pub fn get_ref(&self) -> &R {
+            &self.inner
+        }
+        /// Remove the lookahead buffer, returning the inner reader and data peeked, but not read yet
+        pub fn into_inner(self) -> (R, Vec<u8>) {
+            (self.inner, self.buf)
+        }
+    }
+
+    impl<R: AsyncRead> AsyncRead for PeekBuffer<R> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let this = self.project();
+            if this.buf.is_empty() {
+                return this.inner.poll_read(cx, buf);
+            }
+            let n = this.buf.len().min(buf.remaining());
+            buf.put_slice(&this.buf[..n]);
+            this.buf.drain(..n);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl<R: AsyncRead> AsyncPeek for PeekBuffer<R> {
+        fn poll_peek(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.project();
+            let wanted = buf.remaining().min(*this.capacity);
+            if this.buf.len() < wanted {
+                let old_len = this.buf.len();
+                this.buf.resize(wanted, 0);
+                let mut more = ReadBuf::new(&mut this.buf[old_len..]);
+                let ret = this.inner.poll_read(cx, &mut more);
+                let got = more.filled().len();
+                this.buf.truncate(old_len + got);
+                match ret {
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending if old_len == 0 => return Poll::Pending,
+                    _ => (),
+                }
+            }
+            let n = this.buf.len().min(buf.remaining());
+            buf.put_slice(&this.buf[..n]);
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Make the reader peekable by putting a lookahead buffer of `capacity` bytes in front of it.
+        /// Reader teardown or shutdown notification set up for the old reader is discarded.
+        pub fn with_peek_buffer(self, capacity: usize) -> ReadWriteTokio<PeekBuffer<R>, W> {
+            self.map_read(|r| PeekBuffer::new(r, capacity))
+        }
+    }
+
+    impl<R: AsyncRead + AsyncPeek, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Look at incoming data without consuming it, so the next read returns it again.
+        /// Only available for readers that support peeking, see `AsyncPeek` trait and `with_peek_buffer`.
+        pub fn poll_peek(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<usize>> {
+            self.borrow_pin_read().poll_peek(cx, buf)
+        }
+
+        /// Look at incoming data without consuming it, so the next read returns it again
+        pub async fn peek(&mut self, buf: &mut [u8]) -> std::io::Result<usize>
+        where
+            Self: Unpin,
+        {
+            let mut buf = ReadBuf::new(buf);
+            std::future::poll_fn(|cx| Pin::new(&mut *self).poll_peek(cx, &mut buf)).await
+        }
+    }
+}
+#[cfg(feature = "tokio")]
+pub use self::tokio::{AsyncPeek, PeekBuffer};
@@ -0,0 +1,85 @@
+use tokio_dep::io::{AsyncRead, AsyncWrite};
+use tokio_dep::net::TcpStream;
+
+use std::task::{Context, Poll};
+
+use crate::ReadWriteTokio;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// `tokio` readers which can report read readiness without reading, like `TcpStream::poll_read_ready`.
+///
+/// This trait is sealed: it is implemented for `TcpStream`, and on Unix for `UnixStream` and `pipe::Receiver`.
+/// Note that this trait is only present in `readwrite` if "net" Cargo feature is enabled.
+pub trait PollReadReady: sealed::Sealed {
+    /// Poll for read readiness, see `TcpStream::poll_read_ready`
+    fn poll_read_ready(&self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>>;
+}
+
+/// `tokio` writers which can report write readiness without writing, like `TcpStream::poll_write_ready`.
+///
+/// This trait is sealed: it is implemented for `TcpStream`, and on Unix for `UnixStream` and `pipe::Sender`.
+/// Note that this trait is only present in `readwrite` if "net" Cargo feature is enabled.
+pub trait PollWriteReady: sealed::Sealed {
+    /// Poll for write readiness, see `TcpStream::poll_write_ready`
+    fn poll_write_ready(&self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>>;
+}
+
+macro_rules! impl_ready {
+    (read $t:ty) => {
+        impl PollReadReady for $t {
+            fn poll_read_ready(&self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                <$t>::poll_read_ready(self, cx)
+            }
+        }
+    };
+    (write $t:ty) => {
+        impl PollWriteReady for $t {
+            fn poll_write_ready(&self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                <$t>::poll_write_ready(self, cx)
+            }
+        }
+    };
+}
+
+impl sealed::Sealed for TcpStream {}
+impl_ready!(read TcpStream);
+impl_ready!(write TcpStream);
+
+#[cfg(unix)]
+mod unix {
+    use tokio_dep::net::unix::pipe;
+    use tokio_dep::net::UnixStream;
+
+    use std::task::{Context, Poll};
+
+    use super::{sealed, PollReadReady, PollWriteReady};
+
+    impl sealed::Sealed for UnixStream {}
+    impl_ready!(read UnixStream);
+    impl_ready!(write UnixStream);
+
+    impl sealed::Sealed for pipe::Receiver {}
+    impl_ready!(read pipe::Receiver);
+
+    impl sealed::Sealed for pipe::Sender {}
+    impl_ready!(write pipe::Sender);
+}
+
+impl<R: AsyncRead + PollReadReady, W: AsyncWrite> ReadWriteTokio<R, W> {
+    /// Poll the reader for read readiness without reading.
+    /// Like `TcpStream::poll_read_ready`, only the most recent task is woken.
+    pub fn poll_read_ready(&self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.borrow_read().poll_read_ready(cx)
+    }
+}
+
+impl<R: AsyncRead, W: AsyncWrite + PollWriteReady> ReadWriteTokio<R, W> {
+    /// Poll the writer for write readiness without writing.
+    /// Like `TcpStream::poll_write_ready`, only the most recent task is woken.
+    pub fn poll_write_ready(&self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.borrow_write().poll_write_ready(cx)
+    }
+}
@@ -0,0 +1,110 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::ReadWrite;
+
+/// `ReadWrite` that tolerates a limited number of consecutive zero-length writes
+/// before giving up with `ErrorKind::WriteZero`. Created by `ReadWrite::write_retry`.
+pub struct RetryingReadWrite<R: Read, W: Write> {
+    inner: ReadWrite<R, W>,
+    max_retries: usize,
+}
+
+impl<R: Read, W: Write> RetryingReadWrite<R, W> {
+    /// Retry writes of the given `ReadWrite` up to `max_retries` times in a row
+    /// when the writer makes no progress.
+    pub fn new(inner: ReadWrite<R, W>, max_retries: usize) -> Self {
+        RetryingReadWrite { inner, max_retries }
+    }
+    /// Current retry budget
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+    /// Change retry budget
+    pub fn set_max_retries(&mut self, max_retries: usize) {
+        self.max_retries = max_retries;
+    }
+    /// Borrow the wrapped `ReadWrite`
+    pub fn get_ref(&self) -> &ReadWrite<R, W> {
+        &self.inner
+    }
+    /// Mutably borrow the wrapped `ReadWrite`
+    pub fn get_mut(&mut self) -> &mut ReadWrite<R, W> {
+        &mut self.inner
+    }
+    /// Remove the retry logic, returning the `ReadWrite`
+    pub fn into_inner(self) -> ReadWrite<R, W> {
+        self.inner
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Retry writes that return `Ok(0)` up to `max_retries` times in a row
+    /// (yielding the thread in between) before failing with `ErrorKind::WriteZero`.
+    ///
+    /// Useful with sinks that temporarily accept nothing, e.g. rate-limited ones.
+    pub fn write_retry(self, max_retries: usize) -> RetryingReadWrite<R, W> {
+        RetryingReadWrite::new(self, max_retries)
+    }
+}
+
+/// `write_all` loop with explicit handling of writers that make no progress.
+/// `Interrupted` errors are retried without limit, like in `std`.
+pub(crate) fn write_all_retrying<W: Write + ?Sized>(
+    w: &mut W,
+    mut buf: &[u8],
+    max_retries: usize,
+) -> Result<()> {
+    let mut retries = 0;
+    while !buf.is_empty() {
+        match w.write(buf) {
+            Ok(0) if retries < max_retries => {
+                retries += 1;
+                std::thread::yield_now();
+            }
+            Ok(0) => {
+                return Err(Error::new(
+                    ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ))
+            }
+            Ok(n) => {
+                retries = 0;
+                buf = &buf[n..];
+            }
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+impl<R: Read, W: Write> Read for RetryingReadWrite<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
+}
+
+impl<R: Read, W: Write> Write for RetryingReadWrite<R, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut retries = 0;
+        loop {
+            match self.inner.write(buf)? {
+                0 if !buf.is_empty() && retries < self.max_retries => {
+                    retries += 1;
+                    std::thread::yield_now();
+                }
+                n => return Ok(n),
+            }
+        }
+    }
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        write_all_retrying(&mut self.inner.1, buf, self.max_retries)
+    }
+}
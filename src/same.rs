@@ -0,0 +1,39 @@
+use std::io::{Read, Result, Write};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd};
+
+use crate::ReadWrite;
+
+/// Whether both descriptors refer to the same open file or socket, even if one is a duplicate of the other
+fn same_file(a: BorrowedFd<'_>, b: BorrowedFd<'_>) -> bool {
+    if a.as_raw_fd() == b.as_raw_fd() {
+        return true;
+    }
+    fn id(fd: BorrowedFd<'_>) -> Result<(u64, u64)> {
+        let m = std::fs::File::from(fd.try_clone_to_owned()?).metadata()?;
+        Ok((m.dev(), m.ino()))
+    }
+    match (id(a), id(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+impl<R: Read + AsFd, W: Write> ReadWrite<R, W> {
+    /// Whether the reader of `other` refers to the same file or socket as the reader of `self`,
+    /// e.g. to check in tests that both were created from clones of one `TcpStream`.
+    ///
+    /// Descriptors are compared by device and inode numbers, so duplicates obtained with `try_clone` compare equal.
+    /// Returns `false` if the descriptors cannot be inspected.
+    pub fn same_reader(&self, other: &Self) -> bool {
+        same_file(self.0.as_fd(), other.0.as_fd())
+    }
+}
+
+impl<R: Read, W: Write + AsFd> ReadWrite<R, W> {
+    /// Whether the writer of `other` refers to the same file or socket as the writer of `self`.
+    /// See `same_reader` for details.
+    pub fn same_writer(&self, other: &Self) -> bool {
+        same_file(self.1.as_fd(), other.1.as_fd())
+    }
+}
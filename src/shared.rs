@@ -0,0 +1,201 @@
+use std::io::{Read, Result, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::ReadWrite;
+
+fn lock<T>(m: &Mutex<T>) -> Result<MutexGuard<'_, T>> {
+    m.lock()
+        .map_err(|_| std::io::Error::other("lock of shared pseudo-socket half is poisoned"))
+}
+
+/// Combined reader and writer usable from multiple threads at once.
+///
+/// The reader and the writer are behind separate locks (unless created by `from_shared`), so a blocked read does not prevent writes.
+/// `Read` and `Write` are implemented for `&SharedReadWrite`, and independent handles to each half
+/// can be obtained with `read_handle` and `write_handle`.
+///
+/// Poisoned locks are reported as `io::Error`s.
+pub struct SharedReadWrite<R: Read, W: Write> {
+    r: Arc<Mutex<R>>,
+    w: Arc<Mutex<W>>,
+}
+
+/// Cloneable handle to the reader of `SharedReadWrite`
+pub struct SharedReader<R: Read>(Arc<Mutex<R>>);
+
+/// Cloneable handle to the writer of `SharedReadWrite`
+pub struct SharedWriter<W: Write>(Arc<Mutex<W>>);
+
+impl<R: Read, W: Write> From<ReadWrite<R, W>> for SharedReadWrite<R, W> {
+    fn from(rw: ReadWrite<R, W>) -> Self {
+        SharedReadWrite::new(rw.0, rw.1)
+    }
+}
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Put each half behind its own lock, allowing concurrent reads and writes through shared references
+    pub fn shared(self) -> SharedReadWrite<R, W> {
+        self.into()
+    }
+}
+impl<R: Read, W: Write> SharedReadWrite<R, W> {
+    /// Bundle separate reader and writer into a combined pseudo-socket usable from multiple threads
+    pub fn new(r: R, w: W) -> Self {
+        SharedReadWrite {
+            r: Arc::new(Mutex::new(r)),
+            w: Arc::new(Mutex::new(w)),
+        }
+    }
+    /// Get an independent handle to the reader
+    pub fn read_handle(&self) -> SharedReader<R> {
+        SharedReader(self.r.clone())
+    }
+    /// Get an independent handle to the writer
+    pub fn write_handle(&self) -> SharedWriter<W> {
+        SharedWriter(self.w.clone())
+    }
+    /// Lock and borrow the reader
+    pub fn lock_read(&self) -> Result<MutexGuard<'_, R>> {
+        lock(&self.r)
+    }
+    /// Lock and borrow the writer
+    pub fn lock_write(&self) -> Result<MutexGuard<'_, W>> {
+        lock(&self.w)
+    }
+    /// Convert back into the reader and writer.
+    /// Fails, giving back `self`, if handles obtained from `read_handle` or `write_handle` are still alive.
+    /// Always fails for objects created by `from_shared`, use `try_into_single` for them.
+    pub fn try_into_inner(self) -> std::result::Result<(R, W), Self> {
+        match (Arc::try_unwrap(self.r), Arc::try_unwrap(self.w)) {
+            (Ok(r), Ok(w)) => Ok((
+                r.into_inner().unwrap_or_else(|e| e.into_inner()),
+                w.into_inner().unwrap_or_else(|e| e.into_inner()),
+            )),
+            (r, w) => Err(SharedReadWrite {
+                r: r.map_or_else(|r| r, Arc::new),
+                w: w.map_or_else(|w| w, Arc::new),
+            }),
+        }
+    }
+}
+
+impl<S: Read + Write> SharedReadWrite<S, S> {
+    /// Share a single bidirectional stream between the reader and the writer halves.
+    ///
+    /// Both halves use the same lock, so unlike with `new`, a blocked read holds off writes.
+    pub fn from_shared(s: S) -> Self {
+        let s = Arc::new(Mutex::new(s));
+        SharedReadWrite { r: s.clone(), w: s }
+    }
+    /// Convert back into the single stream given to `from_shared`.
+    ///
+    /// Fails, giving back `self`, if handles obtained from `read_handle` or `write_handle` are still alive,
+    /// or if the halves are not the same stream, i.e. the object was created by `new` or `From<ReadWrite>`.
+    /// Use `try_into_inner` for the latter.
+    pub fn try_into_single(self) -> std::result::Result<S, Self> {
+        if !Arc::ptr_eq(&self.r, &self.w) || Arc::strong_count(&self.r) != 2 {
+            return Err(self);
+        }
+        drop(self.w);
+        match Arc::try_unwrap(self.r) {
+            Ok(s) => Ok(s.into_inner().unwrap_or_else(|e| e.into_inner())),
+            Err(s) => Err(SharedReadWrite { r: s.clone(), w: s }),
+        }
+    }
+}
+
+impl<R: Read> Clone for SharedReader<R> {
+    fn clone(&self) -> Self {
+        SharedReader(self.0.clone())
+    }
+}
+impl<W: Write> Clone for SharedWriter<W> {
+    fn clone(&self) -> Self {
+        SharedWriter(self.0.clone())
+    }
+}
+
+impl<R: Read, W: Write> Read for &SharedReadWrite<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        lock(&self.r)?.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<usize> {
+        lock(&self.r)?.read_vectored(bufs)
+    }
+}
+impl<R: Read, W: Write> Write for &SharedReadWrite<R, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        lock(&self.w)?.write(buf)
+    }
+    fn flush(&mut self) -> Result<()> {
+        lock(&self.w)?.flush()
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize> {
+        lock(&self.w)?.write_vectored(bufs)
+    }
+}
+impl<R: Read, W: Write> Read for SharedReadWrite<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (&*self).read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<usize> {
+        (&*self).read_vectored(bufs)
+    }
+}
+impl<R: Read, W: Write> Write for SharedReadWrite<R, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        (&*self).write(buf)
+    }
+    fn flush(&mut self) -> Result<()> {
+        (&*self).flush()
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize> {
+        (&*self).write_vectored(bufs)
+    }
+}
+
+impl<R: Read> Read for &SharedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        lock(&self.0)?.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<usize> {
+        lock(&self.0)?.read_vectored(bufs)
+    }
+}
+impl<R: Read> Read for SharedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (&*self).read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<usize> {
+        (&*self).read_vectored(bufs)
+    }
+}
+impl<W: Write> Write for &SharedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        lock(&self.0)?.write(buf)
+    }
+    fn flush(&mut self) -> Result<()> {
+        lock(&self.0)?.flush()
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize> {
+        lock(&self.0)?.write_vectored(bufs)
+    }
+}
+impl<W: Write> Write for SharedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        (&*self).write(buf)
+    }
+    fn flush(&mut self) -> Result<()> {
+        (&*self).flush()
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> Result<usize> {
+        (&*self).write_vectored(bufs)
+    }
+}
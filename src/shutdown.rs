@@ -0,0 +1,119 @@
+use std::io::{BufWriter, Read, Result, Write};
+use std::net::{Shutdown, TcpStream};
+
+use crate::ReadWrite;
+
+/// Writers which can signal EOF to the peer while still alive, like `TcpStream::shutdown(Shutdown::Write)`.
+///
+/// Needed when the writer shares its connection with the reader, e.g. after `ReadWrite::from_stream`:
+/// dropping one of the cloned handles does not close the connection.
+///
+/// Implemented for std sockets and references to them, buffered writers around them,
+/// and for writers which close their end when dropped (child process stdin, files), where it does nothing.
+/// Implement it for your own transports to use them with `close_graceful` and `copy_bidirectional`.
+pub trait ShutdownWrite: Write {
+    /// Signal EOF to the peer. Writing afterwards may fail.
+    fn shutdown_write(&mut self) -> Result<()>;
+    /// Shut down the whole underlying connection, so that reads blocked on it in other threads return.
+    /// Used by `copy_bidirectional` to stop the other direction after an error.
+    ///
+    /// Does nothing by default, as most writers do not share anything with a reader.
+    fn shutdown_both(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ShutdownWrite for TcpStream {
+    fn shutdown_write(&mut self) -> Result<()> {
+        TcpStream::shutdown(self, Shutdown::Write)
+    }
+    fn shutdown_both(&mut self) -> Result<()> {
+        TcpStream::shutdown(self, Shutdown::Both)
+    }
+}
+
+impl ShutdownWrite for &TcpStream {
+    fn shutdown_write(&mut self) -> Result<()> {
+        TcpStream::shutdown(self, Shutdown::Write)
+    }
+    fn shutdown_both(&mut self) -> Result<()> {
+        TcpStream::shutdown(self, Shutdown::Both)
+    }
+}
+
+#[cfg(unix)]
+impl ShutdownWrite for std::os::unix::net::UnixStream {
+    fn shutdown_write(&mut self) -> Result<()> {
+        std::os::unix::net::UnixStream::shutdown(self, Shutdown::Write)
+    }
+    fn shutdown_both(&mut self) -> Result<()> {
+        std::os::unix::net::UnixStream::shutdown(self, Shutdown::Both)
+    }
+}
+
+#[cfg(unix)]
+impl ShutdownWrite for &std::os::unix::net::UnixStream {
+    fn shutdown_write(&mut self) -> Result<()> {
+        std::os::unix::net::UnixStream::shutdown(self, Shutdown::Write)
+    }
+    fn shutdown_both(&mut self) -> Result<()> {
+        std::os::unix::net::UnixStream::shutdown(self, Shutdown::Both)
+    }
+}
+
+/// Implement `ShutdownWrite` as a no-op for writers which signal EOF by being dropped
+macro_rules! shutdown_on_drop {
+    ($($t:ty),*) => {
+        $(
+            impl ShutdownWrite for $t {
+                fn shutdown_write(&mut self) -> Result<()> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+shutdown_on_drop!(
+    std::process::ChildStdin,
+    std::fs::File,
+    std::io::Sink,
+    Vec<u8>
+);
+
+impl<W: ShutdownWrite + ?Sized> ShutdownWrite for &mut W {
+    fn shutdown_write(&mut self) -> Result<()> {
+        (**self).shutdown_write()
+    }
+    fn shutdown_both(&mut self) -> Result<()> {
+        (**self).shutdown_both()
+    }
+}
+
+impl<W: ShutdownWrite + ?Sized> ShutdownWrite for Box<W> {
+    fn shutdown_write(&mut self) -> Result<()> {
+        (**self).shutdown_write()
+    }
+    fn shutdown_both(&mut self) -> Result<()> {
+        (**self).shutdown_both()
+    }
+}
+
+impl<W: ShutdownWrite> ShutdownWrite for BufWriter<W> {
+    /// Flush buffered data, then shut down the inner writer
+    fn shutdown_write(&mut self) -> Result<()> {
+        self.flush()?;
+        self.get_mut().shutdown_write()
+    }
+    fn shutdown_both(&mut self) -> Result<()> {
+        self.get_mut().shutdown_both()
+    }
+}
+
+impl<R: Read, W: ShutdownWrite> ShutdownWrite for ReadWrite<R, W> {
+    fn shutdown_write(&mut self) -> Result<()> {
+        self.1.shutdown_write()
+    }
+    fn shutdown_both(&mut self) -> Result<()> {
+        self.1.shutdown_both()
+    }
+}
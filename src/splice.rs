@@ -0,0 +1,46 @@
+use std::io::{ErrorKind, Read, Result, Write};
+use std::os::fd::AsFd;
+
+use rustix::pipe::{splice, SpliceFlags};
+
+use crate::ReadWrite;
+
+const SPLICE_CHUNK: usize = 1 << 16;
+
+impl<R: Read + AsFd, W: Write> ReadWrite<R, W> {
+    /// Move up to `len` bytes from the reader to `dst` with `splice(2)`, without copying them through userspace.
+    /// Note that this method is only present in `readwrite` on Linux with "splice" Cargo feature enabled.
+    ///
+    /// Either the reader or `dst` must be a pipe, otherwise the call fails with `ErrorKind::InvalidInput`.
+    /// Returns the number of bytes moved, zero meaning EOF of the reader.
+    pub fn splice_to<Dst: AsFd>(&self, dst: &Dst, len: usize) -> Result<usize> {
+        Ok(splice(
+            self.0.as_fd(),
+            None,
+            dst.as_fd(),
+            None,
+            len,
+            SpliceFlags::MOVE,
+        )?)
+    }
+
+    /// Copy everything from the reader to `dst` until EOF, returning the number of bytes copied.
+    /// Note that this method is only present in `readwrite` on Linux with "splice" Cargo feature enabled.
+    ///
+    /// Uses `splice_to` if possible. If the kernel refuses to splice between the file descriptors
+    /// before anything was moved, falls back to `std::io::copy`.
+    pub fn copy_spliced<Dst: Write + AsFd>(&mut self, dst: &mut Dst) -> Result<u64> {
+        let mut copied: u64 = 0;
+        loop {
+            match self.splice_to(dst, SPLICE_CHUNK) {
+                Ok(0) => return Ok(copied),
+                Ok(n) => copied += n as u64,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == ErrorKind::InvalidInput && copied == 0 => {
+                    return std::io::copy(&mut self.0, dst)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
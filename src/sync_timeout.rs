@@ -0,0 +1,127 @@
+use std::io::{BufReader, BufWriter, Read, Result, Write};
+use std::net::{TcpStream, UdpSocket};
+use std::time::Duration;
+
+use crate::ReadWrite;
+
+/// Readers with a configurable timeout for blocking reads, like `TcpStream::set_read_timeout`.
+///
+/// Implemented for sockets from `std`, references to them and buffered wrappers around them.
+pub trait SetReadTimeout {
+    /// Set the read timeout. `None` means reads block indefinitely.
+    fn set_read_timeout(&self, dur: Option<Duration>) -> Result<()>;
+    /// Get the read timeout
+    fn read_timeout(&self) -> Result<Option<Duration>>;
+}
+
+/// Writers with a configurable timeout for blocking writes, like `TcpStream::set_write_timeout`.
+///
+/// Implemented for sockets from `std`, references to them and buffered wrappers around them.
+pub trait SetWriteTimeout {
+    /// Set the write timeout. `None` means writes block indefinitely.
+    fn set_write_timeout(&self, dur: Option<Duration>) -> Result<()>;
+    /// Get the write timeout
+    fn write_timeout(&self) -> Result<Option<Duration>>;
+}
+
+macro_rules! impl_for_socket {
+    ($t:ty) => {
+        impl SetReadTimeout for $t {
+            fn set_read_timeout(&self, dur: Option<Duration>) -> Result<()> {
+                <$t>::set_read_timeout(self, dur)
+            }
+            fn read_timeout(&self) -> Result<Option<Duration>> {
+                <$t>::read_timeout(self)
+            }
+        }
+        impl SetWriteTimeout for $t {
+            fn set_write_timeout(&self, dur: Option<Duration>) -> Result<()> {
+                <$t>::set_write_timeout(self, dur)
+            }
+            fn write_timeout(&self) -> Result<Option<Duration>> {
+                <$t>::write_timeout(self)
+            }
+        }
+    };
+}
+
+impl_for_socket!(TcpStream);
+impl_for_socket!(UdpSocket);
+#[cfg(unix)]
+impl_for_socket!(std::os::unix::net::UnixStream);
+
+impl<T: SetReadTimeout + ?Sized> SetReadTimeout for &T {
+    fn set_read_timeout(&self, dur: Option<Duration>) -> Result<()> {
+        (**self).set_read_timeout(dur)
+    }
+    fn read_timeout(&self) -> Result<Option<Duration>> {
+        (**self).read_timeout()
+    }
+}
+
+impl<T: SetWriteTimeout + ?Sized> SetWriteTimeout for &T {
+    fn set_write_timeout(&self, dur: Option<Duration>) -> Result<()> {
+        (**self).set_write_timeout(dur)
+    }
+    fn write_timeout(&self) -> Result<Option<Duration>> {
+        (**self).write_timeout()
+    }
+}
+
+impl<T: SetReadTimeout> SetReadTimeout for BufReader<T> {
+    fn set_read_timeout(&self, dur: Option<Duration>) -> Result<()> {
+        self.get_ref().set_read_timeout(dur)
+    }
+    fn read_timeout(&self) -> Result<Option<Duration>> {
+        self.get_ref().read_timeout()
+    }
+}
+
+impl<T: SetWriteTimeout + Write> SetWriteTimeout for BufWriter<T> {
+    fn set_write_timeout(&self, dur: Option<Duration>) -> Result<()> {
+        self.get_ref().set_write_timeout(dur)
+    }
+    fn write_timeout(&self) -> Result<Option<Duration>> {
+        self.get_ref().write_timeout()
+    }
+}
+
+impl<R: Read + SetReadTimeout, W: Write> ReadWrite<R, W> {
+    /// Set the read timeout of the reader. `None` means reads block indefinitely.
+    pub fn set_read_timeout(&self, dur: Option<Duration>) -> Result<()> {
+        self.0.set_read_timeout(dur)
+    }
+    /// Get the read timeout of the reader
+    pub fn read_timeout(&self) -> Result<Option<Duration>> {
+        self.0.read_timeout()
+    }
+}
+
+impl<R: Read, W: Write + SetWriteTimeout> ReadWrite<R, W> {
+    /// Set the write timeout of the writer. `None` means writes block indefinitely.
+    pub fn set_write_timeout(&self, dur: Option<Duration>) -> Result<()> {
+        self.1.set_write_timeout(dur)
+    }
+    /// Get the write timeout of the writer
+    pub fn write_timeout(&self) -> Result<Option<Duration>> {
+        self.1.write_timeout()
+    }
+}
+
+impl<R: Read + SetReadTimeout, W: Write> SetReadTimeout for ReadWrite<R, W> {
+    fn set_read_timeout(&self, dur: Option<Duration>) -> Result<()> {
+        self.0.set_read_timeout(dur)
+    }
+    fn read_timeout(&self) -> Result<Option<Duration>> {
+        self.0.read_timeout()
+    }
+}
+
+impl<R: Read, W: Write + SetWriteTimeout> SetWriteTimeout for ReadWrite<R, W> {
+    fn set_write_timeout(&self, dur: Option<Duration>) -> Result<()> {
+        self.1.set_write_timeout(dur)
+    }
+    fn write_timeout(&self) -> Result<Option<Duration>> {
+        self.1.write_timeout()
+    }
+}
@@ -0,0 +1,227 @@
+use std::error::Error as StdError;
+use std::io::{Error, IoSlice, IoSliceMut, Read, Result, Write};
+
+use crate::{Direction, ReadWrite};
+
+/// Error of a `Tagged` pseudo-socket, remembering which half it came from.
+///
+/// It is returned wrapped in `std::io::Error` of the same kind as the original error,
+/// so matching on `ErrorKind` keeps working. Use `DirectionalError::find_in` to recover the tag.
+#[derive(Debug)]
+pub struct DirectionalError {
+    /// `Inbound` for errors of the reader, `Outbound` for errors of the writer
+    pub direction: Direction,
+    /// The original error
+    pub source: Error,
+}
+
+impl std::fmt::Display for DirectionalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let half = match self.direction {
+            Direction::Inbound => "reading",
+            Direction::Outbound => "writing",
+        };
+        write!(f, "error {}: {}", half, self.source)
+    }
+}
+
+impl StdError for DirectionalError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl DirectionalError {
+    /// Tag `source` with `direction`, keeping its `ErrorKind`
+    pub fn wrap(direction: Direction, source: Error) -> Error {
+        Error::new(source.kind(), DirectionalError { direction, source })
+    }
+
+    /// Find a `DirectionalError` in the chain of errors starting at `e`, looking inside nested `std::io::Error`s.
+    /// Returns the tag and the original error.
+    pub fn find_in(e: &Error) -> Option<(Direction, &Error)> {
+        let mut cur: Option<&(dyn StdError + 'static)> = Some(e);
+        while let Some(err) = cur {
+            if let Some(tagged) = err.downcast_ref::<DirectionalError>() {
+                return Some((tagged.direction, &tagged.source));
+            }
+            // `source` of `io::Error` skips over its own payload, so look at it explicitly
+            cur = match err.downcast_ref::<Error>().and_then(Error::get_ref) {
+                Some(inner) => Some(inner),
+                None => err.source(),
+            };
+        }
+        None
+    }
+}
+
+/// Wrapper tagging all errors with the direction they came from, see `DirectionalError`.
+/// Created by `tagged` methods or `Tagged::new`.
+pub struct Tagged<T> {
+    inner: T,
+}
+
+impl<T> Tagged<T> {
+    /// Tag errors of `inner`
+    pub fn new(inner: T) -> Self {
+        Tagged { inner }
+    }
+    /// Borrow the inner object
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+    /// Mutably borrow the inner object
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+    /// Stop tagging errors, returning the inner object
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+fn tag_read(e: Error) -> Error {
+    DirectionalError::wrap(Direction::Inbound, e)
+}
+
+fn tag_write(e: Error) -> Error {
+    DirectionalError::wrap(Direction::Outbound, e)
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Tag all errors with the half they came from, see `DirectionalError`
+    pub fn tagged(self) -> Tagged<Self> {
+        Tagged::new(self)
+    }
+}
+
+impl<T: Read> Read for Tagged<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf).map_err(tag_read)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        self.inner.read_vectored(bufs).map_err(tag_read)
+    }
+}
+
+impl<T: Write> Write for Tagged<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf).map_err(tag_write)
+    }
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush().map_err(tag_write)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        self.inner.write_vectored(bufs).map_err(tag_write)
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{tag_read, tag_write, Tagged};
+    use crate::ReadWriteTokio;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Tag all errors with the half they came from, see `DirectionalError`
+        pub fn tagged(self) -> Tagged<Self> {
+            Tagged::new(self)
+        }
+    }
+
+    impl<T: AsyncRead + Unpin> AsyncRead for Tagged<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner)
+                .poll_read(cx, buf)
+                .map_err(tag_read)
+        }
+    }
+
+    impl<T: AsyncWrite + Unpin> AsyncWrite for Tagged<T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut self.get_mut().inner)
+                .poll_write(cx, buf)
+                .map_err(tag_write)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner)
+                .poll_flush(cx)
+                .map_err(tag_write)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner)
+                .poll_shutdown(cx)
+                .map_err(tag_write)
+        }
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{tag_read, tag_write, Tagged};
+    use crate::ReadWriteAsyncstd;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstd<R, W> {
+        /// Tag all errors with the half they came from, see `DirectionalError`
+        pub fn tagged(self) -> Tagged<Self> {
+            Tagged::new(self)
+        }
+    }
+
+    impl<T: AsyncRead + Unpin> AsyncRead for Tagged<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut self.get_mut().inner)
+                .poll_read(cx, buf)
+                .map_err(tag_read)
+        }
+    }
+
+    impl<T: AsyncWrite + Unpin> AsyncWrite for Tagged<T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut self.get_mut().inner)
+                .poll_write(cx, buf)
+                .map_err(tag_write)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner)
+                .poll_flush(cx)
+                .map_err(tag_write)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner)
+                .poll_close(cx)
+                .map_err(tag_write)
+        }
+    }
+}
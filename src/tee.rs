@@ -0,0 +1,79 @@
+use std::io::{Read, Result, Write};
+
+use crate::ReadWrite;
+
+/// Writer which copies everything written to the primary writer into a secondary (tee) writer as well.
+/// Created by `ReadWrite::tee_write`.
+///
+/// Byte counts and errors of the primary writer are returned as is.
+/// Tee errors are propagated by default, after the primary writer has already accepted the data;
+/// use `set_ignore_tee_errors` to disregard them instead.
+pub struct TeeWriter<W: Write, T: Write> {
+    primary: W,
+    tee: T,
+    ignore_tee_errors: bool,
+}
+
+impl<W: Write, T: Write> TeeWriter<W, T> {
+    /// Mirror data written to `primary` into `tee`
+    pub fn new(primary: W, tee: T) -> Self {
+        TeeWriter {
+            primary,
+            tee,
+            ignore_tee_errors: false,
+        }
+    }
+    /// Choose whether failures of the tee writer should be disregarded
+    pub fn set_ignore_tee_errors(&mut self, ignore: bool) {
+        self.ignore_tee_errors = ignore;
+    }
+    /// Borrow the primary writer
+    pub fn borrow_primary(&self) -> &W {
+        &self.primary
+    }
+    /// Borrow the tee writer
+    pub fn borrow_tee(&self) -> &T {
+        &self.tee
+    }
+    /// Mutably borrow the primary writer
+    pub fn borrow_mut_primary(&mut self) -> &mut W {
+        &mut self.primary
+    }
+    /// Mutably borrow the tee writer
+    pub fn borrow_mut_tee(&mut self) -> &mut T {
+        &mut self.tee
+    }
+    /// Convert back into primary and tee writers
+    pub fn into_inner(self) -> (W, T) {
+        (self.primary, self.tee)
+    }
+
+    fn tee_result(&self, r: Result<()>) -> Result<()> {
+        match r {
+            Err(_) if self.ignore_tee_errors => Ok(()),
+            r => r,
+        }
+    }
+}
+
+impl<W: Write, T: Write> Write for TeeWriter<W, T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = self.primary.write(buf)?;
+        let r = self.tee.write_all(&buf[..n]);
+        self.tee_result(r)?;
+        Ok(n)
+    }
+    fn flush(&mut self) -> Result<()> {
+        self.primary.flush()?;
+        let r = self.tee.flush();
+        self.tee_result(r)
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Copy everything written to this pseudo-socket into `tee` as well, e.g. for logging traffic.
+    /// The reader is left intact.
+    pub fn tee_write<T: Write>(self, tee: T) -> ReadWrite<R, TeeWriter<W, T>> {
+        self.map_write(|w| TeeWriter::new(w, tee))
+    }
+}
@@ -0,0 +1,97 @@
+use std::io::{Read, Result, Write};
+use std::time::{Duration, Instant};
+
+use crate::ReadWrite;
+
+/// `ReadWrite` with outbound throughput limited by a token bucket.
+/// Reads pass through untouched. Created by `ReadWrite::throttle_write`.
+pub struct ThrottledReadWrite<R: Read, W: Write> {
+    inner: ReadWrite<R, W>,
+    bytes_per_sec: u64,
+    burst: u64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl<R: Read, W: Write> ThrottledReadWrite<R, W> {
+    /// Limit writes of the given `ReadWrite` to `bytes_per_sec`.
+    ///
+    /// At most a tenth of a second worth of data is let through in one burst.
+    ///
+    /// Panics if `bytes_per_sec` is zero.
+    pub fn new(inner: ReadWrite<R, W>, bytes_per_sec: u64) -> Self {
+        assert!(bytes_per_sec > 0, "throttle rate must be non-zero");
+        ThrottledReadWrite {
+            inner,
+            bytes_per_sec,
+            burst: (bytes_per_sec / 10).max(1),
+            tokens: 0.0,
+            last_refill: Instant::now(),
+        }
+    }
+    /// Current limit, in bytes per second
+    pub fn bytes_per_sec(&self) -> u64 {
+        self.bytes_per_sec
+    }
+    /// Borrow the throttled `ReadWrite`
+    pub fn get_ref(&self) -> &ReadWrite<R, W> {
+        &self.inner
+    }
+    /// Mutably borrow the throttled `ReadWrite`. Writes done through it bypass the limit.
+    pub fn get_mut(&mut self) -> &mut ReadWrite<R, W> {
+        &mut self.inner
+    }
+    /// Remove the throttle, returning the `ReadWrite`
+    pub fn into_inner(self) -> ReadWrite<R, W> {
+        self.inner
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.bytes_per_sec as f64).min(self.burst as f64);
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Limit outbound throughput of this pseudo-socket to `bytes_per_sec`,
+    /// sleeping in `write` as needed. Reads are not affected.
+    ///
+    /// Panics if `bytes_per_sec` is zero.
+    pub fn throttle_write(self, bytes_per_sec: u64) -> ThrottledReadWrite<R, W> {
+        ThrottledReadWrite::new(self, bytes_per_sec)
+    }
+}
+
+impl<R: Read, W: Write> Read for ThrottledReadWrite<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
+}
+
+impl<R: Read, W: Write> Write for ThrottledReadWrite<R, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return self.inner.write(buf);
+        }
+        let wanted = (buf.len() as u64).min(self.burst) as f64;
+        self.refill();
+        if self.tokens < wanted {
+            let missing = wanted - self.tokens;
+            std::thread::sleep(Duration::from_secs_f64(missing / self.bytes_per_sec as f64));
+            self.refill();
+        }
+        let allowed = (self.tokens.floor() as usize).clamp(1, buf.len());
+        let n = self.inner.write(&buf[..allowed])?;
+        self.tokens -= n as f64;
+        Ok(n)
+    }
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
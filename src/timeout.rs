@@ -0,0 +1,350 @@
+fn timed_out(what: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        format!("{} made no progress within the timeout", what),
+    )
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+    use tokio_dep::time::Sleep;
+
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+
+    use super::timed_out;
+    use crate::ReadWriteTokio;
+
+    type Timer = Option<Pin<Box<Sleep>>>;
+
+    /// Arm the timer if the operation is pending, disarm it if the operation completed
+    fn with_timer<T>(
+        timer: &mut Timer,
+        timeout: Option<Duration>,
+        what: &str,
+        cx: &mut Context<'_>,
+        poll: Poll<std::io::Result<T>>,
+    ) -> Poll<std::io::Result<T>> {
+        if poll.is_ready() {
+            *timer = None;
+            return poll;
+        }
+        let timeout = match timeout {
+            Some(x) => x,
+            None => return Poll::Pending,
+        };
+        let t = timer.get_or_insert_with(|| Box::pin(tokio_dep::time::sleep(timeout)));
+        match t.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                *timer = None;
+                Poll::Ready(Err(timed_out(what)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    pin_project_lite::pin_project! {
+        /// `ReadWriteTokio` with idle timeouts for each direction.
+        /// Created by `ReadWriteTokio::with_timeout`.
+        ///
+        /// A timer is armed when an operation returns `Pending` and disarmed when it completes,
+        /// so slow but steady progress never times out.
+        /// If the timer expires first, the operation fails with `ErrorKind::TimedOut`.
+        ///
+        /// Write timeout applies to `poll_write`, `poll_flush` and `poll_shutdown`.
+        ///
+        /// Requires "timeout" Cargo feature.
+        pub struct ReadWriteTokioWithTimeout<R, W> {
+            #[pin]
+            inner: ReadWriteTokio<R, W>,
+            read_timeout: Option<Duration>,
+            write_timeout: Option<Duration>,
+            read_timer: Timer,
+            write_timer: Timer,
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Fail reads or writes which make no progress within the specified durations.
+        /// `None` means no timeout for that direction.
+        pub fn with_timeout(
+            self,
+            read_timeout: Option<Duration>,
+            write_timeout: Option<Duration>,
+        ) -> ReadWriteTokioWithTimeout<R, W> {
+            ReadWriteTokioWithTimeout {
+                inner: self,
+                read_timeout,
+                write_timeout,
+                read_timer: None,
+                write_timer: None,
+            }
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokioWithTimeout<R, W> {
+        /// Change the read timeout. A currently running timer is restarted with the new value.
+        pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+            self.read_timeout = timeout;
+            self.read_timer = None;
+        }
+        /// Change the write timeout. A currently running timer is restarted with the new value.
+        pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+            self.write_timeout = timeout;
+            self.write_timer = None;
+        }
+        /// Get current read timeout
+        pub fn read_timeout(&self) -> Option<Duration> {
+            self.read_timeout
+        }
+        /// Get current write timeout
+        pub fn write_timeout(&self) -> Option<Duration> {
+            self.write_timeout
+        }
+        /// Borrow the wrapped pseudo-socket
+        pub fn get_ref(&self) -> &ReadWriteTokio<R, W> {
+            &self.inner
+        }
+        /// Mutably borrow the wrapped pseudo-socket
+        pub fn get_mut(&mut self) -> &mut ReadWriteTokio<R, W> {
+            &mut self.inner
+        }
+        /// Borrow the pinned wrapped pseudo-socket
+        pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut ReadWriteTokio<R, W>> {
+            self.project().inner
+        }
+        /// Remove the timeouts, returning the wrapped pseudo-socket
+        pub fn into_inner(self) -> ReadWriteTokio<R, W> {
+            self.inner
+        }
+    }
+
+    impl<R: AsyncRead, W> AsyncRead for ReadWriteTokioWithTimeout<R, W> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let p = self.project();
+            let poll = p.inner.poll_read(cx, buf);
+            with_timer(p.read_timer, *p.read_timeout, "read", cx, poll)
+        }
+    }
+
+    impl<R, W: AsyncWrite> AsyncWrite for ReadWriteTokioWithTimeout<R, W> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let p = self.project();
+            let poll = p.inner.poll_write(cx, buf);
+            with_timer(p.write_timer, *p.write_timeout, "write", cx, poll)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let p = self.project();
+            let poll = p.inner.poll_flush(cx);
+            with_timer(p.write_timer, *p.write_timeout, "flush", cx, poll)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let p = self.project();
+            let poll = p.inner.poll_shutdown(cx);
+            with_timer(p.write_timer, *p.write_timeout, "shutdown", cx, poll)
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[std::io::IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            let p = self.project();
+            let poll = p.inner.poll_write_vectored(cx, bufs);
+            with_timer(p.write_timer, *p.write_timeout, "write", cx, poll)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+    }
+}
+#[cfg(feature = "tokio")]
+pub use self::tokio::ReadWriteTokioWithTimeout;
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite};
+    use futures_timer::Delay;
+
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+
+    use super::timed_out;
+    use crate::ReadWriteAsyncstd;
+
+    /// Arm the timer if the operation is pending, disarm it if the operation completed
+    fn with_timer<T>(
+        timer: &mut Option<Delay>,
+        timeout: Option<Duration>,
+        what: &str,
+        cx: &mut Context<'_>,
+        poll: Poll<std::io::Result<T>>,
+    ) -> Poll<std::io::Result<T>> {
+        if poll.is_ready() {
+            *timer = None;
+            return poll;
+        }
+        let timeout = match timeout {
+            Some(x) => x,
+            None => return Poll::Pending,
+        };
+        let t = timer.get_or_insert_with(|| Delay::new(timeout));
+        match Pin::new(t).poll(cx) {
+            Poll::Ready(()) => {
+                *timer = None;
+                Poll::Ready(Err(timed_out(what)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    pin_project_lite::pin_project! {
+        /// `ReadWriteAsyncstd` with idle timeouts for each direction, based on `futures-timer`.
+        /// Created by `ReadWriteAsyncstd::with_timeout`.
+        ///
+        /// A timer is armed when an operation returns `Pending` and disarmed when it completes,
+        /// so slow but steady progress never times out.
+        /// If the timer expires first, the operation fails with `ErrorKind::TimedOut`.
+        ///
+        /// Write timeout applies to `poll_write`, `poll_flush` and `poll_close`.
+        ///
+        /// Requires "timeout" Cargo feature.
+        pub struct ReadWriteAsyncstdWithTimeout<R, W> {
+            #[pin]
+            inner: ReadWriteAsyncstd<R, W>,
+            read_timeout: Option<Duration>,
+            write_timeout: Option<Duration>,
+            read_timer: Option<Delay>,
+            write_timer: Option<Delay>,
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstd<R, W> {
+        /// Fail reads or writes which make no progress within the specified durations.
+        /// `None` means no timeout for that direction.
+        pub fn with_timeout(
+            self,
+            read_timeout: Option<Duration>,
+            write_timeout: Option<Duration>,
+        ) -> ReadWriteAsyncstdWithTimeout<R, W> {
+            ReadWriteAsyncstdWithTimeout {
+                inner: self,
+                read_timeout,
+                write_timeout,
+                read_timer: None,
+                write_timer: None,
+            }
+        }
+    }
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstdWithTimeout<R, W> {
+        /// Change the read timeout. A currently running timer is restarted with the new value.
+        pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+            self.read_timeout = timeout;
+            self.read_timer = None;
+        }
+        /// Change the write timeout. A currently running timer is restarted with the new value.
+        pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+            self.write_timeout = timeout;
+            self.write_timer = None;
+        }
+        /// Get current read timeout
+        pub fn read_timeout(&self) -> Option<Duration> {
+            self.read_timeout
+        }
+        /// Get current write timeout
+        pub fn write_timeout(&self) -> Option<Duration> {
+            self.write_timeout
+        }
+        /// Borrow the wrapped pseudo-socket
+        pub fn get_ref(&self) -> &ReadWriteAsyncstd<R, W> {
+            &self.inner
+        }
+        /// Mutably borrow the wrapped pseudo-socket
+        pub fn get_mut(&mut self) -> &mut ReadWriteAsyncstd<R, W> {
+            &mut self.inner
+        }
+        /// Borrow the pinned wrapped pseudo-socket
+        pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut ReadWriteAsyncstd<R, W>> {
+            self.project().inner
+        }
+        /// Remove the timeouts, returning the wrapped pseudo-socket
+        pub fn into_inner(self) -> ReadWriteAsyncstd<R, W> {
+            self.inner
+        }
+    }
+
+    impl<R: AsyncRead, W> AsyncRead for ReadWriteAsyncstdWithTimeout<R, W> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let p = self.project();
+            let poll = p.inner.poll_read(cx, buf);
+            with_timer(p.read_timer, *p.read_timeout, "read", cx, poll)
+        }
+
+        fn poll_read_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &mut [std::io::IoSliceMut<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            let p = self.project();
+            let poll = p.inner.poll_read_vectored(cx, bufs);
+            with_timer(p.read_timer, *p.read_timeout, "read", cx, poll)
+        }
+    }
+
+    impl<R, W: AsyncWrite> AsyncWrite for ReadWriteAsyncstdWithTimeout<R, W> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let p = self.project();
+            let poll = p.inner.poll_write(cx, buf);
+            with_timer(p.write_timer, *p.write_timeout, "write", cx, poll)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let p = self.project();
+            let poll = p.inner.poll_flush(cx);
+            with_timer(p.write_timer, *p.write_timeout, "flush", cx, poll)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let p = self.project();
+            let poll = p.inner.poll_close(cx);
+            with_timer(p.write_timer, *p.write_timeout, "close", cx, poll)
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[std::io::IoSlice<'_>],
+        ) -> Poll<std::io::Result<usize>> {
+            let p = self.project();
+            let poll = p.inner.poll_write_vectored(cx, bufs);
+            with_timer(p.write_timer, *p.write_timeout, "write", cx, poll)
+        }
+    }
+}
+#[cfg(feature = "asyncstd")]
+pub use self::asyncstd::ReadWriteAsyncstdWithTimeout;
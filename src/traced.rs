@@ -0,0 +1,251 @@
+use std::io::{ErrorKind, IoSlice, IoSliceMut, Read, Result, Write};
+
+use tracing::{debug, trace, Span};
+
+use crate::ReadWrite;
+
+/// I/O wrapper emitting `tracing` events for each operation, within a span covering the whole connection.
+/// Note that this struct is only present in `readwrite` if "tracing" Cargo feature is enabled.
+///
+/// Successful reads, writes and flushes are logged at `TRACE` level with `direction`, `bytes` and `result` fields,
+/// and optionally a `preview` of the data, escaped like in byte string literals.
+/// Errors are logged at `DEBUG` level with the error kind.
+/// Created by `traced` methods or `Traced::new`.
+pub struct Traced<T> {
+    inner: T,
+    span: Span,
+    preview_len: usize,
+}
+
+struct Preview<'a>(&'a [u8]);
+
+impl std::fmt::Display for Preview<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for &b in self.0 {
+            write!(f, "{}", std::ascii::escape_default(b))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Traced<T> {
+    /// Log I/O on `inner` within the given span
+    pub fn new(inner: T, span: Span) -> Self {
+        Traced {
+            inner,
+            span,
+            preview_len: 0,
+        }
+    }
+    /// Include up to `preview_len` first bytes of transferred data into events. Zero (the default) disables previews.
+    pub fn set_preview_len(&mut self, preview_len: usize) {
+        self.preview_len = preview_len;
+    }
+    /// Builder-style version of `set_preview_len`
+    pub fn with_preview_len(mut self, preview_len: usize) -> Self {
+        self.preview_len = preview_len;
+        self
+    }
+    /// The span events are emitted in
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+    /// Borrow the traced object
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+    /// Mutably borrow the traced object. I/O done through it is not logged.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+    /// Stop tracing, returning the inner object
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn log(
+        &self,
+        direction: &'static str,
+        result: std::result::Result<usize, ErrorKind>,
+        data: &[u8],
+    ) {
+        let _entered = self.span.enter();
+        match result {
+            Ok(bytes) if self.preview_len > 0 && !data.is_empty() => {
+                let preview = Preview(&data[..bytes.min(self.preview_len).min(data.len())]);
+                trace!(direction, bytes, result = "ok", preview = %preview)
+            }
+            Ok(bytes) => trace!(direction, bytes, result = "ok"),
+            Err(kind) => debug!(direction, error = ?kind, result = "error"),
+        }
+    }
+}
+
+fn kind_of<X>(result: &Result<X>) -> std::result::Result<(), ErrorKind> {
+    result.as_ref().map(|_| ()).map_err(|e| e.kind())
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Log all I/O on this pseudo-socket with `tracing`, within a `DEBUG` span named `readwrite` with the given `name` field
+    pub fn traced(self, name: &str) -> Traced<Self> {
+        Traced::new(self, tracing::debug_span!("readwrite", name))
+    }
+}
+
+impl<T: Read> Read for Traced<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let ret = self.inner.read(buf);
+        let n = *ret.as_ref().unwrap_or(&0);
+        self.log("read", kind_of(&ret).map(|()| n), &buf[..n]);
+        ret
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        let ret = self.inner.read_vectored(bufs);
+        let n = *ret.as_ref().unwrap_or(&0);
+        self.log("read", kind_of(&ret).map(|()| n), &[]);
+        ret
+    }
+}
+
+impl<T: Write> Write for Traced<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let ret = self.inner.write(buf);
+        let n = *ret.as_ref().unwrap_or(&0);
+        self.log("write", kind_of(&ret).map(|()| n), &buf[..n]);
+        ret
+    }
+    fn flush(&mut self) -> Result<()> {
+        let ret = self.inner.flush();
+        self.log("flush", kind_of(&ret).map(|()| 0), &[]);
+        ret
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        let ret = self.inner.write_vectored(bufs);
+        let n = *ret.as_ref().unwrap_or(&0);
+        self.log("write", kind_of(&ret).map(|()| n), &[]);
+        ret
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{kind_of, Traced};
+    use crate::ReadWriteTokio;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Log all I/O on this pseudo-socket with `tracing`, within a `DEBUG` span named `readwrite` with the given `name` field
+        pub fn traced(self, name: &str) -> Traced<Self> {
+            Traced::new(self, tracing::debug_span!("readwrite", name))
+        }
+    }
+
+    impl<T: AsyncRead + Unpin> AsyncRead for Traced<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            let before = buf.filled().len();
+            let ret = std::task::ready!(Pin::new(&mut this.inner).poll_read(cx, buf));
+            let data = &buf.filled()[before..];
+            this.log("read", kind_of(&ret).map(|()| data.len()), data);
+            Poll::Ready(ret)
+        }
+    }
+
+    impl<T: AsyncWrite + Unpin> AsyncWrite for Traced<T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            let ret = std::task::ready!(Pin::new(&mut this.inner).poll_write(cx, buf));
+            let n = *ret.as_ref().unwrap_or(&0);
+            this.log("write", kind_of(&ret).map(|()| n), &buf[..n]);
+            Poll::Ready(ret)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            let ret = std::task::ready!(Pin::new(&mut this.inner).poll_flush(cx));
+            this.log("flush", kind_of(&ret).map(|()| 0), &[]);
+            Poll::Ready(ret)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            let ret = std::task::ready!(Pin::new(&mut this.inner).poll_shutdown(cx));
+            this.log("shutdown", kind_of(&ret).map(|()| 0), &[]);
+            Poll::Ready(ret)
+        }
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd {
+    use futures::io::{AsyncRead, AsyncWrite};
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{kind_of, Traced};
+    use crate::ReadWriteAsyncstd;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteAsyncstd<R, W> {
+        /// Log all I/O on this pseudo-socket with `tracing`, within a `DEBUG` span named `readwrite` with the given `name` field
+        pub fn traced(self, name: &str) -> Traced<Self> {
+            Traced::new(self, tracing::debug_span!("readwrite", name))
+        }
+    }
+
+    impl<T: AsyncRead + Unpin> AsyncRead for Traced<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            let ret = std::task::ready!(Pin::new(&mut this.inner).poll_read(cx, buf));
+            let n = *ret.as_ref().unwrap_or(&0);
+            this.log("read", kind_of(&ret).map(|()| n), &buf[..n]);
+            Poll::Ready(ret)
+        }
+    }
+
+    impl<T: AsyncWrite + Unpin> AsyncWrite for Traced<T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            let ret = std::task::ready!(Pin::new(&mut this.inner).poll_write(cx, buf));
+            let n = *ret.as_ref().unwrap_or(&0);
+            this.log("write", kind_of(&ret).map(|()| n), &buf[..n]);
+            Poll::Ready(ret)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            let ret = std::task::ready!(Pin::new(&mut this.inner).poll_flush(cx));
+            this.log("flush", kind_of(&ret).map(|()| 0), &[]);
+            Poll::Ready(ret)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            let ret = std::task::ready!(Pin::new(&mut this.inner).poll_close(cx));
+            this.log("close", kind_of(&ret).map(|()| 0), &[]);
+            Poll::Ready(ret)
+        }
+    }
+}
@@ -0,0 +1,453 @@
+use std::collections::VecDeque;
+use std::convert::{TryFrom, TryInto};
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::time::{Duration, Instant};
+
+use crate::ReadWrite;
+
+const MAGIC: &[u8; 4] = b"RWTR";
+const VERSION: u8 = 1;
+
+/// Direction of data flow from the point of view of a pseudo-socket, e.g. of a transcript record or an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Data that was read from the pseudo-socket
+    Inbound,
+    /// Data that was written to the pseudo-socket
+    Outbound,
+}
+
+/// One chunk of recorded traffic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptRecord {
+    /// Whether the data was read or written
+    pub direction: Direction,
+    /// Time since the start of the recording
+    pub offset: Duration,
+    /// The data itself, as returned by one successful `read` or accepted by one `write`
+    pub data: Vec<u8>,
+}
+
+/// Writer of the transcript format used by `Recorder`.
+///
+/// The format is a 4-byte `RWTR` magic and a version byte, followed by records.
+/// Each record is a direction byte (0 for inbound, 1 for outbound), offset in microseconds
+/// as little-endian `u64`, data length as little-endian `u32` and the data.
+pub struct TranscriptWriter<T: Write> {
+    inner: T,
+}
+
+impl<T: Write> TranscriptWriter<T> {
+    /// Start a new transcript, writing the header to `inner`
+    pub fn new(mut inner: T) -> Result<Self> {
+        inner.write_all(MAGIC)?;
+        inner.write_all(&[VERSION])?;
+        Ok(TranscriptWriter { inner })
+    }
+    /// Append a record. Data longer than 4 GiB is rejected with `ErrorKind::InvalidInput`.
+    pub fn write_record(
+        &mut self,
+        direction: Direction,
+        offset: Duration,
+        data: &[u8],
+    ) -> Result<()> {
+        let len = u32::try_from(data.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "transcript record is too long"))?;
+        let direction = match direction {
+            Direction::Inbound => 0u8,
+            Direction::Outbound => 1u8,
+        };
+        let offset = u64::try_from(offset.as_micros()).unwrap_or(u64::MAX);
+        let mut header = [0u8; 13];
+        header[0] = direction;
+        header[1..9].copy_from_slice(&offset.to_le_bytes());
+        header[9..13].copy_from_slice(&len.to_le_bytes());
+        self.inner.write_all(&header)?;
+        self.inner.write_all(data)
+    }
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+    /// Borrow the underlying writer
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+    /// Get back the underlying writer
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+/// Reader of the transcript format written by `TranscriptWriter`.
+/// Also iterates over the records.
+pub struct TranscriptReader<T: Read> {
+    inner: T,
+}
+
+fn invalid_transcript(msg: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid transcript: {}", msg),
+    )
+}
+
+impl<T: Read> TranscriptReader<T> {
+    /// Start reading a transcript, checking its header
+    pub fn new(mut inner: T) -> Result<Self> {
+        let mut header = [0u8; 5];
+        inner.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid_transcript("bad magic"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid_transcript("unsupported version"));
+        }
+        Ok(TranscriptReader { inner })
+    }
+    /// Read the next record, `None` at the end of the transcript
+    pub fn read_record(&mut self) -> Result<Option<TranscriptRecord>> {
+        let mut header = [0u8; 13];
+        let mut got = 0;
+        while got < header.len() {
+            match self.inner.read(&mut header[got..]) {
+                Ok(0) if got == 0 => return Ok(None),
+                Ok(0) => return Err(invalid_transcript("truncated record header")),
+                Ok(n) => got += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        let direction = match header[0] {
+            0 => Direction::Inbound,
+            1 => Direction::Outbound,
+            _ => return Err(invalid_transcript("unknown direction")),
+        };
+        let offset = u64::from_le_bytes(header[1..9].try_into().unwrap());
+        let len = u32::from_le_bytes(header[9..13].try_into().unwrap());
+        let mut data = Vec::new();
+        (&mut self.inner).take(len as u64).read_to_end(&mut data)?;
+        if data.len() != len as usize {
+            return Err(invalid_transcript("truncated record data"));
+        }
+        Ok(Some(TranscriptRecord {
+            direction,
+            offset: Duration::from_micros(offset),
+            data,
+        }))
+    }
+    /// Get back the underlying reader
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Read> Iterator for TranscriptReader<T> {
+    type Item = Result<TranscriptRecord>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Pseudo-socket wrapper that records all successfully read and written data into a transcript.
+/// Created by `ReadWrite::record` or `Recorder::new`.
+///
+/// Failure to write the transcript is reported as the error of the I/O operation being recorded,
+/// even though the operation itself has already happened: data read from the inner pseudo-socket is lost,
+/// and data written to it has already been sent to the peer. Retrying such a failed write sends the data again,
+/// so a recording session should be abandoned after a transcript error.
+pub struct Recorder<S, T: Write> {
+    inner: S,
+    transcript: TranscriptWriter<T>,
+    start: Instant,
+}
+
+impl<S, T: Write> Recorder<S, T> {
+    /// Start recording I/O on `inner` into `transcript`
+    pub fn new(inner: S, transcript: T) -> Result<Self> {
+        Ok(Recorder {
+            inner,
+            transcript: TranscriptWriter::new(transcript)?,
+            start: Instant::now(),
+        })
+    }
+    /// Borrow the recorded pseudo-socket
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+    /// Mutably borrow the recorded pseudo-socket. I/O done through it is not recorded.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+    /// Stop recording, returning the pseudo-socket and the transcript writer
+    pub fn into_inner(self) -> (S, T) {
+        (self.inner, self.transcript.into_inner())
+    }
+
+    fn record(&mut self, direction: Direction, data: &[u8]) -> Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.transcript
+            .write_record(direction, self.start.elapsed(), data)
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Record all traffic of this pseudo-socket into `transcript`, e.g. a `File` or a `Vec<u8>`.
+    /// The recording can be played back with `Replayer`.
+    pub fn record<T: Write>(self, transcript: T) -> Result<Recorder<Self, T>> {
+        Recorder::new(self, transcript)
+    }
+}
+
+impl<S: Read, T: Write> Read for Recorder<S, T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.record(Direction::Inbound, &buf[..n])?;
+        Ok(n)
+    }
+}
+
+impl<S: Write, T: Write> Write for Recorder<S, T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.record(Direction::Outbound, &buf[..n])?;
+        Ok(n)
+    }
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()?;
+        self.transcript.flush()
+    }
+}
+
+/// Mismatch between data written to `Replayer` and the recording.
+///
+/// It is returned wrapped in `std::io::Error` of kind `InvalidData`;
+/// use `std::io::Error::get_ref` and `downcast_ref` to access the details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayMismatch {
+    /// Offset within the outbound stream of the first differing byte
+    pub offset: u64,
+    /// Recorded byte at that offset, `None` if the recording ended before it
+    pub expected: Option<u8>,
+    /// Byte that was written instead
+    pub actual: u8,
+}
+
+impl std::fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.expected {
+            Some(expected) => write!(
+                f,
+                "written data differs from the recording at outbound offset {}: expected byte {:#04x}, got {:#04x}",
+                self.offset, expected, self.actual
+            ),
+            None => write!(
+                f,
+                "written data extends past the end of the recording at outbound offset {}",
+                self.offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayMismatch {}
+
+/// Pseudo-socket playing back a transcript recorded by `Recorder`, standing in for the real peer.
+///
+/// Reads return recorded inbound chunks in order, then EOF.
+/// Writes are checked against the recorded outbound data, failing with `ReplayMismatch` on divergence,
+/// unless verification is turned off with `set_verify_writes`.
+/// Timing of the recording is not reproduced.
+pub struct Replayer {
+    inbound: VecDeque<Vec<u8>>,
+    inbound_pos: usize,
+    outbound: Vec<u8>,
+    outbound_pos: u64,
+    verify_writes: bool,
+}
+
+impl Replayer {
+    /// Create a replayer from records, e.g. collected from `TranscriptReader`.
+    /// Records without data are skipped, so that they do not turn into a premature EOF.
+    pub fn new(records: impl IntoIterator<Item = TranscriptRecord>) -> Self {
+        let mut inbound = VecDeque::new();
+        let mut outbound = Vec::new();
+        for record in records {
+            if record.data.is_empty() {
+                continue;
+            }
+            match record.direction {
+                Direction::Inbound => inbound.push_back(record.data),
+                Direction::Outbound => outbound.extend_from_slice(&record.data),
+            }
+        }
+        Replayer {
+            inbound,
+            inbound_pos: 0,
+            outbound,
+            outbound_pos: 0,
+            verify_writes: true,
+        }
+    }
+    /// Read a whole transcript written by `Recorder` and create a replayer from it
+    pub fn from_transcript<T: Read>(transcript: T) -> Result<Self> {
+        let records = TranscriptReader::new(transcript)?.collect::<Result<Vec<_>>>()?;
+        Ok(Replayer::new(records))
+    }
+    /// Turn checking of written data against the recording on or off. On by default.
+    pub fn set_verify_writes(&mut self, verify: bool) {
+        self.verify_writes = verify;
+    }
+    /// Number of recorded inbound bytes not read yet
+    pub fn remaining_inbound(&self) -> usize {
+        self.inbound.iter().map(Vec::len).sum::<usize>() - self.inbound_pos
+    }
+    /// Number of recorded outbound bytes not written yet
+    pub fn remaining_outbound(&self) -> usize {
+        self.outbound
+            .len()
+            .saturating_sub(self.outbound_pos as usize)
+    }
+
+    fn check_write(&self, buf: &[u8]) -> Result<()> {
+        if !self.verify_writes {
+            return Ok(());
+        }
+        let start = self.outbound_pos as usize;
+        let expected = self.outbound.get(start..).unwrap_or(&[]);
+        for (i, &actual) in buf.iter().enumerate() {
+            let expected = expected.get(i).copied();
+            if expected != Some(actual) {
+                let mismatch = ReplayMismatch {
+                    offset: (start + i) as u64,
+                    expected,
+                    actual,
+                };
+                return Err(Error::new(ErrorKind::InvalidData, mismatch));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Read for Replayer {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let Some(chunk) = self.inbound.front() else {
+            return Ok(0);
+        };
+        let n = buf.len().min(chunk.len() - self.inbound_pos);
+        buf[..n].copy_from_slice(&chunk[self.inbound_pos..self.inbound_pos + n]);
+        self.inbound_pos += n;
+        if self.inbound_pos == chunk.len() {
+            self.inbound.pop_front();
+            self.inbound_pos = 0;
+        }
+        Ok(n)
+    }
+}
+
+impl Write for Replayer {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.check_write(buf)?;
+        self.outbound_pos += buf.len() as u64;
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use std::io::{Read, Write};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use super::{Direction, Recorder, Replayer};
+    use crate::ReadWriteTokio;
+
+    impl<R: AsyncRead, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Record all traffic of this pseudo-socket into `transcript`, e.g. a `File` or a `Vec<u8>`.
+        /// The recording can be played back with `Replayer`.
+        ///
+        /// Note that the transcript is written synchronously from within async I/O calls.
+        pub fn record<T: std::io::Write>(
+            self,
+            transcript: T,
+        ) -> std::io::Result<Recorder<Self, T>> {
+            Recorder::new(self, transcript)
+        }
+    }
+
+    impl<S: AsyncRead + Unpin, T: Write + Unpin> AsyncRead for Recorder<S, T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            let before = buf.filled().len();
+            std::task::ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+            Poll::Ready(this.record(Direction::Inbound, &buf.filled()[before..]))
+        }
+    }
+
+    impl<S: AsyncWrite + Unpin, T: Write + Unpin> AsyncWrite for Recorder<S, T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            let n = std::task::ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+            this.record(Direction::Outbound, &buf[..n])?;
+            Poll::Ready(Ok(n))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            std::task::ready!(Pin::new(&mut this.inner).poll_flush(cx))?;
+            Poll::Ready(this.transcript.flush())
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            std::task::ready!(Pin::new(&mut this.inner).poll_shutdown(cx))?;
+            Poll::Ready(this.transcript.flush())
+        }
+    }
+
+    impl AsyncRead for Replayer {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let n = self.get_mut().read(buf.initialize_unfilled())?;
+            buf.advance(n);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncWrite for Replayer {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(self.get_mut().write(buf))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
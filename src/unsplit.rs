@@ -0,0 +1,95 @@
+/// Reader halves which can be joined back with the matching writer half into the original stream,
+/// like `tokio::net::tcp::OwnedReadHalf::reunite`.
+///
+/// Implemented for halves made by `tokio::io::split`,
+/// and for owned halves of `tokio` TCP and Unix streams if "net" Cargo feature is enabled.
+pub trait Unsplit<W>: Sized {
+    /// The original stream
+    type Joined;
+    /// Join `r` and `w` back, or return both unchanged if they are not halves of the same stream
+    fn unsplit(r: Self, w: W) -> Result<Self::Joined, UnsplitError<Self, W>>;
+}
+
+/// Error returned when trying to join halves of different streams.
+/// The halves are returned back.
+#[derive(Debug)]
+pub struct UnsplitError<R, W> {
+    /// The reader half
+    pub reader: R,
+    /// The writer half
+    pub writer: W,
+}
+
+impl<R, W> UnsplitError<R, W> {
+    /// Take back the halves
+    pub fn into_inner(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+}
+
+impl<R, W> std::fmt::Display for UnsplitError<R, W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("tried to unsplit halves that are not from the same stream")
+    }
+}
+
+impl<R: std::fmt::Debug, W: std::fmt::Debug> std::error::Error for UnsplitError<R, W> {}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use tokio_dep::io::{AsyncRead, AsyncWrite, ReadHalf, WriteHalf};
+
+    use super::{Unsplit, UnsplitError};
+    use crate::ReadWriteTokio;
+
+    #[cfg(feature = "net")]
+    impl Unsplit<tokio_dep::net::tcp::OwnedWriteHalf> for tokio_dep::net::tcp::OwnedReadHalf {
+        type Joined = tokio_dep::net::TcpStream;
+        fn unsplit(
+            r: Self,
+            w: tokio_dep::net::tcp::OwnedWriteHalf,
+        ) -> Result<Self::Joined, UnsplitError<Self, tokio_dep::net::tcp::OwnedWriteHalf>> {
+            r.reunite(w).map_err(|e| UnsplitError {
+                reader: e.0,
+                writer: e.1,
+            })
+        }
+    }
+
+    #[cfg(all(feature = "net", unix))]
+    impl Unsplit<tokio_dep::net::unix::OwnedWriteHalf> for tokio_dep::net::unix::OwnedReadHalf {
+        type Joined = tokio_dep::net::UnixStream;
+        fn unsplit(
+            r: Self,
+            w: tokio_dep::net::unix::OwnedWriteHalf,
+        ) -> Result<Self::Joined, UnsplitError<Self, tokio_dep::net::unix::OwnedWriteHalf>>
+        {
+            r.reunite(w).map_err(|e| UnsplitError {
+                reader: e.0,
+                writer: e.1,
+            })
+        }
+    }
+
+    impl<T: Unpin> Unsplit<WriteHalf<T>> for ReadHalf<T> {
+        type Joined = T;
+        fn unsplit(r: Self, w: WriteHalf<T>) -> Result<T, UnsplitError<Self, WriteHalf<T>>> {
+            if !r.is_pair_of(&w) {
+                return Err(UnsplitError {
+                    reader: r,
+                    writer: w,
+                });
+            }
+            Ok(r.unsplit(w))
+        }
+    }
+
+    impl<R: AsyncRead + Unsplit<W>, W: AsyncWrite> ReadWriteTokio<R, W> {
+        /// Join the halves back into the original stream, see `Unsplit`.
+        /// Reader teardown or shutdown notification, if any, is discarded.
+        pub fn unsplit(self) -> Result<R::Joined, UnsplitError<R, W>> {
+            let (r, w) = self.into_inner();
+            R::unsplit(r, w)
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use futures::io::{AsyncRead, AsyncWrite};
+use futures::stream::Stream;
+use js_sys::Uint8Array;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_streams::readable::IntoStream;
+use wasm_streams::writable::IntoAsyncWrite;
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+fn js_error(e: JsValue) -> std::io::Error {
+    std::io::Error::other(format!("{:?}", e))
+}
+
+/// Combined async reader and writer over a pair of web streams, `futures 0.3` version.
+/// Note that this struct is only present in `readwrite` if "wasm" Cargo feature is enabled.
+///
+/// Chunks of the `ReadableStream` must be `Uint8Array`s. They are buffered across `poll_read` calls,
+/// so reads may be smaller than chunks.
+/// Writes wait for the `WritableStream` to become ready, respecting its backpressure.
+/// `poll_close` closes the `WritableStream`; the `ReadableStream` is left as is.
+///
+/// Both streams stay locked until this object is dropped.
+pub struct ReadWriteWasm {
+    chunks: IntoStream<'static>,
+    pending: Vec<u8>,
+    pos: usize,
+    w: IntoAsyncWrite<'static>,
+}
+
+impl ReadWriteWasm {
+    /// Bundle separate readable and writable web streams into a combined pseudo-socket.
+    ///
+    /// Panics if either stream is already locked.
+    pub fn new(readable: web_sys::ReadableStream, writable: web_sys::WritableStream) -> Self {
+        ReadWriteWasm {
+            chunks: wasm_streams::ReadableStream::from_raw(readable).into_stream(),
+            pending: Vec::new(),
+            pos: 0,
+            w: wasm_streams::WritableStream::from_raw(writable).into_async_write(),
+        }
+    }
+}
+
+impl AsyncRead for ReadWriteWasm {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        while this.pos == this.pending.len() {
+            if buf.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+            let chunk = match std::task::ready!(Pin::new(&mut this.chunks).poll_next(cx)) {
+                None => return Poll::Ready(Ok(0)),
+                Some(chunk) => chunk.map_err(js_error)?,
+            };
+            let chunk = chunk.dyn_into::<Uint8Array>().map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "ReadableStream chunk is not a Uint8Array",
+                )
+            })?;
+            this.pending = chunk.to_vec();
+            this.pos = 0;
+        }
+        let n = buf.len().min(this.pending.len() - this.pos);
+        buf[..n].copy_from_slice(&this.pending[this.pos..this.pos + n]);
+        this.pos += n;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for ReadWriteWasm {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.get_mut().w).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().w).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().w).poll_close(cx)
+    }
+}
@@ -0,0 +1,65 @@
+use std::io::{Error, ErrorKind, Read, Write};
+
+use crate::ReadWrite;
+
+/// Error of `ReadWrite::write_classified`, telling a peer that went away apart from other failures
+#[derive(Debug)]
+pub enum WriteError {
+    /// The peer closed the connection: `BrokenPipe`, `ConnectionReset` or `ConnectionAborted`
+    PeerClosed(Error),
+    /// Any other error
+    Other(Error),
+}
+
+impl WriteError {
+    /// Classify an error returned by a writer
+    pub fn classify(e: Error) -> Self {
+        match e.kind() {
+            ErrorKind::BrokenPipe | ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted => {
+                WriteError::PeerClosed(e)
+            }
+            _ => WriteError::Other(e),
+        }
+    }
+    /// Whether the peer closed the connection
+    pub fn is_peer_closed(&self) -> bool {
+        matches!(self, WriteError::PeerClosed(_))
+    }
+    /// The underlying error
+    pub fn into_inner(self) -> Error {
+        match self {
+            WriteError::PeerClosed(e) | WriteError::Other(e) => e,
+        }
+    }
+}
+
+// The underlying error is only reported through `source`, so that error-chain reporters do not print it twice
+impl std::fmt::Display for WriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WriteError::PeerClosed(_) => f.write_str("peer closed the connection"),
+            WriteError::Other(_) => f.write_str("write failed"),
+        }
+    }
+}
+
+impl std::error::Error for WriteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WriteError::PeerClosed(e) | WriteError::Other(e) => Some(e),
+        }
+    }
+}
+
+impl From<WriteError> for Error {
+    fn from(e: WriteError) -> Self {
+        e.into_inner()
+    }
+}
+
+impl<R: Read, W: Write> ReadWrite<R, W> {
+    /// Write to the writer like `write`, classifying errors with `WriteError::classify`
+    pub fn write_classified(&mut self, buf: &[u8]) -> Result<usize, WriteError> {
+        self.1.write(buf).map_err(WriteError::classify)
+    }
+}
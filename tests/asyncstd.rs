@@ -0,0 +1,244 @@
+#![cfg(feature = "asyncstd")]
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::executor::block_on;
+use futures::io::AsyncWrite;
+use futures::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter, Cursor};
+use readwrite::ReadWriteAsyncstd;
+
+#[test]
+fn map_combinators_layer_buffers() {
+    block_on(async {
+        let rw = ReadWriteAsyncstd::new(
+            Cursor::new(b"first\nsecond\n".to_vec()),
+            Cursor::new(Vec::new()),
+        );
+        let mut rw = rw.map_both(BufReader::new, BufWriter::new);
+        let mut line = String::new();
+        rw.borrow_mut_read().read_line(&mut line).await.unwrap();
+        assert_eq!(line, "first\n");
+
+        rw.write_all(b"out").await.unwrap();
+        assert!(rw.borrow_write().get_ref().get_ref().is_empty());
+        rw.flush().await.unwrap();
+
+        let mut rw = rw
+            .map_read(BufReader::into_inner)
+            .map_write(BufWriter::into_inner);
+        // The rest of the input was buffered by the dropped `BufReader`
+        let mut rest = Vec::new();
+        rw.read_to_end(&mut rest).await.unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(rw.into_writer().into_inner(), b"out");
+    });
+}
+
+#[test]
+fn boxed_from_different_transports() {
+    use readwrite::BoxedReadWriteAsyncstd;
+
+    async fn exchange(rw: &mut BoxedReadWriteAsyncstd<'_>) -> Vec<u8> {
+        rw.write_all(b"ping").await.unwrap();
+        let mut buf = [0; 4];
+        rw.read_exact(&mut buf).await.unwrap();
+        buf.to_vec()
+    }
+
+    block_on(async {
+        let mut cursors =
+            ReadWriteAsyncstd::new(Cursor::new(b"curs".to_vec()), Cursor::new(Vec::new())).boxed();
+        assert_eq!(exchange(&mut cursors).await, b"curs");
+        let mut slices = ReadWriteAsyncstd::new(&b"slic"[..], futures::io::sink()).boxed();
+        assert_eq!(exchange(&mut slices).await, b"slic");
+    });
+}
+
+/// Writer logging calls, whose flush is pending once per write and whose close can fail once
+#[derive(Default)]
+struct EventWriter {
+    events: Vec<&'static str>,
+    flush_pending: bool,
+    fail_close: bool,
+}
+
+impl AsyncWrite for EventWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.events.push("write");
+        self.flush_pending = true;
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if self.flush_pending {
+            self.flush_pending = false;
+            self.events.push("flush pending");
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.events.push("flush");
+        Poll::Ready(Ok(()))
+    }
+    fn poll_close(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if self.fail_close {
+            self.fail_close = false;
+            self.events.push("close failed");
+            return Poll::Ready(Err(std::io::ErrorKind::Other.into()));
+        }
+        self.events.push("close");
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn close_waits_for_flush() {
+    block_on(async {
+        let mut rw = ReadWriteAsyncstd::new(futures::io::empty(), EventWriter::default());
+        rw.write_all(b"data").await.unwrap();
+        rw.close().await.unwrap();
+        assert_eq!(
+            rw.borrow_write().events,
+            ["write", "flush pending", "flush", "close"]
+        );
+    });
+}
+
+#[test]
+fn write_after_failed_close_is_flushed() {
+    block_on(async {
+        let writer = EventWriter {
+            fail_close: true,
+            ..EventWriter::default()
+        };
+        let mut rw = ReadWriteAsyncstd::new(futures::io::empty(), writer);
+        rw.close().await.unwrap_err();
+        rw.write_all(b"more").await.unwrap();
+        rw.close().await.unwrap();
+        assert_eq!(
+            rw.borrow_write().events,
+            [
+                "flush",
+                "close failed",
+                "write",
+                "flush pending",
+                "flush",
+                "close"
+            ]
+        );
+    });
+}
+
+/// Writer whose second `poll_close` panics, like writers that assert they are closed only once
+#[derive(Default)]
+struct CloseOnce {
+    closed: bool,
+}
+
+impl AsyncWrite for CloseOnce {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        assert!(!self.closed, "write after close");
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_close(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        assert!(!self.closed, "closed twice");
+        self.closed = true;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn close_twice_is_fine() {
+    block_on(async {
+        let mut rw = ReadWriteAsyncstd::new(futures::io::empty(), CloseOnce::default());
+        rw.write_all(b"bye").await.unwrap();
+        rw.close().await.unwrap();
+        rw.close().await.unwrap();
+        assert!(rw.borrow_write().closed);
+    });
+}
+
+#[test]
+fn map_read_keeps_writer_state() {
+    block_on(async {
+        let mut rw = ReadWriteAsyncstd::new(futures::io::empty(), CloseOnce::default());
+        rw.close().await.unwrap();
+        let mut rw = rw.map_read(BufReader::new);
+        rw.close().await.unwrap();
+
+        // The flush preceding a failed close is not repeated after `map_read` either
+        let writer = EventWriter {
+            fail_close: true,
+            ..EventWriter::default()
+        };
+        let mut rw = ReadWriteAsyncstd::new(futures::io::empty(), writer);
+        rw.close().await.unwrap_err();
+        let mut rw = rw.map_read(BufReader::new);
+        rw.close().await.unwrap();
+        rw.close().await.unwrap();
+        assert_eq!(rw.borrow_write().events, ["flush", "close failed", "close"]);
+    });
+}
+
+/// The same struct used through the `async_std::io` trait names, which re-export the `futures` traits
+mod async_std_trait_names {
+    use async_std::io::prelude::{ReadExt, WriteExt};
+    use async_std::net::{TcpListener, TcpStream};
+    use async_std::task::block_on;
+    use readwrite::ReadWriteAsyncstd;
+
+    /// Protocol code written against the `async_std::io` trait names
+    async fn request<S>(s: &mut S, msg: &[u8]) -> Vec<u8>
+    where
+        S: async_std::io::Read + async_std::io::Write + Unpin,
+    {
+        s.write_all(msg).await.unwrap();
+        s.flush().await.unwrap();
+        let mut reply = vec![0; msg.len()];
+        s.read_exact(&mut reply).await.unwrap();
+        reply
+    }
+
+    #[test]
+    fn round_trip_through_async_std_traits() {
+        block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let server = async_std::task::spawn(async move {
+                let (s, _) = listener.accept().await.unwrap();
+                async_std::io::copy(&mut &s, &mut &s).await.unwrap();
+            });
+
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let mut rw = ReadWriteAsyncstd::new(stream.clone(), stream);
+            assert_eq!(request(&mut rw, b"hello").await, b"hello");
+            assert_eq!(request(&mut rw, b"again").await, b"again");
+
+            // Dropping both clones of the stream closes the connection
+            drop(rw);
+            server.await;
+        });
+    }
+
+    #[test]
+    fn in_memory_halves() {
+        block_on(async {
+            let mut rw =
+                ReadWriteAsyncstd::new(async_std::io::Cursor::new(b"ping".to_vec()), Vec::new());
+            let mut buf = [0u8; 4];
+            rw.read_exact(&mut buf).await.unwrap();
+            rw.write_all(&buf).await.unwrap();
+            assert_eq!(rw.into_writer(), b"ping");
+        });
+    }
+}
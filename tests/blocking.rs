@@ -0,0 +1,59 @@
+#![cfg(feature = "blocking")]
+
+extern crate tokio_dep as tokio;
+
+use std::io::{Read, Write};
+
+use readwrite::duplex_tokio;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[test]
+fn blocking_adapter_on_thread() {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let (a, mut b) = duplex_tokio(64);
+    let mut a = a.into_blocking(rt.handle().clone());
+
+    let peer = rt.spawn(async move {
+        let mut buf = [0; 4];
+        b.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+        b.write_all(b"pong").await.unwrap();
+        let mut rest = Vec::new();
+        b.read_to_end(&mut rest).await.unwrap();
+        rest
+    });
+
+    let t = std::thread::spawn(move || {
+        a.write_all(b"ping").unwrap();
+        a.flush().unwrap();
+        let mut buf = [0; 4];
+        a.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"pong");
+        a.write_all(b"bye").unwrap();
+        a.shutdown().unwrap();
+    });
+    t.join().unwrap();
+
+    let rest = rt.block_on(peer).unwrap();
+    assert_eq!(rest, b"bye");
+}
+
+#[test]
+fn blocking_adapter_in_place_on_worker() {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let (a, mut b) = duplex_tokio(64);
+    let handle = rt.handle().clone();
+
+    rt.block_on(async move {
+        let writer = tokio::spawn(async move {
+            let mut a = a.into_blocking(handle);
+            a.set_block_in_place(true);
+            a.write_all(b"from worker").unwrap();
+            a.shutdown().unwrap();
+        });
+        let mut buf = Vec::new();
+        b.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(buf, b"from worker");
+        writer.await.unwrap();
+    });
+}
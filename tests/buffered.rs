@@ -0,0 +1,207 @@
+mod common;
+
+use std::io::{BufRead, Read, Write};
+
+use readwrite::ReadWrite;
+
+/// Reader counting `read` calls of the inner reader
+struct CountingReader<R> {
+    inner: R,
+    reads: usize,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reads += 1;
+        self.inner.read(buf)
+    }
+}
+
+#[test]
+fn buffered_reads_coalesce() {
+    let source = CountingReader {
+        inner: &[5u8; 100][..],
+        reads: 0,
+    };
+    let mut rw = ReadWrite::new(source, Vec::new()).buffered();
+    let mut byte = [0; 1];
+    for _ in 0..100 {
+        rw.read_exact(&mut byte).unwrap();
+    }
+    assert_eq!(rw.read(&mut byte).unwrap(), 0);
+    // One read filling the buffer and one hitting EOF
+    assert_eq!(rw.into_reader().into_inner().reads, 2);
+}
+
+#[test]
+fn buf_read_passthrough() {
+    let mut rw = ReadWrite::new(&b"first line\nsecond"[..], Vec::new()).buffered();
+    assert_eq!(rw.fill_buf().unwrap(), b"first line\nsecond");
+    rw.consume(6);
+    let mut line = String::new();
+    rw.read_line(&mut line).unwrap();
+    assert_eq!(line, "line\n");
+    let lines: Vec<String> = rw.lines().map(|l| l.unwrap()).collect();
+    assert_eq!(lines, ["second"]);
+}
+
+#[test]
+fn buffered_with_capacity() {
+    let source = CountingReader {
+        inner: &[5u8; 100][..],
+        reads: 0,
+    };
+    let mut rw = ReadWrite::new(source, std::io::sink()).buffered_with_capacity(10);
+    let mut byte = [0; 1];
+    for _ in 0..100 {
+        rw.read_exact(&mut byte).unwrap();
+    }
+    rw.write_all(b"unbuffered writer").unwrap();
+    // Ten refills of the ten-byte buffer
+    assert_eq!(rw.into_reader().into_inner().reads, 10);
+}
+
+/// Writer counting `write` calls, which can be made to fail
+#[derive(Debug, Default)]
+struct CountingWriter {
+    written: Vec<u8>,
+    writes: usize,
+    broken: bool,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.broken {
+            return Err(std::io::ErrorKind::BrokenPipe.into());
+        }
+        self.writes += 1;
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn small_writes_coalesce() {
+    let mut rw = ReadWrite::new(std::io::empty(), CountingWriter::default()).buffered_both();
+    for _ in 0..100 {
+        rw.write_all(b"x").unwrap();
+    }
+    assert_eq!(rw.borrow_write().get_ref().writes, 0);
+    rw.flush().unwrap();
+    assert_eq!(rw.borrow_write().get_ref().writes, 1);
+    assert_eq!(rw.borrow_write().get_ref().written.len(), 100);
+}
+
+#[test]
+fn unbuffer_flushes() {
+    let mut rw =
+        ReadWrite::new(&b"abc"[..], CountingWriter::default()).buffered_both_with_capacity(2, 64);
+    rw.write_all(b"pending").unwrap();
+    let mut byte = [0; 1];
+    rw.read_exact(&mut byte).unwrap();
+    let rw = rw.unbuffer().unwrap();
+    let (r, w) = rw.into_inner();
+    assert_eq!(w.written, b"pending");
+    // The second byte was buffered and is discarded
+    assert_eq!(r, b"c");
+}
+
+#[test]
+fn failed_unbuffer_keeps_data() {
+    let mut rw = ReadWrite::new(std::io::empty(), CountingWriter::default()).buffered_both();
+    rw.write_all(b"precious").unwrap();
+    rw.borrow_mut_write().get_mut().broken = true;
+
+    let e = match rw.unbuffer() {
+        Ok(_) => panic!("flushing a broken writer succeeded"),
+        Err(e) => e,
+    };
+    assert_eq!(e.error().kind(), std::io::ErrorKind::BrokenPipe);
+    let mut rw = e.into_inner();
+    assert_eq!(rw.borrow_write().buffer(), b"precious");
+
+    rw.borrow_mut_write().get_mut().broken = false;
+    let rw = rw.unbuffer().unwrap();
+    assert_eq!(rw.into_writer().written, b"precious");
+}
+
+#[test]
+fn into_parts_returns_unconsumed_bytes() {
+    let mut rw = ReadWrite::new(&b"0123456789"[..], Vec::new()).buffered_with_capacity(4);
+    let mut one = [0u8; 1];
+    rw.read_exact(&mut one).unwrap();
+    assert_eq!(&one, b"0");
+    rw.write_all(b"out").unwrap();
+
+    let (rest, buffered, w) = rw.into_parts();
+    assert_eq!(buffered, b"123");
+    // Bytes past the buffer were never read from the raw reader
+    assert_eq!(rest, b"456789");
+    assert_eq!(w, b"out");
+}
+
+#[test]
+fn into_parts_hands_over_the_socket() {
+    let (client, mut server) = common::tcp_pair();
+    server.write_all(b"HELLO\nextra").unwrap();
+    drop(server);
+    let mut rw = ReadWrite::from_stream(client).unwrap().buffered();
+    let mut line = String::new();
+    rw.read_line(&mut line).unwrap();
+    assert_eq!(line, "HELLO\n");
+
+    // Whatever the buffer already holds plus the rest of the socket is exactly what followed the line
+    let (mut raw, mut data, _w) = rw.into_parts();
+    raw.read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"extra");
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_buffered {
+    extern crate tokio_dep as tokio;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn unbuffer_flushes() {
+        let (a, mut b) = readwrite::duplex_tokio(64);
+        let mut a = a.buffered_both();
+        a.write_all(b"coalesced").await.unwrap();
+        assert!(!a.borrow_write().buffer().is_empty());
+        let _a = a.unbuffer().await.unwrap();
+        let mut buf = [0; 9];
+        b.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"coalesced");
+    }
+
+    #[tokio::test]
+    async fn into_parts_returns_unconsumed_bytes() {
+        let mut rw = readwrite::ReadWriteTokio::new(&b"abcdef"[..], Vec::new())
+            .map_read(tokio::io::BufReader::new);
+        assert_eq!(rw.read_u8().await.unwrap(), b'a');
+        let (rest, buffered, _w) = rw.into_parts();
+        assert_eq!(buffered, b"bcdef");
+        assert!(rest.is_empty());
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_buffered {
+    use futures::executor::block_on;
+    use futures::io::AsyncWriteExt;
+    use readwrite::ReadWriteAsyncstd;
+
+    #[test]
+    fn unbuffer_flushes() {
+        block_on(async {
+            let mut rw = ReadWriteAsyncstd::new(futures::io::empty(), Vec::new()).buffered_both();
+            rw.write_all(b"coalesced").await.unwrap();
+            assert!(rw.borrow_write().get_ref().is_empty());
+            let rw = rw.unbuffer().await.unwrap();
+            assert_eq!(rw.into_writer(), b"coalesced");
+        });
+    }
+}
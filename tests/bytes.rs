@@ -0,0 +1,116 @@
+#![cfg(feature = "bytes")]
+
+use std::io::{ErrorKind, Read, Write};
+
+use bytes::{Buf, Bytes};
+use readwrite::{echo, ReadWrite};
+
+/// Reader handing out at most three bytes per call
+struct Dribble<'a>(&'a [u8]);
+
+impl Read for Dribble<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(3).min(self.0.len());
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+/// Writer accepting a limited number of bytes, failing afterwards
+struct Limited {
+    data: Vec<u8>,
+    capacity: usize,
+}
+
+impl Write for Limited {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.data.len() == self.capacity {
+            return Err(ErrorKind::BrokenPipe.into());
+        }
+        let n = buf.len().min(self.capacity - self.data.len());
+        self.data.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn round_trip_through_echo() {
+    let payload = Bytes::from((0..100_000u32).map(|x| x as u8).collect::<Vec<u8>>());
+    let mut rw = ReadWrite::duplex_single(echo());
+    let mut outgoing = payload.clone();
+    rw.write_bytes(&mut outgoing).unwrap();
+    assert!(outgoing.is_empty());
+    let received = rw.read_bytes(payload.len()).unwrap();
+    assert_eq!(received.freeze(), payload);
+}
+
+#[test]
+fn short_reads_are_retried() {
+    let mut rw = ReadWrite::new(Dribble(b"0123456789abc"), std::io::sink());
+    assert_eq!(rw.read_bytes(10).unwrap(), &b"0123456789"[..]);
+    // EOF comes first
+    assert_eq!(rw.read_bytes(10).unwrap(), &b"abc"[..]);
+    assert!(rw.read_bytes(10).unwrap().is_empty());
+}
+
+#[test]
+fn huge_length_is_not_allocated_upfront() {
+    let mut rw = ReadWrite::new(&b"tiny"[..], std::io::sink());
+    let got = rw.read_bytes(usize::MAX / 2).unwrap();
+    assert_eq!(got, &b"tiny"[..]);
+    assert!(got.capacity() < 1024 * 1024);
+}
+
+#[test]
+fn chained_buf_is_written_in_order() {
+    let mut rw = ReadWrite::new(std::io::empty(), Vec::new());
+    let mut buf = Bytes::from_static(b"head,").chain(Bytes::from_static(b"body"));
+    rw.write_bytes(&mut buf).unwrap();
+    assert_eq!(rw.into_writer(), b"head,body");
+}
+
+#[test]
+fn failed_write_leaves_the_rest_in_buf() {
+    let w = Limited {
+        data: Vec::new(),
+        capacity: 4,
+    };
+    let mut rw = ReadWrite::new(std::io::empty(), w);
+    let mut buf = Bytes::from_static(b"abcdefgh");
+    let err = rw.write_bytes(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    assert_eq!(buf, &b"efgh"[..]);
+    assert_eq!(rw.borrow_write().data, b"abcd");
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_bytes {
+    extern crate tokio_dep as tokio;
+
+    use bytes::{Buf, Bytes};
+    use readwrite::{duplex_tokio, ReadWriteTokio};
+
+    #[tokio::test]
+    async fn round_trip_through_duplex() {
+        let payload = Bytes::from((0..100_000u32).map(|x| x as u8).collect::<Vec<u8>>());
+        let (mut a, mut b) = duplex_tokio(4096);
+        let expected = payload.clone();
+        let reader = tokio::spawn(async move { b.read_bytes(expected.len()).await.unwrap() });
+        let mut outgoing = Bytes::from_static(b"").chain(payload.clone());
+        a.write_bytes(&mut outgoing).await.unwrap();
+        assert!(!outgoing.has_remaining());
+        assert_eq!(reader.await.unwrap().freeze(), payload);
+    }
+
+    #[tokio::test]
+    async fn huge_length_is_not_allocated_upfront() {
+        let mut rw = ReadWriteTokio::new(&b"tiny"[..], tokio::io::sink());
+        let got = rw.read_bytes(usize::MAX / 2).await.unwrap();
+        assert_eq!(got, &b"tiny"[..]);
+        assert!(got.capacity() < 1024 * 1024);
+    }
+}
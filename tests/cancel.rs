@@ -0,0 +1,138 @@
+#![cfg(any(feature = "tokio", feature = "asyncstd"))]
+
+#[cfg(feature = "tokio")]
+mod tokio_cancel {
+    extern crate tokio_dep as tokio;
+
+    use std::io::{ErrorKind, IoSlice};
+    use std::time::Duration;
+
+    use readwrite::ReadWriteTokio;
+    use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::sync::oneshot;
+
+    /// Signal completing when the sender fires or is dropped
+    fn signal() -> (oneshot::Sender<()>, impl std::future::Future<Output = ()>) {
+        let (tx, rx) = oneshot::channel();
+        (tx, async move {
+            let _ = rx.await;
+        })
+    }
+
+    #[tokio::test]
+    async fn pending_read_ends_with_eof() {
+        let (ours, _silent_peer) = tokio::io::duplex(64);
+        let (r, w) = tokio::io::split(ours);
+        let (cancel, sig) = signal();
+        let mut rw = ReadWriteTokio::new(r, w).with_cancellation(sig);
+        let read = tokio::spawn(async move {
+            let mut buf = [0u8; 16];
+            let n = rw.read(&mut buf).await;
+            (n.unwrap(), rw)
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!read.is_finished());
+
+        cancel.send(()).unwrap();
+        let (n, mut rw) = tokio::time::timeout(Duration::from_secs(5), read)
+            .await
+            .expect("cancelled read did not complete")
+            .unwrap();
+        assert_eq!(n, 0);
+        assert!(rw.is_cancelled());
+        let err = rw.write_all(b"late").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConnectionAborted);
+        rw.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn configured_read_error() {
+        let (ours, _silent_peer) = tokio::io::duplex(64);
+        let (r, w) = tokio::io::split(ours);
+        let (cancel, sig) = signal();
+        let mut rw = ReadWriteTokio::new(r, w)
+            .with_cancellation(sig)
+            .with_read_error(ErrorKind::Interrupted);
+        let read = tokio::spawn(async move { rw.read(&mut [0u8; 16]).await });
+        tokio::task::yield_now().await;
+        drop(cancel);
+        let err = read.await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+    }
+
+    #[tokio::test]
+    async fn io_passes_through_before_cancellation() {
+        let (ours, mut peer) = tokio::io::duplex(64);
+        let (r, w) = tokio::io::split(ours);
+        let (_cancel, sig) = signal();
+        let mut rw = ReadWriteTokio::new(r, w).with_cancellation(sig);
+        rw.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        peer.read_exact(&mut buf).await.unwrap();
+        peer.write_all(b"pong").await.unwrap();
+        rw.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"pong");
+        assert!(!rw.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn vectored_writes_are_forwarded() {
+        let (cancel, sig) = signal();
+        let mut rw = ReadWriteTokio::new(tokio::io::empty(), Vec::new()).with_cancellation(sig);
+        assert!(rw.is_write_vectored());
+        let bufs = [IoSlice::new(b"ab"), IoSlice::new(b"cd")];
+        assert_eq!(rw.write_vectored(&bufs).await.unwrap(), 4);
+        assert_eq!(rw.get_ref().borrow_write(), b"abcd");
+
+        cancel.send(()).unwrap();
+        let err = rw.write_vectored(&bufs).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConnectionAborted);
+        assert_eq!(rw.get_ref().borrow_write(), b"abcd");
+    }
+
+    #[cfg(feature = "cancellation-token")]
+    #[tokio::test]
+    async fn cancellation_token() {
+        let (ours, _silent_peer) = tokio::io::duplex(64);
+        let (r, w) = tokio::io::split(ours);
+        let token = tokio_util::sync::CancellationToken::new();
+        let mut rw = ReadWriteTokio::new(r, w).with_cancellation_token(token.clone());
+        let read = tokio::spawn(async move { rw.read(&mut [0u8; 16]).await.unwrap() });
+        tokio::task::yield_now().await;
+        token.cancel();
+        assert_eq!(read.await.unwrap(), 0);
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_cancel {
+    use std::io::ErrorKind;
+
+    use futures::executor::block_on;
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+    use readwrite::ReadWriteAsyncstd;
+
+    #[test]
+    fn fired_signal_stops_io() {
+        block_on(async {
+            let mut rw = ReadWriteAsyncstd::new(&b"unread"[..], Vec::new())
+                .with_cancellation(futures::future::ready(()));
+            assert_eq!(rw.read(&mut [0u8; 8]).await.unwrap(), 0);
+            let err = rw.write_all(b"x").await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ConnectionAborted);
+            rw.close().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn pending_signal_passes_io_through() {
+        block_on(async {
+            let mut rw = ReadWriteAsyncstd::new(&b"data"[..], Vec::new())
+                .with_cancellation(futures::future::pending());
+            let mut buf = [0u8; 4];
+            rw.read_exact(&mut buf).await.unwrap();
+            rw.write_all(&buf).await.unwrap();
+            assert_eq!(rw.get_ref().borrow_write(), b"data");
+        });
+    }
+}
@@ -0,0 +1,103 @@
+#![cfg(unix)]
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+use readwrite::ReadWrite;
+
+#[test]
+fn child_round_trip() {
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut rw = ReadWrite::from_child(&mut child).unwrap();
+    rw.write_all(b"hello child").unwrap();
+    let (mut r, w) = rw.into_inner();
+    drop(w);
+    let mut out = String::new();
+    r.read_to_string(&mut out).unwrap();
+    assert_eq!(out, "hello child");
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn child_missing_pipe() {
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    let e = ReadWrite::from_child(&mut child).err().unwrap();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(e.to_string().contains("no stdout pipe"), "{}", e);
+    // The child is left untouched
+    assert!(child.stdin.is_some());
+    drop(child.stdin.take());
+    child.wait().unwrap();
+}
+
+#[test]
+fn child_with_stderr() {
+    let mut child = Command::new("sh")
+        .args(["-c", "cat; echo oops >&2"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (rw, mut stderr) = ReadWrite::from_child_with_stderr(&mut child).unwrap();
+    let (mut r, mut w) = rw.into_inner();
+    w.write_all(b"data").unwrap();
+    drop(w);
+    let mut out = String::new();
+    r.read_to_string(&mut out).unwrap();
+    assert_eq!(out, "data");
+    let mut err = String::new();
+    stderr.read_to_string(&mut err).unwrap();
+    assert_eq!(err, "oops\n");
+    child.wait().unwrap();
+}
+
+#[cfg(feature = "from_child")]
+mod tokio_child {
+    extern crate tokio_dep as tokio;
+
+    use readwrite::ReadWriteTokio;
+    use std::process::Stdio;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::process::Command;
+
+    #[tokio::test]
+    async fn child_round_trip() {
+        let mut child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut rw = ReadWriteTokio::from_child(&mut child).unwrap();
+        rw.write_all(b"hello tokio child").await.unwrap();
+        // Dropping the writer closes the pipe, letting `cat` exit
+        let (mut r, w) = rw.into_inner();
+        drop(w);
+        let mut out = String::new();
+        r.read_to_string(&mut out).await.unwrap();
+        assert_eq!(out, "hello tokio child");
+        assert!(child.wait().await.unwrap().success());
+    }
+
+    #[tokio::test]
+    async fn child_missing_pipe() {
+        let mut child = Command::new("cat")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let e = ReadWriteTokio::from_child(&mut child).err().unwrap();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(e.to_string().contains("no stdin pipe"), "{}", e);
+        assert!(child.stdout.is_some());
+        child.wait().await.unwrap();
+    }
+}
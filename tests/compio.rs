@@ -0,0 +1,25 @@
+#![cfg(feature = "compio")]
+
+use compio_io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use futures::executor::block_on;
+use readwrite::ReadWriteCompio;
+
+// In-memory halves complete immediately, so no compio runtime is needed to drive them
+#[test]
+fn in_memory_round_trip() {
+    block_on(async {
+        let mut rw = ReadWriteCompio::new(&b"request"[..], Vec::new());
+
+        let res = rw.read_exact(Vec::with_capacity(7)).await;
+        res.0.unwrap();
+        assert_eq!(res.1, b"request");
+        let res = rw.read_exact(Vec::with_capacity(1)).await;
+        assert_eq!(res.0.unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+
+        let res = rw.write_all(b"response".to_vec()).await;
+        res.0.unwrap();
+        rw.flush().await.unwrap();
+        rw.shutdown().await.unwrap();
+        assert_eq!(rw.into_writer(), b"response");
+    });
+}
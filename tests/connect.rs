@@ -0,0 +1,65 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use readwrite::ReadWrite;
+
+/// Line echo server accepting a single connection
+fn echo_server() -> (SocketAddr, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let t = std::thread::spawn(move || {
+        let (s, _) = listener.accept().unwrap();
+        let mut w = s.try_clone().unwrap();
+        for line in BufReader::new(s).lines() {
+            writeln!(w, "{}", line.unwrap()).unwrap();
+        }
+    });
+    (addr, t)
+}
+
+fn round_trip(rw: &mut ReadWrite<std::net::TcpStream, std::net::TcpStream>) {
+    rw.write_all(b"hello\n").unwrap();
+    let mut reply = [0u8; 6];
+    rw.read_exact(&mut reply).unwrap();
+    assert_eq!(&reply, b"hello\n");
+}
+
+#[test]
+fn connect_round_trip() {
+    let (addr, server) = echo_server();
+    let mut rw = ReadWrite::connect(addr).unwrap();
+    round_trip(&mut rw);
+    drop(rw);
+    server.join().unwrap();
+}
+
+#[test]
+fn connect_timeout_round_trip() {
+    let (addr, server) = echo_server();
+    let mut rw = ReadWrite::connect_timeout(addr, Duration::from_secs(5)).unwrap();
+    round_trip(&mut rw);
+    drop(rw);
+    server.join().unwrap();
+}
+
+#[test]
+fn connect_with_host_name() {
+    let (addr, server) = echo_server();
+    // Every resolved address is tried, so an IPv6 `localhost` entry does not get in the way
+    let mut rw = ReadWrite::connect(("localhost", addr.port())).unwrap();
+    round_trip(&mut rw);
+    drop(rw);
+    server.join().unwrap();
+}
+
+#[test]
+fn refused_connection_is_an_error() {
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    assert!(ReadWrite::connect(addr).is_err());
+    assert!(ReadWrite::connect_timeout(addr, Duration::from_secs(5)).is_err());
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use std::io::{ErrorKind, Read, Write};
+
+use readwrite::{copy_bidirectional, CopyBidirectionalError, ReadWrite};
+
+#[test]
+fn half_close_propagates_over_cloned_sockets() {
+    let (a, mut a_peer) = common::tcp_pair();
+    let (b, mut b_peer) = common::tcp_pair();
+    let a = ReadWrite::from_stream(a).unwrap();
+    let b = ReadWrite::from_stream(b).unwrap();
+    let proxy = std::thread::spawn(move || copy_bidirectional(a, b));
+
+    a_peer.write_all(b"hello").unwrap();
+    a_peer.shutdown(std::net::Shutdown::Write).unwrap();
+    // EOF reaches the other side even though the proxy still holds a clone of the socket
+    let mut got = String::new();
+    b_peer.read_to_string(&mut got).unwrap();
+    assert_eq!(got, "hello");
+
+    // The opposite direction keeps working after the half-close
+    b_peer.write_all(b"world!").unwrap();
+    b_peer.shutdown(std::net::Shutdown::Write).unwrap();
+    got.clear();
+    a_peer.read_to_string(&mut got).unwrap();
+    assert_eq!(got, "world!");
+
+    assert_eq!(proxy.join().unwrap().unwrap(), (5, 6));
+}
+
+/// Reader which fails after handing out some data
+struct FailingReader(usize);
+
+impl Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.0 == 0 {
+            return Err(ErrorKind::ConnectionReset.into());
+        }
+        let n = self.0.min(buf.len());
+        buf[..n].fill(b'x');
+        self.0 -= n;
+        Ok(n)
+    }
+}
+
+#[test]
+fn error_stops_other_direction() {
+    let (a, _a_peer) = common::tcp_pair();
+    let (b, mut b_peer) = common::tcp_pair();
+    let a = ReadWrite::new(FailingReader(3), a);
+    let b = ReadWrite::from_stream(b).unwrap();
+
+    // The peer of `b` never sends anything nor closes, so the b->a direction
+    // only finishes because the failed direction shuts down `b`'s connection
+    let e = copy_bidirectional(a, b).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::ConnectionReset);
+    let counts = e
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<CopyBidirectionalError>()
+        .unwrap();
+    assert_eq!((counts.a_to_b, counts.b_to_a), (3, 0));
+
+    let mut got = Vec::new();
+    b_peer.read_to_end(&mut got).unwrap();
+    assert_eq!(got, b"xxx");
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_copy {
+    extern crate tokio_dep as tokio;
+
+    use std::io::ErrorKind;
+    use std::task::Poll;
+
+    use readwrite::{copy_bidirectional_tokio, FnWriterTokio, ReadWriteTokio};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn half_close_ordering() {
+        let (mut a, mut a_peer) = readwrite::duplex_tokio(64);
+        let (mut b, mut b_peer) = readwrite::duplex_tokio(64);
+        let proxy = tokio::spawn(async move { copy_bidirectional_tokio(&mut a, &mut b).await });
+
+        a_peer.write_all(b"request").await.unwrap();
+        a_peer.shutdown().await.unwrap();
+        let mut got = Vec::new();
+        b_peer.read_to_end(&mut got).await.unwrap();
+        assert_eq!(got, b"request");
+
+        b_peer.write_all(b"reply").await.unwrap();
+        b_peer.shutdown().await.unwrap();
+        got.clear();
+        a_peer.read_to_end(&mut got).await.unwrap();
+        assert_eq!(got, b"reply");
+
+        assert_eq!(proxy.await.unwrap().unwrap(), (7, 5));
+    }
+
+    #[tokio::test]
+    async fn error_aborts_both_directions() {
+        let mut failing = ReadWriteTokio::new(
+            tokio::io::empty(),
+            FnWriterTokio::new(|_, _| Poll::Ready(Err(ErrorKind::BrokenPipe.into()))),
+        );
+        let (mut b, mut b_peer) = readwrite::duplex_tokio(64);
+        b_peer.write_all(b"data").await.unwrap();
+
+        let e = copy_bidirectional_tokio(&mut failing, &mut b)
+            .await
+            .unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::BrokenPipe);
+    }
+}
@@ -0,0 +1,235 @@
+use std::io::{ErrorKind, Write};
+
+use readwrite::{Corkable, ReadWrite};
+
+/// Writer counting underlying calls, accepting at most `max_write` bytes per write
+struct CountingWriter {
+    data: Vec<u8>,
+    writes: usize,
+    flushes: usize,
+    max_write: usize,
+    /// Fail the write with this number (counting from 1) once
+    fail_at: Option<usize>,
+}
+
+impl CountingWriter {
+    fn new() -> Self {
+        CountingWriter {
+            data: Vec::new(),
+            writes: 0,
+            flushes: 0,
+            max_write: usize::MAX,
+            fail_at: None,
+        }
+    }
+
+    fn write_chunk(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes += 1;
+        if self.fail_at == Some(self.writes) {
+            self.fail_at = None;
+            return Err(ErrorKind::ConnectionReset.into());
+        }
+        let n = buf.len().min(self.max_write);
+        self.data.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.write_chunk(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+fn counting() -> Corkable<ReadWrite<std::io::Empty, CountingWriter>> {
+    ReadWrite::new(std::io::empty(), CountingWriter::new()).corkable()
+}
+
+fn writer(c: &Corkable<ReadWrite<std::io::Empty, CountingWriter>>) -> &CountingWriter {
+    c.get_ref().borrow_write()
+}
+
+#[test]
+fn corked_writes_are_batched() {
+    let mut c = counting();
+    c.cork();
+    c.write_all(b"HDR").unwrap();
+    c.write_all(&5u32.to_be_bytes()).unwrap();
+    c.write_all(b"body!").unwrap();
+    assert_eq!(writer(&c).writes, 0);
+    assert_eq!(c.pending().len(), 12);
+
+    c.uncork().unwrap();
+    assert_eq!(writer(&c).writes, 1);
+    assert_eq!(writer(&c).flushes, 1);
+    assert_eq!(writer(&c).data, b"HDR\0\0\0\x05body!");
+    assert!(c.pending().is_empty());
+}
+
+#[test]
+fn uncorked_writes_pass_through() {
+    let mut c = counting();
+    c.write_all(b"a").unwrap();
+    c.write_all(b"b").unwrap();
+    assert_eq!(writer(&c).writes, 2);
+    assert_eq!(writer(&c).flushes, 0);
+}
+
+#[test]
+fn nested_corks() {
+    let mut c = counting();
+    c.cork();
+    c.write_all(b"outer,").unwrap();
+    c.cork();
+    c.write_all(b"inner").unwrap();
+    c.uncork().unwrap();
+    assert!(c.is_corked());
+    assert_eq!(writer(&c).writes, 0);
+    c.uncork().unwrap();
+    assert!(!c.is_corked());
+    assert_eq!(writer(&c).writes, 1);
+    assert_eq!(writer(&c).data, b"outer,inner");
+}
+
+#[test]
+fn uncork_with_empty_buffer_only_flushes() {
+    let mut c = counting();
+    c.cork();
+    c.uncork().unwrap();
+    assert_eq!(writer(&c).writes, 0);
+    assert_eq!(writer(&c).flushes, 1);
+}
+
+#[test]
+fn failed_uncork_keeps_remainder() {
+    let mut c = counting();
+    c.get_mut().borrow_mut_write().max_write = 4;
+    c.get_mut().borrow_mut_write().fail_at = Some(2);
+    c.cork();
+    c.write_all(b"abcdefghij").unwrap();
+
+    let err = c.uncork().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ConnectionReset);
+    assert_eq!(writer(&c).data, b"abcd");
+    assert_eq!(c.pending(), b"efghij");
+
+    // Uncorking again retries the remainder
+    c.uncork().unwrap();
+    assert_eq!(writer(&c).data, b"abcdefghij");
+    assert!(c.pending().is_empty());
+}
+
+#[test]
+fn remainder_can_be_taken_away() {
+    let mut c = counting();
+    c.get_mut().borrow_mut_write().fail_at = Some(1);
+    c.cork();
+    c.write_all(b"lost?").unwrap();
+    assert!(c.uncork().is_err());
+    assert_eq!(c.take_pending(), b"lost?");
+    c.uncork().unwrap();
+    assert!(writer(&c).data.is_empty());
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_cork {
+    extern crate tokio_dep as tokio;
+
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use readwrite::ReadWriteTokio;
+    use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+    use super::CountingWriter;
+
+    impl AsyncWrite for CountingWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(self.get_mut().write_chunk(buf))
+        }
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.get_mut().flushes += 1;
+            Poll::Ready(Ok(()))
+        }
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn corked_writes_are_batched() {
+        let mut c = ReadWriteTokio::new(tokio::io::empty(), CountingWriter::new()).corkable();
+        c.cork();
+        c.write_all(b"one,").await.unwrap();
+        c.write_all(b"two").await.unwrap();
+        c.uncork().await.unwrap();
+        let w = c.get_ref().borrow_write();
+        assert_eq!((w.writes, w.flushes), (1, 1));
+        assert_eq!(w.data, b"one,two");
+    }
+
+    #[tokio::test]
+    async fn shutdown_uncorks() {
+        let mut c = ReadWriteTokio::new(tokio::io::empty(), CountingWriter::new()).corkable();
+        c.cork();
+        c.cork();
+        c.write_all(b"pending").await.unwrap();
+        c.shutdown().await.unwrap();
+        assert!(!c.is_corked());
+        let w = c.get_ref().borrow_write();
+        assert_eq!(w.writes, 1);
+        assert_eq!(w.data, b"pending");
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_cork {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use futures::executor::block_on;
+    use futures::io::{AsyncWrite, AsyncWriteExt};
+    use readwrite::ReadWriteAsyncstd;
+
+    use super::CountingWriter;
+
+    impl AsyncWrite for CountingWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(self.get_mut().write_chunk(buf))
+        }
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.get_mut().flushes += 1;
+            Poll::Ready(Ok(()))
+        }
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn close_uncorks() {
+        block_on(async {
+            let mut c =
+                ReadWriteAsyncstd::new(futures::io::empty(), CountingWriter::new()).corkable();
+            c.cork();
+            c.write_all(b"a").await.unwrap();
+            c.write_all(b"b").await.unwrap();
+            c.close().await.unwrap();
+            let w = c.get_ref().borrow_write();
+            assert_eq!(w.writes, 1);
+            assert_eq!(w.data, b"ab");
+        });
+    }
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use std::io::{ErrorKind, Read, Write};
+use std::time::Duration;
+
+use readwrite::{echo, echo_with, ReadWrite};
+
+#[test]
+fn interleaved_reads_and_writes_keep_order() {
+    let mut e = echo();
+    e.write_all(b"ab").unwrap();
+    let mut one = [0u8; 1];
+    e.read_exact(&mut one).unwrap();
+    assert_eq!(&one, b"a");
+    e.write_all(b"cd").unwrap();
+    e.write_all(b"ef").unwrap();
+    let mut rest = [0u8; 5];
+    e.read_exact(&mut rest).unwrap();
+    assert_eq!(&rest, b"bcdef");
+    assert_eq!(e.available(), 0);
+}
+
+#[test]
+fn empty_read_would_block() {
+    let mut e = echo();
+    assert_eq!(
+        e.read(&mut [0u8; 4]).unwrap_err().kind(),
+        ErrorKind::WouldBlock
+    );
+}
+
+#[test]
+fn blocking_read_waits_for_another_clone() {
+    let mut reader = echo();
+    let mut writer = reader.clone();
+    reader.set_blocking(true);
+    let t = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        writer.write_all(b"late").unwrap();
+    });
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"late");
+    t.join().unwrap();
+}
+
+#[test]
+fn mapped_echo_with_lag() {
+    // Uppercases and holds back the last two bytes written so far
+    let mut held = Vec::new();
+    let mut e = echo_with(move |data: &[u8]| {
+        held.extend(data.iter().map(u8::to_ascii_uppercase));
+        let ready = held.len().saturating_sub(2);
+        held.drain(..ready).collect()
+    });
+    e.write_all(b"hel").unwrap();
+    assert_eq!(e.available(), 1);
+    e.write_all(b"lo").unwrap();
+    let mut buf = [0u8; 3];
+    e.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"HEL");
+    assert!(e.read(&mut buf).is_err());
+}
+
+#[test]
+fn client_code_against_echo() {
+    let e = echo();
+    let mut rw = ReadWrite::new(e.clone(), e);
+    rw.write_all(b"PING\n").unwrap();
+    let mut reply = [0u8; 5];
+    rw.read_exact(&mut reply).unwrap();
+    assert_eq!(&reply, b"PING\n");
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_echo {
+    extern crate tokio_dep as tokio;
+
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Waker};
+
+    use readwrite::{echo, ReadWriteTokio};
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, ReadBuf};
+
+    use super::common::CountingWaker;
+
+    fn poll_read(e: &mut readwrite::Echo, waker: &Waker, storage: &mut [u8]) -> Poll<usize> {
+        let mut cx = Context::from_waker(waker);
+        let mut buf = ReadBuf::new(storage);
+        match Pin::new(e).poll_read(&mut cx, &mut buf) {
+            Poll::Ready(r) => {
+                r.unwrap();
+                Poll::Ready(buf.filled().len())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    #[test]
+    fn pending_read_is_woken_by_write() {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = counter.clone().into();
+        let mut e = echo();
+        let mut storage = [0u8; 8];
+        assert!(poll_read(&mut e, &waker, &mut storage).is_pending());
+        assert!(poll_read(&mut e, &waker, &mut storage).is_pending());
+        assert_eq!(counter.count(), 0);
+
+        let mut writer = e.clone();
+        std::io::Write::write_all(&mut writer, b"wake").unwrap();
+        assert_eq!(counter.count(), 1);
+        assert_eq!(poll_read(&mut e, &waker, &mut storage), Poll::Ready(4));
+        assert_eq!(&storage[..4], b"wake");
+    }
+
+    #[test]
+    fn all_waiting_clones_are_woken() {
+        let first = Arc::new(CountingWaker::default());
+        let second = Arc::new(CountingWaker::default());
+        let (first_waker, second_waker) = (first.clone().into(), second.clone().into());
+        let mut a = echo();
+        let mut b = a.clone();
+        let mut storage = [0u8; 8];
+        assert!(poll_read(&mut a, &first_waker, &mut storage).is_pending());
+        assert!(poll_read(&mut b, &second_waker, &mut storage).is_pending());
+
+        std::io::Write::write_all(&mut a.clone(), b"x").unwrap();
+        assert_eq!((first.count(), second.count()), (1, 1));
+
+        // One of them gets the data, the other one waits again and is woken by the next write
+        assert_eq!(
+            poll_read(&mut b, &second_waker, &mut storage),
+            Poll::Ready(1)
+        );
+        assert!(poll_read(&mut a, &first_waker, &mut storage).is_pending());
+        std::io::Write::write_all(&mut b, b"y").unwrap();
+        assert_eq!(first.count(), 2);
+    }
+
+    #[tokio::test]
+    async fn as_tokio_pseudo_socket() {
+        let e = echo();
+        let mut rw = ReadWriteTokio::new(e.clone(), e.clone());
+        let mut writer = e;
+        let task = tokio::spawn(async move {
+            let mut buf = [0u8; 10];
+            rw.read_exact(&mut buf).await.unwrap();
+            buf
+        });
+        tokio::task::yield_now().await;
+        writer.write_all(b"hello").await.unwrap();
+        tokio::task::yield_now().await;
+        writer.write_all(b"world").await.unwrap();
+        assert_eq!(&task.await.unwrap(), b"helloworld");
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_echo {
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
+    use futures::io::AsyncRead;
+    use readwrite::echo;
+
+    use super::common::CountingWaker;
+
+    #[test]
+    fn pending_read_is_woken_by_write() {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = counter.clone().into();
+        let mut cx = Context::from_waker(&waker);
+        let mut e = echo();
+        let mut buf = [0u8; 8];
+        assert!(Pin::new(&mut e).poll_read(&mut cx, &mut buf).is_pending());
+
+        std::io::Write::write_all(&mut e.clone(), b"ok").unwrap();
+        assert_eq!(counter.count(), 1);
+        match Pin::new(&mut e).poll_read(&mut cx, &mut buf) {
+            Poll::Ready(n) => assert_eq!(n.unwrap(), 2),
+            Poll::Pending => panic!("data was written"),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "embedded-io")]
+
+use embedded_io::{Error, ErrorKind, ErrorType, Read, ReadReady, Write, WriteReady};
+use readwrite::{EitherError, ReadWriteEmbedded};
+
+/// Reader failing with its own error type, different from the writer's
+struct BrokenReader;
+
+impl ErrorType for BrokenReader {
+    type Error = ErrorKind;
+}
+
+impl Read for BrokenReader {
+    fn read(&mut self, _buf: &mut [u8]) -> Result<usize, ErrorKind> {
+        Err(ErrorKind::ConnectionReset)
+    }
+}
+
+#[test]
+fn slices_round_trip() {
+    let mut out = [0u8; 8];
+    let mut rw = ReadWriteEmbedded::new(&b"incoming"[..], &mut out[..]);
+    assert!(rw.read_ready().unwrap());
+    assert!(rw.write_ready().unwrap());
+
+    let mut buf = [0; 8];
+    rw.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"incoming");
+    rw.write_all(b"outgoing").unwrap();
+    rw.flush().unwrap();
+    assert_eq!(&out, b"outgoing");
+}
+
+#[test]
+fn errors_tell_which_half_failed() {
+    let mut out = [0u8; 2];
+    let mut rw = ReadWriteEmbedded::new(BrokenReader, &mut out[..]);
+
+    let e = rw.read(&mut [0; 4]).unwrap_err();
+    assert_eq!(e, EitherError::Read(ErrorKind::ConnectionReset));
+    assert_eq!(e.kind(), ErrorKind::ConnectionReset);
+
+    assert_eq!(rw.write(b"abc").unwrap(), 2);
+    let e = rw.write(b"c").unwrap_err();
+    assert!(matches!(e, EitherError::Write(_)));
+    assert_eq!(e.kind(), ErrorKind::WriteZero);
+}
+
+#[cfg(feature = "embedded-io-async")]
+mod embedded_async {
+    use embedded_io_async::{Read, Write};
+    use readwrite::ReadWriteEmbedded;
+
+    #[test]
+    fn slices_round_trip() {
+        futures::executor::block_on(async {
+            let mut out = [0u8; 4];
+            let mut rw = ReadWriteEmbedded::new(&b"ping"[..], &mut out[..]);
+            let mut buf = [0; 4];
+            rw.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"ping");
+            rw.write_all(b"pong").await.unwrap();
+            rw.flush().await.unwrap();
+            assert_eq!(&out, b"pong");
+        });
+    }
+}
@@ -0,0 +1,66 @@
+use std::io::{ErrorKind, Read};
+
+use readwrite::ReadWrite;
+
+#[test]
+fn eof_after_partial_data_is_an_error() {
+    let mut rw = ReadWrite::new(&b"partial"[..], std::io::sink()).error_on_eof();
+    let mut buf = [0u8; 4];
+    assert_eq!(rw.read(&mut buf).unwrap(), 4);
+    assert_eq!(rw.read(&mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"ial");
+    let err = rw.read(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    // Keeps failing, the reader does not forget that data was seen
+    let err = rw.read(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn immediate_eof_is_still_ok() {
+    let mut rw = ReadWrite::new(std::io::empty(), std::io::sink()).error_on_eof();
+    assert_eq!(rw.read(&mut [0u8; 4]).unwrap(), 0);
+    assert_eq!(rw.read(&mut [0u8; 4]).unwrap(), 0);
+    assert!(!rw.borrow_read().seen_data());
+}
+
+#[test]
+fn empty_buffer_is_not_eof() {
+    let mut rw = ReadWrite::new(&b"x"[..], std::io::sink()).error_on_eof();
+    assert_eq!(rw.read(&mut [0u8; 1]).unwrap(), 1);
+    assert_eq!(rw.read(&mut []).unwrap(), 0);
+    assert!(rw.borrow_read().seen_data());
+}
+
+#[test]
+fn read_to_end_fails_after_data() {
+    let mut rw = ReadWrite::new(&b"truncated"[..], std::io::sink()).error_on_eof();
+    let mut data = Vec::new();
+    let err = rw.read_to_end(&mut data).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(data, b"truncated");
+}
+
+#[test]
+fn eof_between_messages_after_message_done() {
+    let mut rw = ReadWrite::new(&b"msg1msg2"[..], std::io::sink()).error_on_eof();
+    let mut msg = [0u8; 4];
+    for expected in [b"msg1", b"msg2"] {
+        rw.read_exact(&mut msg).unwrap();
+        assert_eq!(&msg, expected);
+        rw.borrow_mut_read().message_done();
+        assert!(!rw.borrow_read().seen_data());
+    }
+    // The peer closed the connection cleanly at a message boundary
+    assert_eq!(rw.read(&mut msg).unwrap(), 0);
+}
+
+#[test]
+fn eof_mid_message_after_message_done() {
+    let mut rw = ReadWrite::new(&b"msg1ms"[..], std::io::sink()).error_on_eof();
+    let mut msg = [0u8; 4];
+    rw.read_exact(&mut msg).unwrap();
+    rw.borrow_mut_read().message_done();
+    let err = rw.read_exact(&mut msg).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,239 @@
+use std::io::{ErrorKind, Write};
+
+use readwrite::ReadWrite;
+
+/// Writer which only exposes data to the peer once flushed, optionally failing the flush
+#[derive(Default)]
+struct Staging {
+    staged: Vec<u8>,
+    visible: Vec<u8>,
+    fail_flush: bool,
+    /// Number of flushes that reached this writer
+    flushes: usize,
+}
+
+impl Staging {
+    fn stage(&mut self, buf: &[u8]) -> usize {
+        self.staged.extend_from_slice(buf);
+        buf.len()
+    }
+
+    fn publish(&mut self) -> std::io::Result<()> {
+        self.flushes += 1;
+        if self.fail_flush {
+            return Err(ErrorKind::BrokenPipe.into());
+        }
+        self.visible.append(&mut self.staged);
+        Ok(())
+    }
+}
+
+impl Write for Staging {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(self.stage(buf))
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.publish()
+    }
+}
+
+#[test]
+fn data_is_visible_after_the_call() {
+    let mut rw = ReadWrite::new(std::io::empty(), Staging::default());
+    rw.write_all(b"no flush").unwrap();
+    assert!(rw.borrow_write().visible.is_empty());
+    rw.write_all_and_flush(b", request\n").unwrap();
+    assert_eq!(rw.borrow_write().visible, b"no flush, request\n");
+}
+
+#[test]
+fn flush_error_is_surfaced() {
+    let staging = Staging {
+        fail_flush: true,
+        ..Staging::default()
+    };
+    let mut rw = ReadWrite::new(std::io::empty(), staging);
+    let err = rw.write_all_and_flush(b"request").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    // The write itself has succeeded
+    assert_eq!(rw.borrow_write().staged, b"request");
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_flush {
+    extern crate tokio_dep as tokio;
+
+    use std::io::ErrorKind;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use readwrite::ReadWriteTokio;
+    use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+    use super::Staging;
+
+    impl AsyncWrite for Staging {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(Ok(self.get_mut().stage(buf)))
+        }
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(self.get_mut().publish())
+        }
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn data_is_visible_after_the_call() {
+        let mut rw = ReadWriteTokio::new(tokio::io::empty(), Staging::default());
+        rw.write_all_and_flush(b"request\n").await.unwrap();
+        assert_eq!(rw.borrow_write().visible, b"request\n");
+    }
+
+    #[tokio::test]
+    async fn flush_error_is_surfaced() {
+        let staging = Staging {
+            fail_flush: true,
+            ..Staging::default()
+        };
+        let mut rw = ReadWriteTokio::new(tokio::io::empty(), staging);
+        let err = rw.write_all_and_flush(b"request").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+        assert_eq!(rw.borrow_write().staged, b"request");
+    }
+
+    #[tokio::test]
+    async fn redundant_flushes_are_elided() {
+        let mut rw = ReadWriteTokio::new(tokio::io::empty(), Staging::default());
+        // The writer may hold data from before it was wrapped, so the first flush goes through
+        rw.flush().await.unwrap();
+        rw.flush().await.unwrap();
+        assert_eq!(rw.borrow_write().flushes, 1);
+
+        rw.write_all(b"a").await.unwrap();
+        rw.write_all(b"b").await.unwrap();
+        rw.flush().await.unwrap();
+        rw.flush().await.unwrap();
+        rw.flush().await.unwrap();
+        assert_eq!(rw.borrow_write().flushes, 2);
+        assert_eq!(rw.borrow_write().visible, b"ab");
+    }
+
+    #[tokio::test]
+    async fn failed_flush_is_retried() {
+        let mut rw = ReadWriteTokio::new(tokio::io::empty(), Staging::default());
+        rw.write_all(b"x").await.unwrap();
+        rw.borrow_mut_write().fail_flush = true;
+        assert!(rw.flush().await.is_err());
+        rw.borrow_mut_write().fail_flush = false;
+        rw.flush().await.unwrap();
+        assert_eq!(rw.borrow_write().flushes, 2);
+        assert_eq!(rw.borrow_write().visible, b"x");
+    }
+
+    #[tokio::test]
+    async fn reader_access_keeps_writer_clean() {
+        let mut rw = ReadWriteTokio::new(&b"in"[..], Staging::default());
+        rw.flush().await.unwrap();
+        let mut buf = [0u8; 2];
+        rw.read_exact(&mut buf).await.unwrap();
+        let _ = Pin::new(&mut rw).borrow_pin_read();
+        let _ = rw.borrow_mut_read();
+        rw.flush().await.unwrap();
+        assert_eq!(rw.borrow_write().flushes, 1);
+
+        // Mutable access to the writer counts as writing
+        rw.borrow_mut_write().staged.extend_from_slice(b"direct");
+        rw.flush().await.unwrap();
+        assert_eq!(rw.borrow_write().flushes, 2);
+        assert_eq!(rw.borrow_write().visible, b"direct");
+    }
+
+    #[tokio::test]
+    async fn writes_through_projected_halves_are_flushed() {
+        let mut rw = ReadWriteTokio::new(tokio::io::empty(), tokio::io::BufWriter::new(Vec::new()));
+        rw.flush().await.unwrap();
+
+        // Hand-written poll code writing into the buffering writer behind the combined object's back
+        std::future::poll_fn(|cx| {
+            let (_r, w) = Pin::new(&mut rw).project_halves();
+            w.poll_write(cx, b"projected")
+        })
+        .await
+        .unwrap();
+        assert!(rw.borrow_write().get_ref().is_empty());
+
+        rw.flush().await.unwrap();
+        assert_eq!(rw.borrow_write().get_ref(), b"projected");
+
+        std::future::poll_fn(|cx| Pin::new(&mut rw).borrow_pin().1.poll_write(cx, b", pinned"))
+            .await
+            .unwrap();
+        rw.flush().await.unwrap();
+        assert_eq!(rw.borrow_write().get_ref(), b"projected, pinned");
+    }
+
+    #[tokio::test]
+    async fn shutdown_forces_a_flush() {
+        let mut rw = ReadWriteTokio::new(tokio::io::empty(), Staging::default());
+        rw.flush().await.unwrap();
+        rw.shutdown().await.unwrap();
+        assert_eq!(rw.borrow_write().flushes, 2);
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_flush {
+    use std::io::ErrorKind;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use futures::executor::block_on;
+    use futures::io::AsyncWrite;
+    use readwrite::ReadWriteAsyncstd;
+
+    use super::Staging;
+
+    impl AsyncWrite for Staging {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(Ok(self.get_mut().stage(buf)))
+        }
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(self.get_mut().publish())
+        }
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn data_is_visible_after_the_call() {
+        block_on(async {
+            let mut rw = ReadWriteAsyncstd::new(futures::io::empty(), Staging::default());
+            rw.write_all_and_flush(b"request\n").await.unwrap();
+            assert_eq!(rw.borrow_write().visible, b"request\n");
+        });
+    }
+
+    #[test]
+    fn flush_error_is_surfaced() {
+        block_on(async {
+            let staging = Staging {
+                fail_flush: true,
+                ..Staging::default()
+            };
+            let mut rw = ReadWriteAsyncstd::new(futures::io::empty(), staging);
+            let err = rw.write_all_and_flush(b"request").await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+        });
+    }
+}
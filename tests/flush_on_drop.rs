@@ -0,0 +1,118 @@
+use std::io::{BufRead, ErrorKind, Write};
+use std::sync::{Arc, Mutex};
+
+use readwrite::ReadWrite;
+
+/// Buffering writer which, unlike `BufWriter`, does not flush itself when dropped.
+/// Flushed data lands in a shared `Vec`, standing in for the peer.
+struct Buffering {
+    buf: Vec<u8>,
+    peer: Arc<Mutex<Vec<u8>>>,
+    fail_flush: bool,
+}
+
+impl Buffering {
+    fn new() -> (Self, Arc<Mutex<Vec<u8>>>) {
+        let peer = Arc::new(Mutex::new(Vec::new()));
+        let w = Buffering {
+            buf: Vec::new(),
+            peer: peer.clone(),
+            fail_flush: false,
+        };
+        (w, peer)
+    }
+}
+
+impl Write for Buffering {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.fail_flush {
+            return Err(ErrorKind::BrokenPipe.into());
+        }
+        self.peer.lock().unwrap().append(&mut self.buf);
+        Ok(())
+    }
+}
+
+#[test]
+fn tail_is_lost_without_the_guard() {
+    let (w, peer) = Buffering::new();
+    let mut rw = ReadWrite::new(std::io::empty(), w);
+    rw.write_all(b"last message").unwrap();
+    drop(rw);
+    assert!(peer.lock().unwrap().is_empty());
+}
+
+#[test]
+fn tail_is_flushed_by_the_guard() {
+    let (w, peer) = Buffering::new();
+    let mut rw = ReadWrite::new(std::io::empty(), w).flush_on_drop();
+    rw.write_all(b"last message").unwrap();
+    drop(rw);
+    assert_eq!(*peer.lock().unwrap(), b"last message");
+}
+
+#[test]
+fn flush_error_on_drop_goes_to_callback() {
+    let (mut w, _peer) = Buffering::new();
+    w.fail_flush = true;
+    let reported = Arc::new(Mutex::new(None));
+    let reported2 = reported.clone();
+    let mut rw = ReadWrite::new(std::io::empty(), w)
+        .flush_on_drop()
+        .on_error(move |e| *reported2.lock().unwrap() = Some(e.kind()));
+    rw.write_all(b"data").unwrap();
+    drop(rw);
+    assert_eq!(*reported.lock().unwrap(), Some(ErrorKind::BrokenPipe));
+}
+
+#[test]
+fn finish_returns_parts_or_the_error() {
+    let (w, peer) = Buffering::new();
+    let mut rw = ReadWrite::new(std::io::empty(), w).flush_on_drop();
+    rw.write_all(b"done").unwrap();
+    let (_r, w) = rw.finish().unwrap();
+    assert!(w.buf.is_empty());
+    assert_eq!(*peer.lock().unwrap(), b"done");
+
+    let (mut w, _peer) = Buffering::new();
+    w.fail_flush = true;
+    let called = Arc::new(Mutex::new(false));
+    let called2 = called.clone();
+    let mut rw = ReadWrite::new(std::io::empty(), w)
+        .flush_on_drop()
+        .on_error(move |_| *called2.lock().unwrap() = true);
+    rw.write_all(b"data").unwrap();
+    let err = rw.finish().err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    // The error was returned, so the guard does not flush or report again
+    assert!(!*called.lock().unwrap());
+}
+
+#[test]
+fn buf_read_passthrough() {
+    let mut rw = ReadWrite::new(&b"line one\nline two\n"[..], std::io::sink()).flush_on_drop();
+    let mut line = String::new();
+    rw.read_line(&mut line).unwrap();
+    assert_eq!(line, "line one\n");
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_finish {
+    extern crate tokio_dep as tokio;
+
+    use readwrite::ReadWriteTokio;
+    use tokio::io::{AsyncWriteExt, BufWriter};
+
+    #[tokio::test]
+    async fn finish_flushes() {
+        let mut rw = ReadWriteTokio::new(tokio::io::empty(), BufWriter::new(Vec::new()));
+        rw.write_all(b"tail").await.unwrap();
+        assert!(rw.borrow_write().get_ref().is_empty());
+        let (_r, w) = rw.finish().await.unwrap();
+        assert_eq!(w.get_ref(), b"tail");
+    }
+}
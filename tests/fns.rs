@@ -0,0 +1,188 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::rc::Rc;
+
+use readwrite::{from_fns, from_fns_with_flush};
+
+/// Protocol code only knowing about `Read + Write`
+fn request<S: Read + Write>(s: &mut S, msg: &[u8]) -> Vec<u8> {
+    s.write_all(msg).unwrap();
+    s.flush().unwrap();
+    let mut buf = vec![0; msg.len()];
+    s.read_exact(&mut buf).unwrap();
+    buf
+}
+
+#[test]
+fn closure_echo() {
+    let queue = Rc::new(RefCell::new(VecDeque::new()));
+    let mut rw = from_fns(
+        {
+            let queue = queue.clone();
+            move |buf: &mut [u8]| queue.borrow_mut().read(buf)
+        },
+        {
+            let queue = queue.clone();
+            move |buf: &[u8]| {
+                queue.borrow_mut().extend(buf);
+                Ok(buf.len())
+            }
+        },
+    );
+    assert_eq!(request(&mut rw, b"hello"), b"hello");
+    assert_eq!(request(&mut rw, b"again"), b"again");
+    assert!(queue.borrow().is_empty());
+}
+
+#[test]
+fn closure_flush() {
+    let flushes = Rc::new(RefCell::new(0));
+    let mut rw = from_fns_with_flush(|_: &mut [u8]| Ok(0), |buf: &[u8]| Ok(buf.len()), {
+        let flushes = flushes.clone();
+        move || {
+            *flushes.borrow_mut() += 1;
+            Ok(())
+        }
+    });
+    rw.write_all(b"data").unwrap();
+    rw.flush().unwrap();
+    assert_eq!(*flushes.borrow(), 1);
+    let mut buf = [0; 4];
+    assert_eq!(rw.read(&mut buf).unwrap(), 0);
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_poll_fns {
+    extern crate tokio_dep as tokio;
+
+    use std::task::Poll;
+
+    use readwrite::{from_poll_fns_tokio, ReadWriteTokio};
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+    /// Transport sending each write as a message, assembled purely from closures.
+    /// Shutting down drops the sender, which the peer sees as EOF.
+    fn transport(
+        tx: UnboundedSender<Vec<u8>>,
+        mut rx: UnboundedReceiver<Vec<u8>>,
+    ) -> impl AsyncRead + AsyncWrite + Unpin {
+        let tx = std::sync::Arc::new(std::sync::Mutex::new(Some(tx)));
+        let tx2 = tx.clone();
+        let mut leftover = Vec::new();
+        ReadWriteTokio::from_poll_fns(
+            move |cx, buf| {
+                if leftover.is_empty() {
+                    match rx.poll_recv(cx) {
+                        Poll::Ready(Some(msg)) => leftover = msg,
+                        Poll::Ready(None) => return Poll::Ready(Ok(())),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+                let n = leftover.len().min(buf.remaining());
+                buf.put_slice(&leftover[..n]);
+                leftover.drain(..n);
+                Poll::Ready(Ok(()))
+            },
+            move |_cx, buf| {
+                let sent = tx.lock().unwrap().as_ref().map(|tx| tx.send(buf.to_vec()));
+                match sent {
+                    Some(Ok(())) => Poll::Ready(Ok(buf.len())),
+                    _ => Poll::Ready(Err(std::io::ErrorKind::BrokenPipe.into())),
+                }
+            },
+            |_cx| Poll::Ready(Ok(())),
+            move |_cx| {
+                tx2.lock().unwrap().take();
+                Poll::Ready(Ok(()))
+            },
+        )
+    }
+
+    fn channel_pair() -> (
+        impl AsyncRead + AsyncWrite + Unpin,
+        impl AsyncRead + AsyncWrite + Unpin,
+    ) {
+        let (tx_a, rx_a) = unbounded_channel();
+        let (tx_b, rx_b) = unbounded_channel();
+        (transport(tx_a, rx_b), transport(tx_b, rx_a))
+    }
+
+    #[tokio::test]
+    async fn channel_transport_through_copy() {
+        let (mut a, mut b) = channel_pair();
+        let payload: Vec<u8> = (0..10_000u32).map(|x| x as u8).collect();
+
+        let copied = tokio::io::copy(&mut &payload[..], &mut a).await.unwrap();
+        assert_eq!(copied, payload.len() as u64);
+        a.shutdown().await.unwrap();
+        assert!(a.write_all(b"late").await.is_err());
+
+        let mut received = Vec::new();
+        tokio::io::copy(&mut b, &mut received).await.unwrap();
+        assert_eq!(received, payload);
+    }
+
+    #[tokio::test]
+    async fn channel_transport_both_ways() {
+        let (mut a, mut b) = channel_pair();
+        let server = tokio::spawn(async move {
+            let mut buf = [0u8; 4];
+            b.read_exact(&mut buf).await.unwrap();
+            b.write_all(b"pong").await.unwrap();
+            buf
+        });
+        a.write_all(b"ping").await.unwrap();
+        let mut reply = [0u8; 4];
+        a.read_exact(&mut reply).await.unwrap();
+        assert_eq!(&reply, b"pong");
+        assert_eq!(&server.await.unwrap(), b"ping");
+    }
+
+    #[tokio::test]
+    async fn default_flush_and_shutdown_are_noops() {
+        let mut rw = from_poll_fns_tokio(
+            |_cx, _buf| Poll::Ready(Ok(())),
+            |_cx, buf: &[u8]| Poll::Ready(Ok(buf.len())),
+        );
+        rw.write_all(b"ignored").await.unwrap();
+        rw.flush().await.unwrap();
+        rw.shutdown().await.unwrap();
+        let mut buf = Vec::new();
+        assert_eq!(rw.read_to_end(&mut buf).await.unwrap(), 0);
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_poll_fns {
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+    use std::task::Poll;
+
+    use futures::executor::block_on;
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+    use readwrite::from_poll_fns_asyncstd;
+
+    #[test]
+    fn closure_echo() {
+        block_on(async {
+            let queue = Arc::new(Mutex::new(VecDeque::new()));
+            let queue2 = queue.clone();
+            let mut rw = from_poll_fns_asyncstd(
+                move |_cx, buf: &mut [u8]| {
+                    Poll::Ready(std::io::Read::read(&mut *queue.lock().unwrap(), buf))
+                },
+                move |_cx, buf: &[u8]| {
+                    queue2.lock().unwrap().extend(buf);
+                    Poll::Ready(Ok(buf.len()))
+                },
+            );
+            rw.write_all(b"hello").await.unwrap();
+            rw.close().await.unwrap();
+            let mut buf = [0u8; 5];
+            rw.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello");
+        });
+    }
+}
@@ -0,0 +1,95 @@
+use readwrite::{GenericRead, GenericReadWrite, GenericWrite};
+
+#[derive(Debug, PartialEq)]
+enum RadioError {
+    Noise,
+}
+
+#[derive(Debug, PartialEq)]
+enum ProtocolError {
+    Radio(RadioError),
+    Full,
+    Io(std::io::ErrorKind),
+}
+
+impl From<RadioError> for ProtocolError {
+    fn from(e: RadioError) -> Self {
+        ProtocolError::Radio(e)
+    }
+}
+
+impl From<std::io::Error> for ProtocolError {
+    fn from(e: std::io::Error) -> Self {
+        ProtocolError::Io(e.kind())
+    }
+}
+
+/// Reader with its own error enum, yielding one byte and then failing
+struct Radio(bool);
+
+impl GenericRead for Radio {
+    type Error = RadioError;
+    fn generic_read(&mut self, buf: &mut [u8]) -> Result<usize, RadioError> {
+        if std::mem::replace(&mut self.0, false) {
+            buf[0] = 7;
+            return Ok(1);
+        }
+        Err(RadioError::Noise)
+    }
+}
+
+/// Writer which already reports the combined error type
+struct Leds(Vec<u8>);
+
+impl GenericWrite for Leds {
+    type Error = ProtocolError;
+    fn generic_write(&mut self, buf: &[u8]) -> Result<usize, ProtocolError> {
+        if self.0.len() >= 2 {
+            return Err(ProtocolError::Full);
+        }
+        self.0.push(buf[0]);
+        Ok(1)
+    }
+    fn generic_flush(&mut self) -> Result<(), ProtocolError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn custom_error_surfaces_through_read() {
+    let mut rw: GenericReadWrite<_, _, ProtocolError> =
+        GenericReadWrite::new(Radio(true), Leds(Vec::new()));
+    let mut buf = [0; 4];
+    assert_eq!(rw.read(&mut buf), Ok(1));
+    assert_eq!(buf[0], 7);
+    assert_eq!(
+        rw.read(&mut buf),
+        Err(ProtocolError::Radio(RadioError::Noise))
+    );
+
+    assert_eq!(rw.write(b"ab"), Ok(1));
+    assert_eq!(rw.write(b"b"), Ok(1));
+    assert_eq!(rw.write(b"c"), Err(ProtocolError::Full));
+    rw.flush().unwrap();
+    assert_eq!(rw.into_writer().0, b"ab");
+}
+
+#[test]
+fn std_halves_through_adapters() {
+    use readwrite::{StdReader, StdWriter};
+
+    let mut rw: GenericReadWrite<_, _, ProtocolError> =
+        GenericReadWrite::new(StdReader(&b"std"[..]), StdWriter(Vec::new()));
+    let mut buf = [0; 3];
+    assert_eq!(rw.read(&mut buf), Ok(3));
+    assert_eq!(&buf, b"std");
+    assert_eq!(rw.write(b"xy"), Ok(2));
+    assert_eq!(rw.into_writer().0, b"xy");
+
+    let mut rw: GenericReadWrite<_, _, ProtocolError> = GenericReadWrite::new(
+        StdReader(std::io::empty()),
+        StdWriter(std::io::Cursor::new(&mut [0u8; 0][..])),
+    );
+    assert_eq!(rw.read(&mut buf), Ok(0));
+    assert_eq!(rw.write(b"z"), Ok(0));
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use std::io::{ErrorKind, Read, Write};
+
+use readwrite::ReadWrite;
+
+#[test]
+fn close_graceful_cloned_tcp_stream() {
+    let (client, mut server) = common::tcp_pair();
+    // Reader and writer are clones of the same socket, so dropping the writer alone would not send EOF
+    let mut rw = ReadWrite::from_stream(client).unwrap();
+
+    rw.write_all(b"bye").unwrap();
+    server.write_all(&[7; 100]).unwrap();
+
+    let peer = std::thread::spawn(move || {
+        let mut received = Vec::new();
+        server.read_to_end(&mut received).unwrap();
+        assert_eq!(received, b"bye");
+        // Dropping the server closes the connection, ending the drain
+    });
+    assert_eq!(rw.close_graceful(1000).unwrap(), 100);
+    peer.join().unwrap();
+}
+
+#[test]
+fn close_graceful_drain_limit() {
+    let (client, mut server) = common::tcp_pair();
+    let rw = ReadWrite::from_stream(client).unwrap();
+
+    server.write_all(&[7; 5000]).unwrap();
+    let e = rw.close_graceful(1000).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidData);
+    drop(server);
+}
+
+#[test]
+fn close_graceful_exact_limit() {
+    let rw = ReadWrite::new(&[1u8; 1000][..], Vec::new());
+    assert_eq!(rw.close_graceful(1000).unwrap(), 1000);
+    let rw = ReadWrite::new(&[1u8; 1001][..], Vec::new());
+    assert_eq!(
+        rw.close_graceful(1000).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_graceful {
+    extern crate tokio_dep as tokio;
+
+    use readwrite::duplex_tokio;
+    use std::io::ErrorKind;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn close_graceful_drains_queued_data() {
+        let (a, mut b) = duplex_tokio(1024);
+        b.write_all(&[7; 100]).await.unwrap();
+        let peer = tokio::spawn(async move {
+            let mut received = Vec::new();
+            b.read_to_end(&mut received).await.unwrap();
+            b.shutdown().await.unwrap();
+            received
+        });
+        assert_eq!(a.close_graceful(1000).await.unwrap(), 100);
+        assert!(peer.await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn close_graceful_drain_limit() {
+        let (a, mut b) = duplex_tokio(8192);
+        b.write_all(&[7; 5000]).await.unwrap();
+        let e = a.close_graceful(1000).await.unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_graceful {
+    use futures::io::Cursor;
+    use readwrite::ReadWriteAsyncstd;
+
+    #[test]
+    fn close_graceful_counts_and_limits() {
+        futures::executor::block_on(async {
+            let rw = ReadWriteAsyncstd::new(Cursor::new(vec![0; 10]), Cursor::new(Vec::new()));
+            assert_eq!(rw.close_graceful(100).await.unwrap(), 10);
+            let rw = ReadWriteAsyncstd::new(Cursor::new(vec![0; 10]), Cursor::new(Vec::new()));
+            assert!(rw.close_graceful(5).await.is_err());
+        });
+    }
+}
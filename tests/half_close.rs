@@ -0,0 +1,146 @@
+mod common;
+
+use std::io::{Read, Write};
+
+use readwrite::{ReadWrite, ReadWriteHalfClosable};
+
+/// Peer reading the whole request until EOF, then answering and closing
+fn answer_after_eof(mut peer: impl Read + Write) {
+    let mut request = Vec::new();
+    peer.read_to_end(&mut request).unwrap();
+    peer.write_all(b"response to ").unwrap();
+    peer.write_all(&request).unwrap();
+}
+
+#[test]
+fn close_write_peer_sees_eof() {
+    // Reader and writer are clones of one connection, so dropping the writer alone would not send EOF
+    let (client, server) = common::tcp_pair();
+    let mut rw = ReadWrite::from_stream(client).unwrap().half_closable();
+    let peer = std::thread::spawn(move || answer_after_eof(server));
+
+    rw.write_all(b"request").unwrap();
+    rw.close_write().unwrap();
+    assert!(rw.is_write_closed());
+    assert_eq!(
+        rw.write(b"more").unwrap_err().kind(),
+        std::io::ErrorKind::BrokenPipe
+    );
+
+    let mut response = String::new();
+    rw.read_to_string(&mut response).unwrap();
+    assert_eq!(response, "response to request");
+    peer.join().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn close_write_unix_clones() {
+    use std::os::unix::net::UnixStream;
+
+    let (ours, theirs) = UnixStream::pair().unwrap();
+    let mut rw = ReadWrite::new(ours.try_clone().unwrap(), ours).half_closable();
+    let peer = std::thread::spawn(move || answer_after_eof(theirs));
+
+    rw.write_all(b"ping").unwrap();
+    rw.close_write().unwrap();
+    let mut response = String::new();
+    rw.read_to_string(&mut response).unwrap();
+    assert_eq!(response, "response to ping");
+    peer.join().unwrap();
+}
+
+#[test]
+fn drop_write_flushes_and_drops() {
+    let mut sent = Vec::new();
+    let mut rw = ReadWriteHalfClosable::new(std::io::empty(), std::io::BufWriter::new(&mut sent));
+    rw.write_all(b"buffered").unwrap();
+    rw.drop_write().unwrap();
+    assert!(rw.is_write_closed());
+    assert!(rw.into_inner().1.is_none());
+    assert_eq!(sent, b"buffered");
+}
+
+#[test]
+fn close_read_keeps_writer() {
+    let mut rw = ReadWriteHalfClosable::new(&b"ignored"[..], Vec::new());
+    rw.close_read();
+    assert!(rw.is_read_closed());
+    let mut buf = [0; 4];
+    assert_eq!(rw.read(&mut buf).unwrap(), 0);
+    rw.write_all(b"still writable").unwrap();
+    let (r, w) = rw.into_inner();
+    assert!(r.is_none());
+    assert_eq!(w.unwrap(), b"still writable");
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_half_close {
+    extern crate tokio_dep as tokio;
+
+    use readwrite::ReadWriteTokioHalfClosable;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn close_write_peer_sees_eof() {
+        let (w, mut peer_r) = tokio::io::duplex(64);
+        let (mut peer_w, r) = tokio::io::duplex(64);
+        let mut rw = ReadWriteTokioHalfClosable::new(r, w);
+
+        let peer = tokio::spawn(async move {
+            let mut request = Vec::new();
+            peer_r.read_to_end(&mut request).await.unwrap();
+            peer_w.write_all(b"response to ").await.unwrap();
+            peer_w.write_all(&request).await.unwrap();
+        });
+
+        rw.write_all(b"request").await.unwrap();
+        rw.close_write().await.unwrap();
+        assert!(rw.is_write_closed());
+        assert_eq!(
+            rw.write(b"more").await.unwrap_err().kind(),
+            std::io::ErrorKind::BrokenPipe
+        );
+
+        let mut response = String::new();
+        rw.read_to_string(&mut response).await.unwrap();
+        assert_eq!(response, "response to request");
+        peer.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn close_read_pinned() {
+        let (r, _peer) = tokio::io::duplex(64);
+        let mut rw = Box::pin(ReadWriteTokioHalfClosable::new(r, Vec::new()));
+        rw.as_mut().close_read_pinned();
+        assert!(rw.is_read_closed());
+        let mut buf = [0; 4];
+        assert_eq!(rw.read(&mut buf).await.unwrap(), 0);
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_half_close {
+    use futures::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+    use readwrite::ReadWriteAsyncstd;
+
+    #[test]
+    fn close_halves() {
+        futures::executor::block_on(async {
+            let mut rw =
+                ReadWriteAsyncstd::new(Cursor::new(b"data".to_vec()), Cursor::new(Vec::new()))
+                    .half_closable();
+            rw.write_all(b"out").await.unwrap();
+            rw.close_write().await.unwrap();
+            assert_eq!(
+                rw.write(b"more").await.unwrap_err().kind(),
+                std::io::ErrorKind::BrokenPipe
+            );
+            let mut buf = [0; 2];
+            rw.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"da");
+            rw.close_read();
+            assert_eq!(rw.read(&mut buf).await.unwrap(), 0);
+        });
+    }
+}
@@ -0,0 +1,112 @@
+use std::io::{ErrorKind, IoSlice, Read, Write};
+
+use readwrite::ReadWrite;
+
+#[test]
+fn exact_boundary_write() {
+    let mut rw = ReadWrite::new(std::io::empty(), Vec::new()).limited(None, Some(8));
+    rw.write_all(b"12345678").unwrap();
+    assert_eq!(rw.quota().remaining_write(), Some(0));
+    assert_eq!(rw.write(b"9").unwrap_err().kind(), ErrorKind::Other);
+    // Empty writes still succeed
+    assert_eq!(rw.write(b"").unwrap(), 0);
+    assert_eq!(rw.into_inner().into_writer(), b"12345678");
+}
+
+#[test]
+fn write_crossing_boundary_is_truncated() {
+    let mut rw = ReadWrite::new(std::io::empty(), Vec::new()).limited(None, Some(5));
+    assert_eq!(rw.write(b"1234567").unwrap(), 5);
+    assert!(rw.write(b"67").is_err());
+    assert_eq!(rw.into_inner().into_writer(), b"12345");
+}
+
+#[test]
+fn vectored_write_crossing_boundary() {
+    let mut rw = ReadWrite::new(std::io::empty(), Vec::new()).limited(None, Some(5));
+    let bufs = [
+        IoSlice::new(b"abc"),
+        IoSlice::new(b"def"),
+        IoSlice::new(b"g"),
+    ];
+    assert_eq!(rw.write_vectored(&bufs).unwrap(), 5);
+    assert_eq!(rw.quota().remaining_write(), Some(0));
+    assert!(rw.write_vectored(&bufs[2..]).is_err());
+    assert_eq!(rw.into_inner().into_writer(), b"abcde");
+}
+
+#[test]
+fn read_quota_signals_eof_or_error() {
+    let mut rw = ReadWrite::new(&b"abcdef"[..], std::io::sink()).limited(Some(4), None);
+    let mut got = Vec::new();
+    rw.read_to_end(&mut got).unwrap();
+    assert_eq!(got, b"abcd");
+
+    rw.set_error_on_read_exhausted(true);
+    assert_eq!(rw.read(&mut [0; 2]).unwrap_err().kind(), ErrorKind::Other);
+}
+
+#[test]
+fn refill_mid_stream() {
+    let mut rw = ReadWrite::new(&b"abcdef"[..], Vec::new()).limited(Some(3), Some(2));
+    let quota = rw.quota();
+
+    let mut got = Vec::new();
+    rw.read_to_end(&mut got).unwrap();
+    assert_eq!(got, b"abc");
+    rw.write_all(b"xy").unwrap();
+    assert!(rw.write(b"z").is_err());
+
+    quota.add_read(10);
+    quota.set_write(1);
+    rw.read_to_end(&mut got).unwrap();
+    assert_eq!(got, b"abcdef");
+    assert_eq!(quota.remaining_read(), Some(7));
+    rw.write_all(b"z").unwrap();
+    assert_eq!(rw.into_inner().into_writer(), b"xyz");
+}
+
+#[test]
+fn unlimited_direction_is_untouched() {
+    let rw = ReadWrite::new(&b"data"[..], Vec::new()).limited(None, Some(0));
+    let quota = rw.quota();
+    quota.add_read(5);
+    assert_eq!(quota.remaining_read(), None);
+    let mut rw = rw;
+    let mut got = String::new();
+    rw.read_to_string(&mut got).unwrap();
+    assert_eq!(got, "data");
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_limit {
+    extern crate tokio_dep as tokio;
+
+    use std::io::IoSlice;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn quotas_and_refill() {
+        let (a, mut b) = readwrite::duplex_tokio(64);
+        let mut a = a.limited(Some(3), Some(4));
+        let quota = a.quota();
+
+        let bufs = [IoSlice::new(b"ab"), IoSlice::new(b"cdef")];
+        let n = a.write_vectored(&bufs).await.unwrap();
+        assert!(n <= 4);
+        a.write_all(&b"abcd"[n..]).await.unwrap();
+        assert!(a.write(b"e").await.is_err());
+        let mut buf = [0; 4];
+        b.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"abcd");
+
+        b.write_all(b"123456").await.unwrap();
+        let mut buf = [0; 6];
+        assert_eq!(a.read(&mut buf).await.unwrap(), 3);
+        assert_eq!(a.read(&mut buf[3..]).await.unwrap(), 0);
+        quota.add_read(3);
+        a.read_exact(&mut buf[3..]).await.unwrap();
+        assert_eq!(&buf, b"123456");
+    }
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "test-util")]
+
+use std::io::{Read, Write};
+
+use readwrite::MockReadWrite;
+
+#[test]
+fn scripted_conversation() {
+    let mut mock = MockReadWrite::new()
+        .expect_write("HELO example.org\r\n")
+        .with_read("250 hello\r\n")
+        .expect_write("QUIT\r\n")
+        .with_read("221 bye\r\n");
+
+    // Writes may be split arbitrarily
+    mock.write_all(b"HELO ").unwrap();
+    mock.write_all(b"example.org\r\nQUIT\r\n").unwrap();
+    let mut replies = String::new();
+    mock.read_to_string(&mut replies).unwrap();
+    assert_eq!(replies, "250 hello\r\n221 bye\r\n");
+    assert!(mock.is_done());
+}
+
+#[test]
+fn reads_return_responses_in_order() {
+    let mut mock = MockReadWrite::new().with_read("first").with_read("second");
+    let mut buf = [0u8; 16];
+    assert_eq!(mock.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"first");
+    assert_eq!(mock.read(&mut buf[..3]).unwrap(), 3);
+    assert_eq!(&buf[..3], b"sec");
+    assert_eq!(mock.read(&mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"ond");
+    assert_eq!(mock.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn empty_entries_are_skipped() {
+    let mut mock = MockReadWrite::new()
+        .with_read("a")
+        .with_read("")
+        .with_read("b")
+        .expect_write("");
+    let mut data = Vec::new();
+    mock.read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"ab");
+    // Dropping must not complain about the empty expected write
+    assert!(mock.is_done());
+}
+
+#[test]
+#[should_panic(
+    expected = "unexpected write payload at offset 4: expected b\"PING\\n\", got b\"PONG\\n\""
+)]
+fn unexpected_write_payload_panics() {
+    let mut mock = MockReadWrite::new()
+        .expect_write("AUTH")
+        .expect_write("PING\n");
+    mock.write_all(b"AUTH").unwrap();
+    let _ = mock.write(b"PONG\n");
+}
+
+#[test]
+#[should_panic(expected = "no more writes were scripted")]
+fn write_past_the_script_panics() {
+    let mut mock = MockReadWrite::new().expect_write("one");
+    mock.write_all(b"one").unwrap();
+    let _ = mock.write(b"two");
+}
+
+#[test]
+#[should_panic(expected = "dropped with 1 scripted writes not performed: b\"BYE\"")]
+fn pending_writes_panic_on_drop() {
+    let mut mock = MockReadWrite::new().expect_write("HI").expect_write("BYE");
+    mock.write_all(b"HI").unwrap();
+}
@@ -0,0 +1,30 @@
+#![cfg(all(feature = "monoio", unix))]
+
+extern crate monoio_dep as monoio;
+
+use monoio::io::{AsyncReadRentExt, AsyncWriteRent, AsyncWriteRentExt};
+use monoio::net::UnixStream;
+use readwrite::ReadWriteMonoio;
+
+#[test]
+fn current_thread_round_trip() {
+    monoio::start::<monoio::LegacyDriver, _>(async {
+        // Each direction uses its own socket, so the halves are truly independent
+        let (ours_in, mut theirs_out) = UnixStream::pair().unwrap();
+        let (ours_out, mut theirs_in) = UnixStream::pair().unwrap();
+        let mut rw = ReadWriteMonoio::new(ours_in, ours_out);
+
+        let (res, _) = theirs_out.write_all(b"ping".to_vec()).await;
+        res.unwrap();
+        let (res, buf) = rw.read_exact(vec![0; 4]).await;
+        assert_eq!(res.unwrap(), 4);
+        assert_eq!(buf, b"ping");
+
+        let (res, _) = rw.write_all(b"pong".to_vec()).await;
+        res.unwrap();
+        rw.flush().await.unwrap();
+        let (res, buf) = theirs_in.read_exact(vec![0; 4]).await;
+        assert_eq!(res.unwrap(), 4);
+        assert_eq!(buf, b"pong");
+    });
+}
@@ -0,0 +1,176 @@
+use std::io::{ErrorKind, Read, Write};
+use std::time::Duration;
+
+use readwrite::{OnMissingRead, OnMissingWrite, ReadWrite};
+
+mod common;
+
+#[test]
+fn read_only_discards_writes() {
+    let mut rw = ReadWrite::read_only(&b"log line\n"[..], OnMissingWrite::Discard);
+    rw.write_all(b"ignored").unwrap();
+    rw.flush().unwrap();
+    let mut data = String::new();
+    rw.read_to_string(&mut data).unwrap();
+    assert_eq!(data, "log line\n");
+}
+
+#[test]
+fn read_only_broken_pipe() {
+    let mut rw = ReadWrite::read_only(std::io::empty(), OnMissingWrite::BrokenPipe);
+    assert_eq!(rw.write(b"x").unwrap_err().kind(), ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn write_only_eof() {
+    let mut rw = ReadWrite::write_only(Vec::new(), OnMissingRead::Eof);
+    assert_eq!(rw.read(&mut [0; 4]).unwrap(), 0);
+    rw.write_all(b"sink").unwrap();
+    assert_eq!(rw.into_inner().1, b"sink");
+}
+
+#[test]
+fn write_only_unsupported() {
+    let mut rw = ReadWrite::write_only(Vec::new(), OnMissingRead::Unsupported);
+    assert_eq!(
+        rw.read(&mut [0; 4]).unwrap_err().kind(),
+        ErrorKind::Unsupported
+    );
+}
+
+#[test]
+fn write_only_never_blocks_the_thread() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    // The thread stays parked forever and is left behind when the test ends
+    std::thread::spawn(move || {
+        let mut rw = ReadWrite::write_only(std::io::sink(), OnMissingRead::Never);
+        let result = rw.read(&mut [0; 4]);
+        let _ = tx.send(result.is_ok());
+    });
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(200)),
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+    );
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_one_sided {
+    extern crate tokio_dep as tokio;
+
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
+    use readwrite::{OnMissingRead, OnMissingWrite, ReadWriteTokio};
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, ReadBuf};
+
+    use super::common::CountingWaker;
+
+    #[tokio::test]
+    async fn write_policies() {
+        let mut rw = ReadWriteTokio::read_only(&b"abc"[..], OnMissingWrite::Discard);
+        rw.write_all(b"ignored").await.unwrap();
+        let mut data = Vec::new();
+        rw.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data, b"abc");
+
+        let mut rw = ReadWriteTokio::read_only(tokio::io::empty(), OnMissingWrite::BrokenPipe);
+        let err = rw.write(b"x").await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+    }
+
+    #[tokio::test]
+    async fn read_policies() {
+        let mut rw = ReadWriteTokio::write_only(Vec::new(), OnMissingRead::Eof);
+        assert_eq!(rw.read(&mut [0; 4]).await.unwrap(), 0);
+
+        let mut rw = ReadWriteTokio::write_only(Vec::new(), OnMissingRead::Unsupported);
+        let err = rw.read(&mut [0; 4]).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn never_stays_pending_without_wakeups() {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = counter.clone().into();
+        let mut cx = Context::from_waker(&waker);
+        let mut rw = ReadWriteTokio::write_only(tokio::io::sink(), OnMissingRead::Never);
+        let mut storage = [0u8; 4];
+        for _ in 0..3 {
+            let mut buf = ReadBuf::new(&mut storage);
+            assert!(matches!(
+                Pin::new(&mut rw).poll_read(&mut cx, &mut buf),
+                Poll::Pending
+            ));
+        }
+        // Nothing asks for the task to be polled again, so an executor would not spin on it
+        assert_eq!(counter.count(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn never_times_out() {
+        let mut rw = ReadWriteTokio::write_only(tokio::io::sink(), OnMissingRead::Never);
+        let mut buf = [0u8; 4];
+        let read = tokio::time::timeout(std::time::Duration::from_secs(3600), rw.read(&mut buf));
+        assert!(read.await.is_err());
+        // The writer still works
+        rw.write_all(b"still writable").await.unwrap();
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_one_sided {
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
+    use futures::executor::block_on;
+    use futures::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+    use readwrite::{OnMissingRead, OnMissingWrite, ReadWriteAsyncstd};
+
+    use super::common::CountingWaker;
+
+    #[test]
+    fn write_policies() {
+        block_on(async {
+            let mut rw = ReadWriteAsyncstd::read_only(&b"abc"[..], OnMissingWrite::Discard);
+            rw.write_all(b"ignored").await.unwrap();
+            let mut data = Vec::new();
+            rw.read_to_end(&mut data).await.unwrap();
+            assert_eq!(data, b"abc");
+
+            let mut rw =
+                ReadWriteAsyncstd::read_only(futures::io::empty(), OnMissingWrite::BrokenPipe);
+            let err = rw.write(b"x").await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+        });
+    }
+
+    #[test]
+    fn read_policies() {
+        block_on(async {
+            let mut rw = ReadWriteAsyncstd::write_only(futures::io::sink(), OnMissingRead::Eof);
+            assert_eq!(rw.read(&mut [0; 4]).await.unwrap(), 0);
+
+            let mut rw =
+                ReadWriteAsyncstd::write_only(futures::io::sink(), OnMissingRead::Unsupported);
+            let err = rw.read(&mut [0; 4]).await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+        });
+    }
+
+    #[test]
+    fn never_stays_pending_without_wakeups() {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = counter.clone().into();
+        let mut cx = Context::from_waker(&waker);
+        let mut rw = ReadWriteAsyncstd::write_only(futures::io::sink(), OnMissingRead::Never);
+        for _ in 0..3 {
+            assert!(matches!(
+                Pin::new(&mut rw).poll_read(&mut cx, &mut [0; 4]),
+                Poll::Pending
+            ));
+        }
+        assert_eq!(counter.count(), 0);
+    }
+}
@@ -0,0 +1,49 @@
+use readwrite::Pair;
+
+/// Generic helper written once for every combined struct
+fn rebuild<P: Pair>(p: P) -> P {
+    let (r, w) = p.into_inner();
+    P::new(r, w)
+}
+
+fn halves<P: Pair>(mut p: P) -> (P::Reader, P::Writer) {
+    let _: (&mut P::Reader, &mut P::Writer) = p.borrow_mut();
+    let _: (&P::Reader, &P::Writer) = p.borrow();
+    p.into_inner()
+}
+
+#[test]
+fn sync_pair() {
+    let rw = rebuild(readwrite::ReadWrite::new(&b"in"[..], vec![1]));
+    let (r, w) = halves(rw);
+    assert_eq!((r, w), (&b"in"[..], vec![1]));
+    assert_eq!(
+        Pair::into_writer(readwrite::ReadWrite::new(&b""[..], vec![2])),
+        [2]
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_pair() {
+    extern crate tokio_dep as tokio;
+
+    let rw = rebuild(readwrite::ReadWriteTokio::new(
+        &b"in"[..],
+        tokio::io::sink(),
+    ));
+    let (r, _w) = halves(rw);
+    assert_eq!(r, b"in");
+    let rw = readwrite::ReadWriteTokio::new(&b"in"[..], tokio::io::sink());
+    assert_eq!(Pair::into_reader(rw), b"in");
+}
+
+#[cfg(feature = "asyncstd")]
+#[test]
+fn asyncstd_pair() {
+    let rw = rebuild(readwrite::ReadWriteAsyncstd::new(&b"in"[..], Vec::new()));
+    let (r, w) = halves(rw);
+    assert_eq!((r, w), (&b"in"[..], Vec::new()));
+    let rw = readwrite::ReadWriteAsyncstd::new(&b"in"[..], vec![3]);
+    assert_eq!(Pair::into_writer(rw), [3]);
+}
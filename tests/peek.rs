@@ -0,0 +1,113 @@
+mod common;
+
+use std::io::{Read, Write};
+
+use readwrite::{Peek, ReadWrite};
+
+#[test]
+fn peek_then_read_tcp() {
+    let (client, mut server) = common::tcp_pair();
+    let mut rw = ReadWrite::from_stream(client).unwrap();
+
+    server.write_all(b"GET /").unwrap();
+    let mut peeked = [0; 5];
+    let mut n = 0;
+    while n < peeked.len() {
+        n = rw.peek(&mut peeked).unwrap();
+    }
+    assert_eq!(&peeked, b"GET /");
+
+    let mut read = [0; 5];
+    rw.read_exact(&mut read).unwrap();
+    assert_eq!(read, peeked);
+}
+
+#[test]
+fn peek_through_shared_stream() {
+    let (client, mut server) = common::tcp_pair();
+    server.write_all(b"TLS?").unwrap();
+
+    // Only a shared reference to the stream is needed
+    let stream = &client;
+    let mut rw = ReadWrite::duplex_single(stream);
+    let mut peeked = [0; 4];
+    let mut n = 0;
+    while n < peeked.len() {
+        n = rw.peek(&mut peeked).unwrap();
+    }
+    let mut read = [0; 4];
+    (&client).read_exact(&mut read).unwrap();
+    assert_eq!(read, peeked);
+    assert_eq!(&read, b"TLS?");
+}
+
+#[test]
+fn peek_udp() {
+    let a = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut b = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    a.send_to(b"datagram", b.local_addr().unwrap()).unwrap();
+    let mut peeked = [0; 8];
+    assert_eq!(Peek::peek(&mut b, &mut peeked).unwrap(), 8);
+    let mut received = [0; 8];
+    assert_eq!(b.recv(&mut received).unwrap(), 8);
+    assert_eq!(received, peeked);
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_peek {
+    extern crate tokio_dep as tokio;
+
+    use readwrite::ReadWriteTokio;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn peek_buffer_redelivers_in_order() {
+        let (ours, mut theirs) = tokio::io::duplex(64);
+        let (r, w) = tokio::io::split(ours);
+        let mut rw = ReadWriteTokio::new(r, w).with_peek_buffer(4);
+
+        theirs.write_all(b"\x16\x03\x01rest").await.unwrap();
+        let mut sniff = [0; 2];
+        assert_eq!(rw.peek(&mut sniff).await.unwrap(), 2);
+        assert_eq!(&sniff, b"\x16\x03");
+
+        // A longer peek is limited by the buffer capacity and starts at the same place
+        let mut longer = [0; 8];
+        let n = rw.peek(&mut longer).await.unwrap();
+        assert!((2..=4).contains(&n));
+        assert_eq!(&longer[..2], b"\x16\x03");
+
+        drop(theirs);
+        let mut all = Vec::new();
+        rw.read_to_end(&mut all).await.unwrap();
+        assert_eq!(all, b"\x16\x03\x01rest");
+    }
+
+    #[tokio::test]
+    async fn peek_buffer_at_eof() {
+        let mut rw = ReadWriteTokio::new(&b""[..], tokio::io::sink()).with_peek_buffer(4);
+        assert_eq!(rw.peek(&mut [0; 4]).await.unwrap(), 0);
+    }
+
+    #[cfg(feature = "net")]
+    #[tokio::test]
+    async fn peek_tcp_then_read() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = tokio::net::TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        let mut rw = ReadWriteTokio::new(server, tokio::io::sink());
+
+        client.write_all(b"hello").await.unwrap();
+        let mut peeked = [0; 5];
+        let mut n = 0;
+        while n < peeked.len() {
+            n = rw.peek(&mut peeked).await.unwrap();
+        }
+        assert_eq!(&peeked, b"hello");
+        let mut read = [0; 5];
+        rw.read_exact(&mut read).await.unwrap();
+        assert_eq!(read, peeked);
+    }
+}
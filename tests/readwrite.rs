@@ -0,0 +1,379 @@
+mod common;
+
+use std::io::{Cursor, Read, Write};
+
+use readwrite::{BoxedReadWrite, ReadWrite};
+
+#[test]
+fn boxed_cursor_vec() {
+    struct Holder {
+        rw: BoxedReadWrite<'static>,
+    }
+
+    let rw = ReadWrite::new(Cursor::new(b"hello".to_vec()), Vec::new());
+    let mut holder = Holder { rw: rw.boxed() };
+
+    let mut buf = String::new();
+    holder.rw.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "hello");
+    holder.rw.write_all(b"world").unwrap();
+    holder.rw.flush().unwrap();
+}
+
+#[test]
+fn boxed_local_non_send() {
+    let shared = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    struct RcWriter(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+    impl Write for RcWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut rw = ReadWrite::new(&b"abc"[..], RcWriter(shared.clone())).boxed_local();
+    let mut buf = [0; 3];
+    rw.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"abc");
+    rw.write_all(b"xyz").unwrap();
+    assert_eq!(*shared.borrow(), b"xyz");
+}
+
+#[test]
+fn from_stream_tcp() {
+    let (client, mut server) = common::tcp_pair();
+    let mut rw = ReadWrite::from_stream(client).unwrap();
+
+    rw.write_all(b"request").unwrap();
+    let mut buf = [0; 7];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"request");
+
+    server.write_all(b"response").unwrap();
+    let mut buf = [0; 8];
+    rw.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"response");
+
+    // Both halves refer to the same connection
+    let (r, w) = rw.borrow();
+    assert_eq!(r.local_addr().unwrap(), w.local_addr().unwrap());
+    assert_eq!(r.peer_addr().unwrap(), server.local_addr().unwrap());
+}
+
+#[test]
+fn try_new_propagates_error() {
+    let r: std::io::Result<&[u8]> = Ok(b"data");
+    let w: std::io::Result<Vec<u8>> = Err(std::io::Error::new(
+        std::io::ErrorKind::PermissionDenied,
+        "no writer",
+    ));
+    let e = ReadWrite::try_new(r, w).err().unwrap();
+    assert_eq!(e.kind(), std::io::ErrorKind::PermissionDenied);
+    assert_eq!(e.to_string(), "no writer");
+
+    let rw = ReadWrite::try_new::<()>(Ok(&b"data"[..]), Ok(Vec::new())).unwrap();
+    assert_eq!(rw.into_reader(), b"data");
+}
+
+#[test]
+fn write_vectored_reaches_writer() {
+    #[derive(Default)]
+    struct VectoredRecorder {
+        calls: Vec<&'static str>,
+    }
+    impl Write for VectoredRecorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.calls.push("write");
+            Ok(buf.len())
+        }
+        fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+            self.calls.push("write_vectored");
+            Ok(bufs.iter().map(|b| b.len()).sum())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut rw = ReadWrite::new(std::io::empty(), VectoredRecorder::default());
+    let bufs = [std::io::IoSlice::new(b"ab"), std::io::IoSlice::new(b"cd")];
+    assert_eq!(rw.write_vectored(&bufs).unwrap(), 4);
+    assert_eq!(rw.borrow_write().calls, ["write_vectored"]);
+}
+
+/// Writer counting `write` calls of the inner writer
+#[derive(Debug)]
+struct CountingWriter<W> {
+    inner: W,
+    writes: usize,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes += 1;
+        self.inner.write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[test]
+fn map_combinators_layer_buffers() {
+    use std::io::{BufRead, BufReader, BufWriter};
+
+    let rw = ReadWrite::new(Cursor::new(b"line one\nline two\n".to_vec()), Vec::new());
+    let mut rw = rw.map_read(BufReader::new).map_write(|w| CountingWriter {
+        inner: w,
+        writes: 0,
+    });
+    let mut line = String::new();
+    rw.read_line(&mut line).unwrap();
+    assert_eq!(line, "line one\n");
+
+    let mut rw = rw.map_write(BufWriter::new);
+    for _ in 0..10 {
+        rw.write_all(b"x").unwrap();
+    }
+    rw.flush().unwrap();
+    let counting = rw.into_writer().into_inner().unwrap();
+    assert_eq!(counting.writes, 1);
+    assert_eq!(counting.inner, b"xxxxxxxxxx");
+
+    let rw = ReadWrite::new(&b"ab"[..], Vec::new()).map_both(
+        |r| r.chain(&b"cd"[..]),
+        |w| CountingWriter {
+            inner: w,
+            writes: 0,
+        },
+    );
+    let (mut r, _) = rw.into_inner();
+    let mut all = String::new();
+    r.read_to_string(&mut all).unwrap();
+    assert_eq!(all, "abcd");
+}
+
+#[test]
+fn boxed_from_different_transports() {
+    fn exchange(rw: &mut BoxedReadWrite<'_>) -> String {
+        rw.write_all(b"ping").unwrap();
+        let mut buf = [0; 4];
+        rw.read_exact(&mut buf).unwrap();
+        String::from_utf8(buf.to_vec()).unwrap()
+    }
+
+    let mut in_memory = ReadWrite::new(Cursor::new(b"mem!".to_vec()), Vec::new()).boxed();
+    assert_eq!(exchange(&mut in_memory), "mem!");
+
+    let (client, mut server) = common::tcp_pair();
+    let peer = std::thread::spawn(move || {
+        let mut buf = [0; 4];
+        server.read_exact(&mut buf).unwrap();
+        server.write_all(b"tcp!").unwrap();
+    });
+    let mut tcp = ReadWrite::from_stream(client).unwrap().boxed();
+    assert_eq!(exchange(&mut tcp), "tcp!");
+    peer.join().unwrap();
+}
+
+#[test]
+fn try_clone_files_write_from_both() {
+    use std::fs::OpenOptions;
+
+    let path = std::env::temp_dir().join(format!("readwrite-try-clone-{}", std::process::id()));
+    let w = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(&path)
+        .unwrap();
+    let r = std::fs::File::open(&path).unwrap();
+    let mut rw = ReadWrite::new(r, w);
+    let mut clone = rw.try_clone().unwrap();
+
+    // Clones share the file offsets, so writes and reads continue where the other left off
+    rw.write_all(b"original,").unwrap();
+    clone.write_all(b"clone").unwrap();
+    let mut buf = [0; 9];
+    rw.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"original,");
+    let mut rest = String::new();
+    clone.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "clone");
+
+    drop((rw, clone));
+    assert_eq!(std::fs::read(&path).unwrap(), b"original,clone");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn chain_read_header_then_source() {
+    let header = Cursor::new(b"HDR".to_vec());
+    let mut rw = ReadWrite::new(header, Vec::new()).chain_read(&b"12345"[..]);
+    let mut data = Vec::new();
+    let mut buf = [0u8; 2];
+    loop {
+        match rw.read(&mut buf).unwrap() {
+            0 => break,
+            n => data.extend_from_slice(&buf[..n]),
+        }
+    }
+    assert_eq!(data, b"HDR12345");
+    assert_eq!(rw.read(&mut buf).unwrap(), 0);
+    // The writer is untouched
+    rw.write_all(b"reply").unwrap();
+    assert_eq!(rw.into_inner().1, b"reply");
+}
+
+/// Reader returning at most 3 bytes per call and `Interrupted` on every other call
+struct Trickle<'a> {
+    data: &'a [u8],
+    interrupt: bool,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if !self.interrupt {
+            return Err(std::io::ErrorKind::Interrupted.into());
+        }
+        let n = buf.len().min(3).min(self.data.len());
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn read_exact_vectored_fills_all_buffers() {
+    use std::io::IoSliceMut;
+
+    let trickle = Trickle {
+        data: b"abcdefghijklmnoXYZ",
+        interrupt: false,
+    };
+    let mut rw = ReadWrite::new(trickle, std::io::sink());
+    let (mut a, mut b, mut c) = ([0u8; 4], [0u8; 5], [0u8; 6]);
+    let mut bufs = [
+        IoSliceMut::new(&mut a),
+        IoSliceMut::new(&mut b),
+        IoSliceMut::new(&mut c),
+    ];
+    rw.read_exact_vectored(&mut bufs).unwrap();
+    assert_eq!(&a, b"abcd");
+    assert_eq!(&b, b"efghi");
+    assert_eq!(&c, b"jklmno");
+
+    // The rest of the data is left in the reader
+    let mut rest = Vec::new();
+    rw.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"XYZ");
+}
+
+#[test]
+fn read_exact_vectored_eof() {
+    use std::io::IoSliceMut;
+
+    let mut rw = ReadWrite::new(&b"short"[..], std::io::sink());
+    let (mut a, mut b) = ([0u8; 4], [0u8; 4]);
+    let mut bufs = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)];
+    let err = rw.read_exact_vectored(&mut bufs).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+fn send_socket(data: &'static [u8]) -> impl Read + Write + Send {
+    ReadWrite::new_send(data, Vec::new())
+}
+
+#[test]
+fn new_send_crosses_threads() {
+    let mut rw = send_socket(b"from another thread");
+    let data = std::thread::spawn(move || {
+        let mut data = String::new();
+        rw.read_to_string(&mut data).unwrap();
+        data
+    })
+    .join()
+    .unwrap();
+    assert_eq!(data, "from another thread");
+}
+
+#[test]
+fn duplex_single_over_tcp() {
+    let (client, mut server) = common::tcp_pair();
+    let mut rw = ReadWrite::duplex_single(&client);
+    rw.write_all(b"request").unwrap();
+    rw.flush().unwrap();
+
+    let mut buf = [0u8; 7];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"request");
+    server.write_all(b"reply").unwrap();
+
+    let mut reply = [0u8; 5];
+    rw.read_exact(&mut reply).unwrap();
+    assert_eq!(&reply, b"reply");
+
+    // Dropping the reader half leaves the shared connection open
+    let mut w = rw.into_writer();
+    w.write_all(b"more").unwrap();
+    let mut more = [0u8; 4];
+    server.read_exact(&mut more).unwrap();
+    assert_eq!(&more, b"more");
+}
+
+#[test]
+fn duplex_single_echo() {
+    let mut rw = ReadWrite::duplex_single(readwrite::echo());
+    rw.write_all(b"loop").unwrap();
+    let mut buf = [0u8; 4];
+    rw.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"loop");
+}
+
+/// Plugin-style API typed on trait objects
+fn plugin(input: &mut dyn Read, output: &mut dyn Write) -> std::io::Result<usize> {
+    let mut data = Vec::new();
+    input.read_to_end(&mut data)?;
+    data.reverse();
+    output.write_all(&data)?;
+    Ok(data.len())
+}
+
+#[test]
+fn as_dyn_mut_passes_trait_objects() {
+    let mut rw = ReadWrite::new(&b"abc"[..], Vec::new());
+    let (r, w) = rw.as_dyn_mut();
+    assert_eq!(plugin(r, w).unwrap(), 3);
+    // Still usable afterwards, nothing was consumed
+    rw.write_all(b"!").unwrap();
+    assert_eq!(rw.into_writer(), b"cba!");
+}
+
+#[test]
+fn drain_reader_then_reply() {
+    let (client, mut server) = common::tcp_pair();
+    server.write_all(b"the whole request").unwrap();
+    server.shutdown(std::net::Shutdown::Write).unwrap();
+
+    let mut rw = ReadWrite::from_stream(client).unwrap();
+    assert_eq!(rw.drain_reader().unwrap(), b"the whole request");
+    assert!(rw.drain_reader().unwrap().is_empty());
+
+    rw.write_all(b"reply").unwrap();
+    drop(rw);
+    let mut reply = Vec::new();
+    server.read_to_end(&mut reply).unwrap();
+    assert_eq!(reply, b"reply");
+}
+
+#[test]
+fn drain_reader_leaves_writer_untouched() {
+    let mut rw = ReadWrite::new(&b"rest"[..], b"kept".to_vec());
+    assert_eq!(rw.drain_reader().unwrap(), b"rest");
+    assert_eq!(rw.borrow_write(), b"kept");
+    rw.write_all(b", more").unwrap();
+    assert_eq!(rw.into_writer(), b"kept, more");
+}
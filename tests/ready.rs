@@ -0,0 +1,71 @@
+#![cfg(feature = "net")]
+
+extern crate tokio_dep as tokio;
+
+use std::task::Poll;
+use std::time::Duration;
+
+use readwrite::ReadWriteTokio;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+async fn tcp_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (server, _) = listener.accept().await.unwrap();
+    (client, server)
+}
+
+/// Poll once, without waiting
+async fn poll_once<T>(mut f: impl FnMut(&mut std::task::Context<'_>) -> Poll<T>) -> Poll<T> {
+    std::future::poll_fn(|cx| Poll::Ready(f(cx))).await
+}
+
+#[tokio::test]
+async fn read_readiness_without_reading() {
+    let (inbound, mut inbound_peer) = tcp_pair().await;
+    let (outbound, _outbound_peer) = tcp_pair().await;
+    let mut rw = ReadWriteTokio::new(inbound, outbound);
+
+    // Nothing was sent yet, while the other direction can be written right away
+    assert!(poll_once(|cx| rw.poll_read_ready(cx)).await.is_pending());
+    assert!(matches!(
+        poll_once(|cx| rw.poll_write_ready(cx)).await,
+        Poll::Ready(Ok(()))
+    ));
+
+    inbound_peer.write_all(b"data").await.unwrap();
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        std::future::poll_fn(|cx| rw.poll_read_ready(cx)),
+    )
+    .await
+    .expect("data did not make the reader ready")
+    .unwrap();
+
+    // Probing did not consume anything
+    let mut buf = [0u8; 4];
+    rw.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"data");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_halves() {
+    let (inbound, mut inbound_peer) = tokio::net::UnixStream::pair().unwrap();
+    let (outbound, mut outbound_peer) = tokio::net::UnixStream::pair().unwrap();
+    let mut rw = ReadWriteTokio::new(inbound, outbound);
+    assert!(poll_once(|cx| rw.poll_read_ready(cx)).await.is_pending());
+
+    inbound_peer.write_all(b"x").await.unwrap();
+    std::future::poll_fn(|cx| rw.poll_read_ready(cx))
+        .await
+        .unwrap();
+    std::future::poll_fn(|cx| rw.poll_write_ready(cx))
+        .await
+        .unwrap();
+    rw.write_all(b"y").await.unwrap();
+    assert_eq!(outbound_peer.read_u8().await.unwrap(), b'y');
+}
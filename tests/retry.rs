@@ -0,0 +1,69 @@
+use std::io::{ErrorKind, Write};
+
+use readwrite::ReadWrite;
+
+/// Writer accepting nothing for the first `stalls` calls, then at most two bytes per call
+struct StallingWriter {
+    stalls: usize,
+    written: Vec<u8>,
+}
+
+impl Write for StallingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.stalls > 0 {
+            self.stalls -= 1;
+            return Ok(0);
+        }
+        let n = buf.len().min(2);
+        self.written.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn stalling(stalls: usize) -> ReadWrite<std::io::Empty, StallingWriter> {
+    ReadWrite::new(
+        std::io::empty(),
+        StallingWriter {
+            stalls,
+            written: Vec::new(),
+        },
+    )
+}
+
+#[test]
+fn zero_write_is_write_zero_error() {
+    let mut rw = stalling(1);
+    let e = rw.write_all(b"data").unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::WriteZero);
+    assert!(rw.borrow_write().written.is_empty());
+}
+
+#[test]
+fn partial_writes_are_continued() {
+    let mut rw = stalling(0);
+    rw.write_all(b"12345").unwrap();
+    assert_eq!(rw.borrow_write().written, b"12345");
+}
+
+#[test]
+fn retry_budget_tolerates_stalls() {
+    let mut rw = stalling(3).write_retry(3);
+    rw.write_all(b"abcde").unwrap();
+    assert_eq!(rw.get_ref().borrow_write().written, b"abcde");
+
+    let mut rw = stalling(2).write_retry(2);
+    assert_eq!(rw.write(b"xyz").unwrap(), 2);
+}
+
+#[test]
+fn retry_budget_exhausted() {
+    let mut rw = stalling(4).write_retry(3);
+    let e = rw.write_all(b"abcde").unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::WriteZero);
+
+    let mut rw = stalling(4).write_retry(3);
+    assert_eq!(rw.write(b"abc").unwrap(), 0);
+}
@@ -0,0 +1,31 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::net::TcpStream;
+
+use readwrite::ReadWrite;
+
+#[test]
+fn clones_of_one_stream_are_the_same() {
+    let (client, server) = common::tcp_pair();
+    let a = ReadWrite::from_stream(client.try_clone().unwrap()).unwrap();
+    let b = ReadWrite::from_stream(client).unwrap();
+    assert!(a.same_reader(&b));
+    assert!(a.same_writer(&b));
+
+    let other: ReadWrite<TcpStream, TcpStream> = ReadWrite::from_stream(server).unwrap();
+    assert!(!a.same_reader(&other));
+    assert!(!a.same_writer(&other));
+}
+
+#[test]
+fn halves_of_from_stream_share_the_socket() {
+    let (client, _server) = common::tcp_pair();
+    let (r, w) = ReadWrite::from_stream(client).unwrap().into_inner();
+    let original = ReadWrite::new(r.try_clone().unwrap(), w.try_clone().unwrap());
+    // The writer half used as a reader is still the same socket
+    let swapped = ReadWrite::new(w, r);
+    assert!(original.same_reader(&swapped));
+    assert!(original.same_writer(&swapped));
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use readwrite::{ReadWrite, SharedReadWrite};
+
+const LEN: usize = 1 << 20;
+
+#[test]
+fn concurrent_read_and_write_over_tcp() {
+    let (client, mut server) = common::tcp_pair();
+    // The peer only starts sending after it has received everything,
+    // so the pending read must not hold off the writer.
+    let peer = std::thread::spawn(move || {
+        let mut received = vec![0; LEN];
+        server.read_exact(&mut received).unwrap();
+        assert!(received.iter().all(|&b| b == 1));
+        server.write_all(&vec![2; LEN]).unwrap();
+    });
+
+    let rw = Arc::new(ReadWrite::from_stream(client).unwrap().shared());
+    let mut reader = rw.read_handle();
+    let reading = std::thread::spawn(move || {
+        let mut received = Vec::new();
+        reader.read_to_end(&mut received).unwrap();
+        received
+    });
+    let writer = rw.clone();
+    let writing = std::thread::spawn(move || {
+        for chunk in vec![1; LEN].chunks(4096) {
+            (&*writer).write_all(chunk).unwrap();
+        }
+    });
+
+    writing.join().unwrap();
+    peer.join().unwrap();
+    let received = reading.join().unwrap();
+    assert_eq!(received.len(), LEN);
+    assert!(received.iter().all(|&b| b == 2));
+}
+
+#[test]
+fn poisoned_lock_is_io_error() {
+    let rw = Arc::new(SharedReadWrite::new(&b"data"[..], Vec::new()));
+    let poisoner = rw.clone();
+    std::thread::spawn(move || {
+        let _guard = poisoner.lock_write().unwrap();
+        panic!("poison the writer lock");
+    })
+    .join()
+    .unwrap_err();
+
+    assert!((&*rw).write(b"x").is_err());
+    let mut buf = [0; 4];
+    (&*rw).read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"data");
+}
+
+#[test]
+fn handles_outlive_and_block_into_inner() {
+    let rw = SharedReadWrite::new(&b"ab"[..], Vec::new());
+    let mut w = rw.write_handle();
+    w.write_all(b"xy").unwrap();
+    let rw = rw.try_into_inner().err().unwrap();
+    drop(w);
+    let (r, w) = rw.try_into_inner().ok().unwrap();
+    assert_eq!(r, b"ab");
+    assert_eq!(w, b"xy");
+}
+
+#[test]
+fn try_into_single_sole_owner() {
+    let rw = SharedReadWrite::from_shared(std::io::Cursor::new(b"single".to_vec()));
+    let mut buf = [0; 3];
+    (&rw).read_exact(&mut buf).unwrap();
+    let cursor = rw.try_into_single().ok().unwrap();
+    assert_eq!(cursor.position(), 3);
+}
+
+#[test]
+fn try_into_single_with_handle_alive() {
+    let rw = SharedReadWrite::from_shared(std::io::Cursor::new(Vec::new()));
+    let handle = rw.read_handle();
+    let rw = rw.try_into_single().err().unwrap();
+    drop(handle);
+    assert!(rw.try_into_single().is_ok());
+
+    // Halves given separately can't be joined into a single stream
+    let rw = SharedReadWrite::new(
+        std::io::Cursor::new(Vec::new()),
+        std::io::Cursor::new(Vec::new()),
+    );
+    assert!(rw.try_into_single().is_err());
+}
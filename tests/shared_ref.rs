@@ -0,0 +1,71 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use readwrite::ReadWrite;
+
+#[test]
+fn shared_reference_tcp_threads() {
+    let (client, mut server) = common::tcp_pair();
+    let rw = Arc::new(ReadWrite::from_stream(client).unwrap());
+
+    let echo = std::thread::spawn(move || {
+        let mut buf = [0; 1000];
+        server.read_exact(&mut buf).unwrap();
+        server.write_all(&buf).unwrap();
+    });
+
+    let reader = {
+        let rw = rw.clone();
+        std::thread::spawn(move || {
+            let mut buf = [0; 1000];
+            (&*rw).read_exact(&mut buf).unwrap();
+            buf
+        })
+    };
+    let writer = {
+        let rw = rw.clone();
+        std::thread::spawn(move || {
+            for chunk in [[1u8; 500], [2u8; 500]] {
+                (&*rw).write_all(&chunk).unwrap();
+            }
+        })
+    };
+
+    writer.join().unwrap();
+    let received = reader.join().unwrap();
+    assert!(received[..500].iter().all(|&b| b == 1));
+    assert!(received[500..].iter().all(|&b| b == 2));
+    echo.join().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn shared_reference_unix_scoped_threads() {
+    use std::os::unix::net::UnixStream;
+
+    let (ours, mut theirs) = UnixStream::pair().unwrap();
+    let rw = ReadWrite::new(ours.try_clone().unwrap(), ours);
+    let rw = &rw;
+
+    std::thread::scope(|s| {
+        // Blocks until the peer answers, which it only does after the write below
+        let reader = s.spawn(move || {
+            let mut buf = [0; 5];
+            let mut r = rw;
+            r.read_exact(&mut buf).unwrap();
+            buf
+        });
+        s.spawn(move || {
+            let mut buf = [0; 4];
+            theirs.read_exact(&mut buf).unwrap();
+            assert_eq!(&buf, b"ping");
+            theirs.write_all(b"pong!").unwrap();
+        });
+        let mut w = rw;
+        w.write_all(b"ping").unwrap();
+        w.flush().unwrap();
+        assert_eq!(&reader.join().unwrap(), b"pong!");
+    });
+}
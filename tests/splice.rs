@@ -0,0 +1,89 @@
+#![cfg(all(feature = "splice", target_os = "linux"))]
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+use readwrite::ReadWrite;
+
+fn pipe() -> (File, File) {
+    let (r, w) = rustix::pipe::pipe().unwrap();
+    (File::from(r), File::from(w))
+}
+
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 + i / 251) as u8).collect()
+}
+
+#[test]
+fn copy_spliced_between_pipes() {
+    let data = pattern(1 << 20);
+    let (src_r, mut src_w) = pipe();
+    let (dst_r, mut dst_w) = pipe();
+
+    let producer = {
+        let data = data.clone();
+        thread::spawn(move || src_w.write_all(&data).unwrap())
+    };
+    let consumer = thread::spawn(move || {
+        let mut received = Vec::new();
+        { dst_r }.read_to_end(&mut received).unwrap();
+        received
+    });
+
+    let mut rw = ReadWrite::new(src_r, std::io::sink());
+    let copied = rw.copy_spliced(&mut dst_w).unwrap();
+    drop(dst_w);
+    producer.join().unwrap();
+
+    assert_eq!(copied, data.len() as u64);
+    assert!(consumer.join().unwrap() == data);
+}
+
+#[test]
+fn splice_to_moves_at_most_len() {
+    let (src_r, mut src_w) = pipe();
+    let (mut dst_r, dst_w) = pipe();
+    src_w.write_all(b"0123456789").unwrap();
+
+    let rw = ReadWrite::new(src_r, std::io::sink());
+    assert_eq!(rw.splice_to(&dst_w, 4).unwrap(), 4);
+    drop(dst_w);
+    let mut moved = Vec::new();
+    dst_r.read_to_end(&mut moved).unwrap();
+    assert_eq!(moved, b"0123");
+
+    drop(src_w);
+    let mut rest = Vec::new();
+    rw.into_inner().0.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"456789");
+}
+
+#[test]
+fn copy_spliced_falls_back_without_pipes() {
+    // Neither end is a pipe, so the kernel refuses to splice
+    let data = pattern(100_000);
+    let (src, mut src_peer) = UnixStream::pair().unwrap();
+    let (mut dst, dst_peer) = UnixStream::pair().unwrap();
+
+    let producer = {
+        let data = data.clone();
+        thread::spawn(move || {
+            src_peer.write_all(&data).unwrap();
+        })
+    };
+    let consumer = thread::spawn(move || {
+        let mut received = Vec::new();
+        { dst_peer }.read_to_end(&mut received).unwrap();
+        received
+    });
+
+    let mut rw = ReadWrite::new(src, std::io::sink());
+    let copied = rw.copy_spliced(&mut dst).unwrap();
+    drop(dst);
+    producer.join().unwrap();
+
+    assert_eq!(copied, data.len() as u64);
+    assert!(consumer.join().unwrap() == data);
+}
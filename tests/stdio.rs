@@ -0,0 +1,97 @@
+//! The test binary re-runs itself as a child process with stdin and stdout redirected to pipes.
+
+use std::io::{BufRead, Read, Write};
+use std::process::{Command, Stdio};
+
+use readwrite::ReadWrite;
+
+const CHILD_ENV: &str = "READWRITE_STDIO_CHILD";
+
+/// Run test `name` of this binary in a child process, feeding it `input` and returning its stdout
+fn run_child(name: &str, input: &[u8]) -> String {
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", name, "--nocapture", "--test-threads=1"])
+        .env(CHILD_ENV, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut rw = ReadWrite::from_child(&mut child).unwrap();
+    rw.write_all(input).unwrap();
+    let (mut r, w) = rw.into_inner();
+    drop(w);
+    let mut out = String::new();
+    r.read_to_string(&mut out).unwrap();
+    assert!(child.wait().unwrap().success());
+    out
+}
+
+#[test]
+fn stdio_child() {
+    if std::env::var_os(CHILD_ENV).is_none() {
+        return;
+    }
+    let mut rw = ReadWrite::stdio();
+    let mut line = String::new();
+    rw.read_line(&mut line).unwrap();
+    write!(rw, "echo: {}", line).unwrap();
+    rw.flush().unwrap();
+}
+
+#[test]
+fn stdio_round_trip() {
+    let out = run_child("stdio_child", b"hello\n");
+    assert!(out.contains("echo: hello\n"), "{:?}", out);
+}
+
+#[test]
+fn stdio_unlocked_child() {
+    if std::env::var_os(CHILD_ENV).is_none() {
+        return;
+    }
+    let mut rw = ReadWrite::stdio_unlocked();
+    let mut buf = [0; 5];
+    rw.read_exact(&mut buf).unwrap();
+    rw.write_all(b"unlocked: ").unwrap();
+    rw.write_all(&buf).unwrap();
+    rw.flush().unwrap();
+}
+
+#[test]
+fn stdio_unlocked_round_trip() {
+    let out = run_child("stdio_unlocked_child", b"12345");
+    assert!(out.contains("unlocked: 12345"), "{:?}", out);
+}
+
+#[cfg(feature = "stdio")]
+mod tokio_stdio {
+    extern crate tokio_dep as tokio;
+
+    use readwrite::ReadWriteTokio;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    #[test]
+    fn stdio_child() {
+        if std::env::var_os(super::CHILD_ENV).is_none() {
+            return;
+        }
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let mut rw = ReadWriteTokio::stdio().map_read(BufReader::new);
+            let mut line = String::new();
+            rw.borrow_mut_read().read_line(&mut line).await.unwrap();
+            rw.write_all(format!("tokio echo: {}", line).as_bytes())
+                .await
+                .unwrap();
+            rw.flush().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn stdio_round_trip() {
+        let out = super::run_child("tokio_stdio::stdio_child", b"hi\n");
+        assert!(out.contains("tokio echo: hi\n"), "{:?}", out);
+    }
+}
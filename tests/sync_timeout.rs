@@ -0,0 +1,51 @@
+mod common;
+
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::time::{Duration, Instant};
+
+use readwrite::{ReadWrite, SetReadTimeout};
+
+#[test]
+fn read_times_out_through_combined_object() {
+    let (client, _server) = common::tcp_pair();
+    let mut rw = ReadWrite::from_stream(client).unwrap();
+    assert_eq!(rw.read_timeout().unwrap(), None);
+
+    let timeout = Duration::from_millis(100);
+    rw.set_read_timeout(Some(timeout)).unwrap();
+    assert!(rw.read_timeout().unwrap().is_some());
+
+    let start = Instant::now();
+    let err = rw.read(&mut [0; 16]).unwrap_err();
+    // Unix reports `WouldBlock`, Windows reports `TimedOut`
+    assert!(matches!(
+        err.kind(),
+        ErrorKind::WouldBlock | ErrorKind::TimedOut
+    ));
+    assert!(start.elapsed() >= Duration::from_millis(90));
+
+    rw.set_read_timeout(None).unwrap();
+    assert_eq!(rw.read_timeout().unwrap(), None);
+}
+
+#[test]
+fn buffered_halves_forward_timeouts() {
+    let (client, mut server) = common::tcp_pair();
+    let mut rw = ReadWrite::new(BufReader::new(&client), BufWriter::new(&client));
+
+    rw.set_write_timeout(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(
+        client.write_timeout().unwrap(),
+        Some(Duration::from_secs(5))
+    );
+    rw.set_read_timeout(Some(Duration::from_millis(50)))
+        .unwrap();
+    // The kernel may round the timeout to its clock granularity
+    assert!(SetReadTimeout::read_timeout(&client).unwrap().is_some());
+
+    server.write_all(b"ok").unwrap();
+    let mut buf = [0; 2];
+    rw.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ok");
+    assert!(rw.read(&mut buf).is_err());
+}
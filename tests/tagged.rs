@@ -0,0 +1,126 @@
+use std::error::Error as StdError;
+use std::io::{Error, ErrorKind, Read, Write};
+
+use readwrite::{Direction, DirectionalError, ReadWrite};
+
+struct Broken(ErrorKind);
+
+impl Read for Broken {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Err(self.0.into())
+    }
+}
+
+impl Write for Broken {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(self.0.into())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Err(self.0.into())
+    }
+}
+
+/// Error of a protocol layer on top of the pseudo-socket, keeping the I/O error as its source
+#[derive(Debug)]
+struct HandshakeError(Error);
+
+impl std::fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("handshake failed")
+    }
+}
+
+impl StdError for HandshakeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[test]
+fn kinds_are_preserved() {
+    let mut rw = ReadWrite::new(
+        Broken(ErrorKind::ConnectionReset),
+        Broken(ErrorKind::BrokenPipe),
+    )
+    .tagged();
+    let read_err = rw.read(&mut [0; 4]).unwrap_err();
+    assert_eq!(read_err.kind(), ErrorKind::ConnectionReset);
+    let write_err = rw.write(b"x").unwrap_err();
+    assert_eq!(write_err.kind(), ErrorKind::BrokenPipe);
+    let flush_err = rw.flush().unwrap_err();
+    assert_eq!(flush_err.kind(), ErrorKind::BrokenPipe);
+
+    let (direction, original) = DirectionalError::find_in(&read_err).unwrap();
+    assert_eq!(direction, Direction::Inbound);
+    assert_eq!(original.kind(), ErrorKind::ConnectionReset);
+    let (direction, _) = DirectionalError::find_in(&flush_err).unwrap();
+    assert_eq!(direction, Direction::Outbound);
+}
+
+#[test]
+fn same_kind_different_halves() {
+    // Both halves fail the same way, only the tag tells them apart
+    let mut rw =
+        ReadWrite::new(Broken(ErrorKind::BrokenPipe), Broken(ErrorKind::BrokenPipe)).tagged();
+    let read_err = rw.read(&mut [0; 4]).unwrap_err();
+    let write_err = rw.write_all(b"x").unwrap_err();
+    assert_eq!(read_err.kind(), write_err.kind());
+    assert_eq!(
+        DirectionalError::find_in(&read_err).unwrap().0,
+        Direction::Inbound
+    );
+    assert_eq!(
+        DirectionalError::find_in(&write_err).unwrap().0,
+        Direction::Outbound
+    );
+}
+
+#[test]
+fn tag_found_through_extra_layers() {
+    let mut rw = ReadWrite::new(std::io::empty(), Broken(ErrorKind::BrokenPipe)).tagged();
+    let err = rw.write(b"x").unwrap_err();
+
+    // A protocol error with the I/O error as its source, boxed into another `io::Error`
+    let layered = Error::other(HandshakeError(err));
+    let (direction, original) = DirectionalError::find_in(&layered).unwrap();
+    assert_eq!(direction, Direction::Outbound);
+    assert_eq!(original.kind(), ErrorKind::BrokenPipe);
+
+    // `io::Error` directly inside `io::Error`
+    let rewrapped = Error::new(ErrorKind::TimedOut, layered);
+    assert_eq!(
+        DirectionalError::find_in(&rewrapped).unwrap().0,
+        Direction::Outbound
+    );
+}
+
+#[test]
+fn untagged_errors_are_not_found() {
+    let plain = Error::other(HandshakeError(ErrorKind::BrokenPipe.into()));
+    assert!(DirectionalError::find_in(&plain).is_none());
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_tagged {
+    extern crate tokio_dep as tokio;
+
+    use std::io::ErrorKind;
+
+    use readwrite::{Direction, DirectionalError, ReadWriteTokio};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn write_errors_tagged() {
+        let (ours, theirs) = tokio::io::duplex(8);
+        drop(theirs);
+        let (r, w) = tokio::io::split(ours);
+        let mut rw = ReadWriteTokio::new(r, w).tagged();
+        assert_eq!(rw.read(&mut [0; 4]).await.unwrap(), 0);
+        let err = rw.write_all(b"data").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+        assert_eq!(
+            DirectionalError::find_in(&err).unwrap().0,
+            Direction::Outbound
+        );
+    }
+}
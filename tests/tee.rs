@@ -0,0 +1,44 @@
+use std::io::Write;
+
+use readwrite::ReadWrite;
+
+#[test]
+fn tee_copies_writes() {
+    let mut rw = ReadWrite::new(std::io::empty(), Vec::new()).tee_write(Vec::new());
+    rw.write_all(b"12345678").unwrap();
+    rw.flush().unwrap();
+    let (primary, tee) = rw.into_writer().into_inner();
+    assert_eq!(primary, b"12345678");
+    assert_eq!(tee, b"12345678");
+}
+
+#[test]
+fn tee_copies_only_accepted_bytes() {
+    // The primary writer accepts at most 3 bytes per write
+    let mut small = [0u8; 3];
+    let mut rw = ReadWrite::new(std::io::empty(), &mut small[..]).tee_write(Vec::new());
+    let n = rw.write(b"12345678").unwrap();
+    assert_eq!(n, 3);
+    assert_eq!(rw.borrow_write().borrow_tee(), b"123");
+}
+
+#[test]
+fn tee_errors() {
+    struct Failing;
+    impl Write for Failing {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::Other.into())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut rw = ReadWrite::new(std::io::empty(), Vec::new()).tee_write(Failing);
+    assert!(rw.write(b"data").is_err());
+    // The primary writer has accepted the data anyway
+    assert_eq!(rw.borrow_write().borrow_primary(), b"data");
+
+    rw.borrow_mut_write().set_ignore_tee_errors(true);
+    assert_eq!(rw.write(b"more").unwrap(), 4);
+}
@@ -0,0 +1,29 @@
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+use readwrite::ReadWrite;
+
+#[test]
+fn throttle_lower_bound() {
+    let payload: Vec<u8> = (0..3000u32).map(|x| x as u8).collect();
+    let mut rw = ReadWrite::new(std::io::empty(), Vec::new()).throttle_write(10_000);
+
+    let start = Instant::now();
+    rw.write_all(&payload).unwrap();
+    let elapsed = start.elapsed();
+
+    // 3000 bytes at 10000 bytes per second, starting with an empty bucket
+    assert!(elapsed >= Duration::from_millis(290), "{:?}", elapsed);
+    assert_eq!(rw.into_inner().into_writer(), payload);
+}
+
+#[test]
+fn throttle_leaves_reads_alone() {
+    let mut rw = ReadWrite::new(&b"unthrottled"[..], Vec::new()).throttle_write(1);
+
+    let start = Instant::now();
+    let mut buf = String::new();
+    rw.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "unthrottled");
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "timeout")]
+
+#[cfg(feature = "tokio")]
+mod tokio_timeout {
+    extern crate tokio_dep as tokio;
+
+    use std::io::ErrorKind;
+    use std::time::Duration;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::time::{sleep, Instant};
+
+    const IDLE: Duration = Duration::from_secs(30);
+
+    #[tokio::test(start_paused = true)]
+    async fn data_arrives_just_in_time() {
+        let (a, mut b) = readwrite::duplex_tokio(64);
+        let mut a = a.with_timeout(Some(IDLE), None);
+        tokio::spawn(async move {
+            sleep(IDLE - Duration::from_millis(1)).await;
+            b.write_all(b"late").await.unwrap();
+            // Keep the peer open until the reader is done
+            sleep(IDLE).await;
+        });
+        let mut buf = [0; 4];
+        a.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"late");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_read_expires() {
+        let (a, _b) = readwrite::duplex_tokio(64);
+        let mut a = a.with_timeout(Some(IDLE), None);
+        a.set_read_timeout(Some(Duration::from_secs(5)));
+        let start = Instant::now();
+        let e = a.read(&mut [0; 4]).await.unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::TimedOut);
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_but_steady_progress_never_times_out() {
+        let (a, mut b) = readwrite::duplex_tokio(64);
+        let mut a = a.with_timeout(Some(IDLE), None);
+        tokio::spawn(async move {
+            for i in 0..10u8 {
+                sleep(IDLE / 2).await;
+                b.write_all(&[i]).await.unwrap();
+            }
+        });
+        let start = Instant::now();
+        let mut buf = [0; 10];
+        a.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert!(start.elapsed() > IDLE * 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stalled_write_expires() {
+        let (a, _b) = readwrite::duplex_tokio(4);
+        let mut a = a.with_timeout(None, Some(Duration::from_secs(10)));
+        let e = a.write_all(b"too much data").await.unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::TimedOut);
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_timeout {
+    use std::io::ErrorKind;
+    use std::task::Poll;
+    use std::time::{Duration, Instant};
+
+    use futures::executor::block_on;
+    use futures::io::AsyncReadExt;
+
+    #[test]
+    fn idle_read_expires() {
+        // Reader which never has data, as it never wakes the task
+        let rw = readwrite::from_poll_fns_asyncstd(
+            |_, _| Poll::Pending,
+            |_, buf| Poll::Ready(Ok(buf.len())),
+        );
+        let mut rw = rw.with_timeout(Some(Duration::from_millis(50)), None);
+        let start = Instant::now();
+        let e = block_on(rw.read(&mut [0; 4])).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::TimedOut);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn ready_data_does_not_time_out() {
+        let rw = readwrite::ReadWriteAsyncstd::new(&b"data"[..], futures::io::sink());
+        let mut rw = rw.with_timeout(Some(Duration::from_millis(1)), None);
+        let mut all = Vec::new();
+        block_on(rw.read_to_end(&mut all)).unwrap();
+        assert_eq!(all, b"data");
+    }
+}
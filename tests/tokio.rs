@@ -0,0 +1,377 @@
+#![cfg(feature = "tokio")]
+
+extern crate tokio_dep as tokio;
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use readwrite::{OnShutdown, ReadWriteTokio};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+/// Writer whose shutdown is pending once, then completes with the configured result
+struct SlowShutdown {
+    polled: bool,
+    fail: bool,
+    done: Arc<AtomicBool>,
+}
+
+impl AsyncWrite for SlowShutdown {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if !self.polled {
+            self.polled = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        if self.fail {
+            return Poll::Ready(Err(std::io::ErrorKind::Other.into()));
+        }
+        self.done.store(true, Ordering::SeqCst);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn teardown_runs_once_after_shutdown() {
+    let done = Arc::new(AtomicBool::new(false));
+    let runs = Arc::new(AtomicUsize::new(0));
+    let w = SlowShutdown {
+        polled: false,
+        fail: false,
+        done: done.clone(),
+    };
+    let mut rw = ReadWriteTokio::new_shutdown_both(tokio::io::empty(), w, {
+        let (done, runs) = (done.clone(), runs.clone());
+        move |_r| {
+            assert!(
+                done.load(Ordering::SeqCst),
+                "teardown before writer shutdown"
+            );
+            runs.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    rw.shutdown().await.unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    rw.shutdown().await.unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn teardown_skipped_on_shutdown_error() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let w = SlowShutdown {
+        polled: false,
+        fail: true,
+        done: Arc::new(AtomicBool::new(false)),
+    };
+    let mut rw = ReadWriteTokio::new_shutdown_both(tokio::io::empty(), w, {
+        let runs = runs.clone();
+        move |_r| {
+            runs.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    rw.shutdown().await.unwrap_err();
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+}
+
+/// Writer recording which write method was called
+#[derive(Default)]
+struct VectoredRecorder {
+    calls: Vec<&'static str>,
+}
+
+impl AsyncWrite for VectoredRecorder {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.calls.push("poll_write");
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        self.calls.push("poll_write_vectored");
+        Poll::Ready(Ok(bufs.iter().map(|b| b.len()).sum()))
+    }
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn vectored_writes_reach_writer() {
+    let mut rw = ReadWriteTokio::new(tokio::io::empty(), VectoredRecorder::default());
+    assert!(rw.is_write_vectored());
+    let bufs = [std::io::IoSlice::new(b"ab"), std::io::IoSlice::new(b"cd")];
+    assert_eq!(rw.write_vectored(&bufs).await.unwrap(), 4);
+    assert_eq!(rw.borrow_write().calls, ["poll_write_vectored"]);
+
+    let plain = SlowShutdown {
+        polled: false,
+        fail: false,
+        done: Arc::new(AtomicBool::new(false)),
+    };
+    let rw = ReadWriteTokio::new(tokio::io::empty(), plain);
+    assert!(!rw.is_write_vectored());
+}
+
+/// Reader recording the shutdown notification
+struct NotifiedReader {
+    notified: Arc<AtomicBool>,
+}
+
+impl AsyncRead for NotifiedReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        _: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl OnShutdown for NotifiedReader {
+    fn poll_on_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.notified.store(true, Ordering::SeqCst);
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Writer asserting that the reader was notified before it is shut down
+struct CheckingWriter {
+    notified: Arc<AtomicBool>,
+    shut_down: bool,
+}
+
+impl AsyncWrite for CheckingWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_shutdown(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        assert!(
+            self.notified.load(Ordering::SeqCst),
+            "reader not notified yet"
+        );
+        self.shut_down = true;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn reader_notified_before_writer_shutdown() {
+    let notified = Arc::new(AtomicBool::new(false));
+    let r = NotifiedReader {
+        notified: notified.clone(),
+    };
+    let w = CheckingWriter {
+        notified: notified.clone(),
+        shut_down: false,
+    };
+    let mut rw = ReadWriteTokio::new(r, w).notify_reader_on_shutdown();
+    rw.shutdown().await.unwrap();
+    assert!(notified.load(Ordering::SeqCst));
+    assert!(rw.borrow_write().shut_down);
+}
+
+#[tokio::test]
+async fn map_combinators_layer_buffers() {
+    use tokio::io::{AsyncBufReadExt, BufReader, BufWriter};
+
+    let (a, mut b) = readwrite::duplex_tokio(64);
+    b.write_all(b"hello\n").await.unwrap();
+    let mut a = a.map_both(BufReader::new, BufWriter::new);
+    let mut line = String::new();
+    a.borrow_mut_read().read_line(&mut line).await.unwrap();
+    assert_eq!(line, "hello\n");
+
+    a.write_all(b"buffered").await.unwrap();
+    assert!(a.borrow_write().buffer().len() == 8);
+    a.flush().await.unwrap();
+    let mut buf = [0; 8];
+    tokio::io::AsyncReadExt::read_exact(&mut b, &mut buf)
+        .await
+        .unwrap();
+    assert_eq!(&buf, b"buffered");
+
+    let a = a
+        .map_read(BufReader::into_inner)
+        .map_write(BufWriter::into_inner);
+    let (_r, _w): (tokio::io::DuplexStream, tokio::io::DuplexStream) = a.into_inner();
+}
+
+#[tokio::test]
+async fn boxed_from_different_transports() {
+    use readwrite::BoxedReadWriteTokio;
+    use tokio::io::AsyncReadExt;
+
+    async fn exchange(rw: &mut BoxedReadWriteTokio<'_>) -> Vec<u8> {
+        rw.write_all(b"ping").await.unwrap();
+        let mut buf = [0; 4];
+        rw.read_exact(&mut buf).await.unwrap();
+        buf.to_vec()
+    }
+
+    let (a, mut b) = readwrite::duplex_tokio(64);
+    b.write_all(b"pong").await.unwrap();
+    assert_eq!(exchange(&mut a.boxed()).await, b"pong");
+
+    let mut other = ReadWriteTokio::new(&b"data"[..], tokio::io::sink()).boxed();
+    assert_eq!(exchange(&mut other).await, b"data");
+}
+
+#[tokio::test]
+async fn duplex_both_directions() {
+    use tokio::io::AsyncReadExt;
+
+    let (mut a, mut b) = readwrite::duplex_tokio(16);
+    a.write_all(b"from a").await.unwrap();
+    let mut buf = [0; 6];
+    b.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"from a");
+
+    b.write_all(b"from b").await.unwrap();
+    a.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"from b");
+
+    // Shutting down one endpoint is seen as EOF by the other only
+    a.shutdown().await.unwrap();
+    assert_eq!(b.read(&mut buf).await.unwrap(), 0);
+    b.write_all(b"still").await.unwrap();
+    a.read_exact(&mut buf[..5]).await.unwrap();
+    assert_eq!(&buf[..5], b"still");
+}
+
+#[tokio::test]
+async fn custom_poll_with_projected_halves() {
+    use tokio::io::AsyncReadExt;
+
+    /// Copy one chunk from the reader to the writer, uppercased, using both pinned halves at once
+    fn poll_upcase_chunk<R: AsyncRead, W: AsyncWrite>(
+        rw: Pin<&mut ReadWriteTokio<R, W>>,
+        cx: &mut Context<'_>,
+    ) -> Poll<std::io::Result<usize>> {
+        let (r, mut w) = rw.project_halves();
+        let mut storage = [0; 16];
+        let mut buf = ReadBuf::new(&mut storage);
+        std::task::ready!(r.poll_read(cx, &mut buf))?;
+        let upper = buf.filled().to_ascii_uppercase();
+        let mut written = 0;
+        while written < upper.len() {
+            written += std::task::ready!(w.as_mut().poll_write(cx, &upper[written..]))?;
+        }
+        Poll::Ready(Ok(written))
+    }
+
+    let (a, mut b) = readwrite::duplex_tokio(64);
+    let mut a = std::pin::pin!(a);
+    b.write_all(b"shout").await.unwrap();
+    let n = std::future::poll_fn(|cx| poll_upcase_chunk(a.as_mut(), cx))
+        .await
+        .unwrap();
+    assert_eq!(n, 5);
+    let mut buf = [0; 5];
+    b.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"SHOUT");
+}
+
+#[cfg(feature = "bytes")]
+#[tokio::test]
+async fn read_buf_into_bytes_mut() {
+    let mut rw = ReadWriteTokio::new(&b"framed payload"[..], tokio::io::sink());
+    let mut buf = bytes::BytesMut::with_capacity(6);
+    buf.extend_from_slice(b">");
+    let n = rw.read_buf(&mut buf).await.unwrap();
+    assert!(n > 0);
+    assert_eq!(buf.len(), 1 + n);
+    assert_eq!(&buf[1..], &b"framed payload"[..n]);
+
+    while rw.read_buf(&mut buf).await.unwrap() > 0 {
+        buf.reserve(4);
+    }
+    assert_eq!(&buf[..], b">framed payload");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn split_async_reads_and_writes_concurrently() {
+    use tokio::io::AsyncReadExt;
+
+    let (a, b) = readwrite::duplex_tokio(64);
+    // The peer echoes everything back
+    let (mut br, mut bw) = b.split_async();
+    let echo = tokio::spawn(async move { tokio::io::copy(&mut br, &mut bw).await });
+
+    let payload: Vec<u8> = (0..20_000u32).map(|x| x as u8).collect();
+    let (mut ar, mut aw) = a.split_async();
+    // The read is in flight before anything is written, and the payload is much larger
+    // than the duplex buffer, so both tasks have to make progress at the same time
+    let len = payload.len();
+    let reader = tokio::spawn(async move {
+        let mut received = vec![0u8; len];
+        ar.read_exact(&mut received).await.unwrap();
+        (ar, received)
+    });
+    tokio::task::yield_now().await;
+    let to_send = payload.clone();
+    let writer = tokio::spawn(async move {
+        aw.write_all(&to_send).await.unwrap();
+        aw
+    });
+
+    let (ar, received) = reader.await.unwrap();
+    let aw = writer.await.unwrap();
+    assert_eq!(received, payload);
+
+    // Reunited halves keep working as one pseudo-socket
+    let mut a = ar.unsplit(aw);
+    a.write_all(b"end").await.unwrap();
+    let mut tail = [0u8; 3];
+    a.read_exact(&mut tail).await.unwrap();
+    assert_eq!(&tail, b"end");
+
+    a.shutdown().await.unwrap();
+    drop(a);
+    echo.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn split_async_halves_drop_cleanly() {
+    let (a, b) = readwrite::duplex_tokio(64);
+    let (ar, mut aw) = a.split_async();
+    drop(ar);
+    aw.write_all(b"still open").await.unwrap();
+    drop(aw);
+    // Both halves are gone, so the peer sees EOF
+    let mut rest = Vec::new();
+    tokio::io::AsyncReadExt::read_to_end(&mut b.into_reader(), &mut rest)
+        .await
+        .unwrap();
+    assert_eq!(rest, b"still open");
+}
@@ -0,0 +1,189 @@
+#![cfg(feature = "tracing")]
+
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::sync::{Arc, Mutex};
+
+use readwrite::ReadWrite;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Event captured by `Collector`: its level and fields formatted with `Debug`
+#[derive(Debug)]
+struct Captured {
+    level: Level,
+    fields: HashMap<String, String>,
+}
+
+impl Captured {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+}
+
+/// Subscriber collecting all events, with their fields
+#[derive(Clone, Default)]
+struct Collector {
+    events: Arc<Mutex<Vec<Captured>>>,
+    spans: Arc<Mutex<Vec<String>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{:?}", value));
+    }
+}
+
+impl Subscriber for Collector {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(span.metadata().name().to_owned());
+        Id::from_u64(spans.len() as u64)
+    }
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = HashMap::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        self.events.lock().unwrap().push(Captured {
+            level: *event.metadata().level(),
+            fields,
+        });
+    }
+    fn enter(&self, _span: &Id) {}
+    fn exit(&self, _span: &Id) {}
+}
+
+struct Failing;
+
+impl Write for Failing {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(ErrorKind::ConnectionReset.into())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn events_carry_byte_counts() {
+    let collector = Collector::default();
+    tracing::subscriber::with_default(collector.clone(), || {
+        let mut rw = ReadWrite::new(&b"hello"[..], Vec::new()).traced("test");
+        let mut buf = [0u8; 3];
+        assert_eq!(rw.read(&mut buf).unwrap(), 3);
+        rw.write_all(b"abcd").unwrap();
+        rw.flush().unwrap();
+    });
+
+    assert_eq!(*collector.spans.lock().unwrap(), ["readwrite"]);
+    let events = collector.events.lock().unwrap();
+    let summary: Vec<_> = events
+        .iter()
+        .map(|e| {
+            (
+                e.level,
+                e.field("direction").unwrap(),
+                e.field("bytes").unwrap(),
+                e.field("result").unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (Level::TRACE, "read", "3", "ok"),
+            (Level::TRACE, "write", "4", "ok"),
+            (Level::TRACE, "flush", "0", "ok"),
+        ]
+    );
+    assert!(events.iter().all(|e| e.field("preview").is_none()));
+}
+
+#[test]
+fn preview_is_escaped_and_truncated() {
+    let collector = Collector::default();
+    tracing::subscriber::with_default(collector.clone(), || {
+        let mut rw = ReadWrite::new(std::io::empty(), Vec::new())
+            .traced("test")
+            .with_preview_len(4);
+        rw.write_all(b"a\nbcdef").unwrap();
+    });
+    let events = collector.events.lock().unwrap();
+    assert_eq!(events[0].field("bytes"), Some("7"));
+    assert_eq!(events[0].field("preview"), Some("a\\nbc"));
+}
+
+#[test]
+fn errors_are_logged_at_debug_with_kind() {
+    let collector = Collector::default();
+    tracing::subscriber::with_default(collector.clone(), || {
+        let mut rw = ReadWrite::new(std::io::empty(), Failing).traced("test");
+        assert!(rw.write(b"data").is_err());
+    });
+    let events = collector.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].level, Level::DEBUG);
+    assert_eq!(events[0].field("direction"), Some("write"));
+    assert_eq!(events[0].field("error"), Some("ConnectionReset"));
+    assert_eq!(events[0].field("result"), Some("error"));
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_traced {
+    extern crate tokio_dep as tokio;
+
+    use readwrite::ReadWriteTokio;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tracing::Level;
+
+    use super::Collector;
+
+    #[test]
+    fn events_carry_byte_counts() {
+        let collector = Collector::default();
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        tracing::subscriber::with_default(collector.clone(), || {
+            rt.block_on(async {
+                let mut rw = ReadWriteTokio::new(&b"hello"[..], Vec::new()).traced("test");
+                let mut data = Vec::new();
+                rw.read_to_end(&mut data).await.unwrap();
+                rw.write_all(b"ab").await.unwrap();
+                rw.shutdown().await.unwrap();
+            })
+        });
+
+        let events = collector.events.lock().unwrap();
+        let summary: Vec<_> = events
+            .iter()
+            .map(|e| {
+                (
+                    e.level,
+                    e.field("direction").unwrap(),
+                    e.field("bytes").unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Level::TRACE, "read", "5"),
+                (Level::TRACE, "read", "0"),
+                (Level::TRACE, "write", "2"),
+                (Level::TRACE, "shutdown", "0"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::io::{Cursor, ErrorKind, Read, Result, Write};
+use std::time::Duration;
+
+use readwrite::{
+    Direction, ReadWrite, ReplayMismatch, Replayer, TranscriptReader, TranscriptRecord,
+    TranscriptWriter,
+};
+
+/// Client logic under test: greets the server, then asks for a number of items and reads them all
+fn client<S: Read + Write>(s: &mut S) -> Result<(Vec<u8>, Vec<u8>)> {
+    s.write_all(b"HELLO\n")?;
+    let mut greeting = [0u8; 6];
+    s.read_exact(&mut greeting)?;
+    s.write_all(b"GET 3\n")?;
+    let mut items = Vec::new();
+    s.read_to_end(&mut items)?;
+    Ok((greeting.to_vec(), items))
+}
+
+fn record_session() -> ((Vec<u8>, Vec<u8>), Vec<u8>) {
+    let server = Cursor::new(b"WORLD\nitem1\nitem2\nitem3\n".to_vec());
+    let mut recorder = ReadWrite::new(server, Vec::new())
+        .record(Vec::new())
+        .unwrap();
+    let result = client(&mut recorder).unwrap();
+    recorder.flush().unwrap();
+    let (rw, transcript) = recorder.into_inner();
+    assert_eq!(rw.into_inner().1, b"HELLO\nGET 3\n");
+    (result, transcript)
+}
+
+#[test]
+fn record_then_replay_gives_same_results() {
+    let (recorded, transcript) = record_session();
+
+    let records = TranscriptReader::new(&transcript[..])
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    let inbound: Vec<u8> = records
+        .iter()
+        .filter(|r| r.direction == Direction::Inbound)
+        .flat_map(|r| r.data.iter().copied())
+        .collect();
+    assert_eq!(inbound, b"WORLD\nitem1\nitem2\nitem3\n");
+    assert!(records.windows(2).all(|w| w[0].offset <= w[1].offset));
+
+    let mut replayer = Replayer::from_transcript(&transcript[..]).unwrap();
+    let replayed = client(&mut replayer).unwrap();
+    assert_eq!(replayed, recorded);
+    assert_eq!(replayer.remaining_inbound(), 0);
+    assert_eq!(replayer.remaining_outbound(), 0);
+}
+
+#[test]
+fn mismatching_write_reports_offset() {
+    let (_, transcript) = record_session();
+    let mut replayer = Replayer::from_transcript(&transcript[..]).unwrap();
+    replayer.write_all(b"HELLO\n").unwrap();
+    let mut greeting = [0u8; 6];
+    replayer.read_exact(&mut greeting).unwrap();
+
+    let err = replayer.write_all(b"GET 4\n").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let mismatch = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<ReplayMismatch>()
+        .unwrap();
+    assert_eq!(
+        *mismatch,
+        ReplayMismatch {
+            offset: 10,
+            expected: Some(b'3'),
+            actual: b'4',
+        }
+    );
+}
+
+#[test]
+fn write_past_the_recording() {
+    let (_, transcript) = record_session();
+    let mut replayer = Replayer::from_transcript(&transcript[..]).unwrap();
+    replayer.write_all(b"HELLO\nGET 3\n").unwrap();
+    let err = replayer.write(b"!").unwrap_err();
+    let mismatch = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<ReplayMismatch>()
+        .unwrap();
+    assert_eq!(mismatch.offset, 12);
+    assert_eq!(mismatch.expected, None);
+}
+
+#[test]
+fn unverified_writes_are_accepted() {
+    let (recorded, transcript) = record_session();
+    let mut replayer = Replayer::from_transcript(&transcript[..]).unwrap();
+    replayer.set_verify_writes(false);
+    replayer.write_all(b"something else entirely\n").unwrap();
+    let mut greeting = [0u8; 6];
+    replayer.read_exact(&mut greeting).unwrap();
+    assert_eq!(greeting.to_vec(), recorded.0);
+}
+
+#[test]
+fn empty_records_are_skipped() {
+    let record = |direction, data: &[u8]| TranscriptRecord {
+        direction,
+        offset: Duration::ZERO,
+        data: data.to_vec(),
+    };
+    let mut replayer = Replayer::new(vec![
+        record(Direction::Inbound, b"ab"),
+        record(Direction::Inbound, b""),
+        record(Direction::Outbound, b""),
+        record(Direction::Inbound, b"cd"),
+    ]);
+    let mut data = Vec::new();
+    replayer.read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"abcd");
+}
+
+#[test]
+fn transcript_format_round_trip() {
+    let mut writer = TranscriptWriter::new(Vec::new()).unwrap();
+    writer
+        .write_record(Direction::Outbound, Duration::from_micros(5), b"out")
+        .unwrap();
+    writer
+        .write_record(Direction::Inbound, Duration::from_micros(7), b"in")
+        .unwrap();
+    let bytes = writer.into_inner();
+    assert_eq!(&bytes[..5], b"RWTR\x01");
+
+    let records = TranscriptReader::new(&bytes[..])
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].direction, Direction::Outbound);
+    assert_eq!(records[0].offset, Duration::from_micros(5));
+    assert_eq!(records[1].data, b"in");
+
+    let err = TranscriptReader::new(&bytes[..bytes.len() - 1])
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(TranscriptReader::new(&b"XXXX\x01"[..]).is_err());
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_transcript {
+    extern crate tokio_dep as tokio;
+
+    use readwrite::{ReadWriteTokio, Replayer};
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+    async fn client<S: AsyncRead + AsyncWrite + Unpin>(s: &mut S) -> std::io::Result<Vec<u8>> {
+        s.write_all(b"PING\n").await?;
+        let mut reply = Vec::new();
+        s.read_to_end(&mut reply).await?;
+        Ok(reply)
+    }
+
+    #[tokio::test]
+    async fn record_then_replay_gives_same_results() {
+        let (ours, mut theirs) = tokio::io::duplex(64);
+        let peer = tokio::spawn(async move {
+            let mut request = [0u8; 5];
+            theirs.read_exact(&mut request).await.unwrap();
+            theirs.write_all(b"PONG\n").await.unwrap();
+            request
+        });
+        let (r, w) = tokio::io::split(ours);
+        let mut recorder = ReadWriteTokio::new(r, w).record(Vec::new()).unwrap();
+        let recorded = client(&mut recorder).await.unwrap();
+        assert_eq!(&peer.await.unwrap(), b"PING\n");
+        assert_eq!(recorded, b"PONG\n");
+
+        let (_, transcript) = recorder.into_inner();
+        let mut replayer = Replayer::from_transcript(&transcript[..]).unwrap();
+        assert_eq!(client(&mut replayer).await.unwrap(), recorded);
+    }
+}
@@ -0,0 +1,52 @@
+#![cfg(any(feature = "tokio", feature = "asyncstd"))]
+
+#[cfg(feature = "tokio")]
+mod tokio_unpin {
+    extern crate tokio_dep as tokio;
+
+    use readwrite::ReadWriteTokio;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+    /// Higher-level combinator requiring `Unpin`, like most `AsyncReadExt`-based helpers
+    async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(s: &mut S) -> [u8; 4] {
+        s.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        s.read_exact(&mut buf).await.unwrap();
+        buf
+    }
+
+    #[tokio::test]
+    async fn unpin_halves_give_unpin_pseudo_socket() {
+        let mut rw = ReadWriteTokio::new(&b"pong"[..], Vec::new());
+        assert_eq!(&exchange(&mut rw).await, b"pong");
+        assert_eq!(rw.borrow_write(), b"ping");
+
+        let (mut a, mut b) = readwrite::duplex_tokio(64);
+        b.write_all(b"pong").await.unwrap();
+        assert_eq!(&exchange(&mut a).await, b"pong");
+    }
+}
+
+#[cfg(feature = "asyncstd")]
+mod asyncstd_unpin {
+    use futures::executor::block_on;
+    use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use readwrite::ReadWriteAsyncstd;
+
+    /// Higher-level combinator requiring `Unpin`, like most `AsyncReadExt`-based helpers
+    async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(s: &mut S) -> [u8; 4] {
+        s.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        s.read_exact(&mut buf).await.unwrap();
+        buf
+    }
+
+    #[test]
+    fn unpin_halves_give_unpin_pseudo_socket() {
+        block_on(async {
+            let mut rw = ReadWriteAsyncstd::new(&b"pong"[..], Vec::new());
+            assert_eq!(&exchange(&mut rw).await, b"pong");
+            assert_eq!(rw.into_writer(), b"ping");
+        });
+    }
+}
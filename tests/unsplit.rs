@@ -0,0 +1,95 @@
+#![cfg(feature = "tokio")]
+
+extern crate tokio_dep as tokio;
+
+use readwrite::ReadWriteTokio;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn split_halves_are_joined_back() {
+    let (ours, mut theirs) = tokio::io::duplex(64);
+    let (r, w) = tokio::io::split(ours);
+    let rw = ReadWriteTokio::new(r, w);
+    let mut joined = rw.unsplit().unwrap();
+
+    joined.write_all(b"ping").await.unwrap();
+    let mut buf = [0; 4];
+    theirs.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+}
+
+#[tokio::test]
+async fn mismatched_split_halves_are_returned() {
+    let (a, _a_peer) = tokio::io::duplex(64);
+    let (b, mut b_peer) = tokio::io::duplex(64);
+    let (a_r, _a_w) = tokio::io::split(a);
+    let (_b_r, b_w) = tokio::io::split(b);
+
+    let err = ReadWriteTokio::new(a_r, b_w).unsplit().unwrap_err();
+    // The halves are still usable
+    let (_r, mut w) = err.into_inner();
+    w.write_all(b"still here").await.unwrap();
+    let mut buf = [0; 10];
+    b_peer.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"still here");
+}
+
+#[cfg(feature = "net")]
+mod tcp {
+    use super::tokio;
+
+    use readwrite::ReadWriteTokio;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    async fn tcp_pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        (client, server)
+    }
+
+    #[tokio::test]
+    async fn owned_halves_reunite() {
+        let (client, mut server) = tcp_pair().await;
+        let peer = client.peer_addr().unwrap();
+        let (r, w) = client.into_split();
+        let mut joined = ReadWriteTokio::new(r, w).unsplit().unwrap();
+        assert_eq!(joined.peer_addr().unwrap(), peer);
+
+        server.write_all(b"hi").await.unwrap();
+        let mut buf = [0; 2];
+        joined.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hi");
+    }
+
+    #[tokio::test]
+    async fn owned_halves_of_different_streams() {
+        let (a, _a_peer) = tcp_pair().await;
+        let (b, _b_peer) = tcp_pair().await;
+        let (a_r, _a_w) = a.into_split();
+        let (_b_r, b_w) = b.into_split();
+        let err = ReadWriteTokio::new(a_r, b_w).unsplit().unwrap_err();
+        let (r, w) = err.into_inner();
+        assert_ne!(r.peer_addr().unwrap(), w.peer_addr().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_owned_halves() {
+        let (a, mut a_peer) = tokio::net::UnixStream::pair().unwrap();
+        let (b, _b_peer) = tokio::net::UnixStream::pair().unwrap();
+        let (a_r, a_w) = a.into_split();
+        let (_b_r, b_w) = b.into_split();
+
+        let err = ReadWriteTokio::new(a_r, b_w).unsplit().unwrap_err();
+        let (a_r, _b_w) = err.into_inner();
+        let mut joined = ReadWriteTokio::new(a_r, a_w).unsplit().unwrap();
+        a_peer.write_all(b"unix").await.unwrap();
+        let mut buf = [0; 4];
+        joined.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"unix");
+    }
+}
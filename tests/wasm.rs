@@ -0,0 +1,54 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+use readwrite::ReadWriteWasm;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Pseudo-socket whose writes come back as reads, through an identity `TransformStream`
+fn loopback() -> ReadWriteWasm {
+    let transform = web_sys::TransformStream::new().unwrap();
+    ReadWriteWasm::new(transform.readable(), transform.writable())
+}
+
+#[wasm_bindgen_test]
+async fn transform_stream_round_trip() {
+    let (mut r, mut w) = loopback().split();
+    let writer = async {
+        // The transform stream applies backpressure until chunks are read
+        for chunk in [&b"hello, "[..], b"web ", b"streams"] {
+            w.write_all(chunk).await.unwrap();
+        }
+        w.close().await.unwrap();
+    };
+    let reader = async {
+        let mut data = Vec::new();
+        r.read_to_end(&mut data).await.unwrap();
+        data
+    };
+    let ((), data) = futures::join!(writer, reader);
+    assert_eq!(data, b"hello, web streams");
+}
+
+#[wasm_bindgen_test]
+async fn chunks_are_buffered_across_small_reads() {
+    let (mut r, mut w) = loopback().split();
+    let writer = async {
+        w.write_all(b"0123456789").await.unwrap();
+        w.close().await.unwrap();
+    };
+    let reader = async {
+        let mut pieces = Vec::new();
+        let mut buf = [0u8; 3];
+        loop {
+            match r.read(&mut buf).await.unwrap() {
+                0 => break,
+                n => pieces.push(buf[..n].to_vec()),
+            }
+        }
+        pieces
+    };
+    let ((), pieces) = futures::join!(writer, reader);
+    assert_eq!(pieces, [&b"012"[..], b"345", b"678", b"9"]);
+}
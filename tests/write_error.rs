@@ -0,0 +1,77 @@
+use std::error::Error as StdError;
+use std::io::{Error, ErrorKind, Write};
+
+use readwrite::{ReadWrite, WriteError};
+
+/// Writer failing every write with the given kind
+struct Failing(ErrorKind);
+
+impl Write for Failing {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(Error::new(self.0, "mock failure"))
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn classified(kind: ErrorKind) -> WriteError {
+    let mut rw = ReadWrite::new(std::io::empty(), Failing(kind));
+    rw.write_classified(b"data").unwrap_err()
+}
+
+/// Messages of the error and all its sources, as printed by error-chain reporters
+fn chain(e: &dyn StdError) -> Vec<String> {
+    let mut messages = vec![e.to_string()];
+    let mut source = e.source();
+    while let Some(s) = source {
+        messages.push(s.to_string());
+        source = s.source();
+    }
+    messages
+}
+
+#[test]
+fn peer_closed_kinds() {
+    for kind in [
+        ErrorKind::BrokenPipe,
+        ErrorKind::ConnectionReset,
+        ErrorKind::ConnectionAborted,
+    ] {
+        let e = classified(kind);
+        assert!(e.is_peer_closed(), "{:?}", kind);
+        assert_eq!(e.into_inner().kind(), kind);
+    }
+}
+
+#[test]
+fn other_kinds() {
+    for kind in [
+        ErrorKind::TimedOut,
+        ErrorKind::PermissionDenied,
+        ErrorKind::WriteZero,
+    ] {
+        let e = classified(kind);
+        assert!(matches!(e, WriteError::Other(_)), "{:?}", kind);
+        assert_eq!(Error::from(e).kind(), kind);
+    }
+}
+
+#[test]
+fn successful_write_passes_through() {
+    let mut rw = ReadWrite::new(std::io::empty(), Vec::new());
+    assert_eq!(rw.write_classified(b"ok").unwrap(), 2);
+    assert_eq!(rw.into_writer(), b"ok");
+}
+
+#[test]
+fn message_is_not_repeated_in_the_chain() {
+    assert_eq!(
+        chain(&classified(ErrorKind::BrokenPipe)),
+        ["peer closed the connection", "mock failure"]
+    );
+    assert_eq!(
+        chain(&classified(ErrorKind::TimedOut)),
+        ["write failed", "mock failure"]
+    );
+}